@@ -0,0 +1,129 @@
+//! Measures how long it takes to build parsing trees: the whole `load_tree` for the vanilla
+//! `commands.json`, and `into_parsing_tree` on its own for a large synthetic tree. Also reports
+//! the memory used by the nodes of the vanilla tree.
+//!
+//! Run with `cargo bench -p dpc-common --bench build_tree` from the workspace root.
+//!
+//! Best times over four runs on the same machine, before and after `into_parsing_tree` stopped
+//! cloning nodes into placeholder slots:
+//!
+//! | benchmark           | before  | after   |
+//! |---------------------|---------|---------|
+//! | `into_parsing_tree` | 6.11 ms | 3.29 ms |
+//! | `load_tree`         | 1.12 ms | 1.05 ms |
+//!
+//! `load_tree` is dominated by importing the JSON, so it barely changes.
+//!
+//! Memory of the vanilla tree, before and after interning literal names: 1847 nodes of 72 bytes
+//! both times, since the symbol fits into the padding of `ParsingNode`, and 1772 bytes of node
+//! names on the heap. The 899 literals are interned as 415 symbols with 4047 bytes of names,
+//! which the interner stores in a single 4 KiB chunk in addition to the nodes.
+
+use std::{
+    collections::HashSet,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use dpc_common::{
+    BuildNodeId, BuildTree, Node, NodeKind, ParsingNode, ParsingTree, SmallString,
+    parse::argument::Argument,
+};
+
+const ITERATIONS: u32 = 200;
+
+fn main() {
+    let commands_json =
+        std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/../../commands.json"))
+            .expect("commands.json must exist in the workspace root");
+
+    bench("load_tree", || {
+        let start = Instant::now();
+        let tree = black_box(dpc_common::load_tree(black_box(&commands_json)));
+        let elapsed = start.elapsed();
+        drop(tree);
+        elapsed
+    });
+
+    report_memory(&dpc_common::load_tree(&commands_json).unwrap());
+
+    bench("into_parsing_tree", || {
+        let build_tree = synthetic_tree();
+        let start = Instant::now();
+        let tree = black_box(build_tree.into_parsing_tree());
+        let elapsed = start.elapsed();
+        drop(tree);
+        elapsed
+    });
+}
+
+fn bench(name: &str, mut run: impl FnMut() -> Duration) {
+    // Warm up
+    run();
+
+    let mut total = Duration::ZERO;
+    let mut best = Duration::MAX;
+    for _ in 0..ITERATIONS {
+        let elapsed = run();
+        total += elapsed;
+        best = best.min(elapsed);
+    }
+
+    println!(
+        "{name}: {:?} mean, {:?} best over {ITERATIONS} iterations",
+        total / ITERATIONS,
+        best,
+    );
+}
+
+fn report_memory(tree: &ParsingTree) {
+    // Names of up to 15 bytes are stored inline, longer ones are allocated on the heap
+    let max_inline_len = size_of::<SmallString>() - 1;
+
+    let nodes: Vec<&Node> = (0..).map_while(|idx| tree.get_node(idx)).collect();
+    let literals: Vec<&str> = nodes
+        .iter()
+        .filter(|node| matches!(node.kind, NodeKind::Literal(_)))
+        .map(|node| node.name())
+        .collect();
+    let heap_bytes: usize = nodes
+        .iter()
+        .map(|node| node.name().len())
+        .filter(|&len| len > max_inline_len)
+        .sum();
+    let distinct_literals = literals.iter().collect::<HashSet<_>>().len();
+    assert_eq!(tree.literals().len(), distinct_literals);
+    let interned_bytes: usize = tree.literals().iter().map(|(_, name)| name.len()).sum();
+
+    println!(
+        "memory: {} nodes of {} bytes, {heap_bytes} bytes of names on the heap, {} literals \
+         interned as {distinct_literals} symbols with {interned_bytes} bytes of names",
+        nodes.len(),
+        size_of::<ParsingNode>(),
+        literals.len(),
+    );
+}
+
+/// Builds a tree with mixed literal and argument siblings on every level, where the leaves
+/// redirect back to their command.
+fn synthetic_tree() -> BuildTree {
+    fn insert_level(tree: &mut BuildTree, parent: BuildNodeId, command: BuildNodeId, depth: u32) {
+        for i in 0..4 {
+            let argument = tree.insert(parent, (format!("arg{i}"), Argument::Bool));
+            let literal = tree.insert(parent, format!("literal{i}"));
+            for node in [argument, literal] {
+                match depth {
+                    0 => tree.redirect(node, command),
+                    _ => insert_level(tree, node, command, depth - 1),
+                }
+            }
+        }
+    }
+
+    let mut tree = BuildTree::default();
+    for i in 0..100 {
+        let command = tree.insert(BuildNodeId::ROOT, format!("command{i}"));
+        insert_level(&mut tree, command, command, 2);
+    }
+    tree
+}
@@ -1,4 +1,6 @@
-use std::{iter, num::NonZeroUsize};
+use std::{mem, num::NonZeroUsize};
+
+use smallvec::SmallVec;
 
 use super::{Node, NodeKind};
 use crate::{
@@ -143,6 +145,56 @@ impl BuildTree {
         };
     }
 
+    /// Unlinks the node from its parent, e.g. to strip commands which are unavailable on a server.
+    /// The node and its descendants stay allocated, but they can no longer be found and are left
+    /// out of the parsing tree.
+    ///
+    /// Nodes which redirect into the removed subtree have to be removed as well, otherwise
+    /// [`into_parsing_tree`](Self::into_parsing_tree) panics.
+    pub fn remove_node(&mut self, node_id: BuildNodeId) {
+        assert_ne!(node_id, BuildNodeId::ROOT, "cannot remove the root node");
+        assert!(node_id.index() < self.nodes.len());
+
+        let next_sibling = self.get_node(node_id).next_sibling;
+
+        // Nodes do not know their parent, so search for the sibling chain containing the node
+        for parent_idx in 0..self.nodes.len() {
+            let BuildNodeNext::Children { first_child, count } = self.nodes[parent_idx].next else {
+                continue;
+            };
+            if count == 0 {
+                continue;
+            }
+
+            if first_child.get() == node_id.index() {
+                let first_child = match next_sibling {
+                    BuildNodeId::INVALID => NonZeroUsize::MAX,
+                    next_sibling => NonZeroUsize::new(next_sibling.index()).unwrap(),
+                };
+                self.nodes[parent_idx].next = BuildNodeNext::Children {
+                    first_child,
+                    count: count - 1,
+                };
+                return;
+            }
+
+            let mut prev_id = BuildNodeId(first_child.get());
+            while prev_id != BuildNodeId::INVALID {
+                if self.get_node(prev_id).next_sibling == node_id {
+                    self.get_node_mut(prev_id).next_sibling = next_sibling;
+                    self.nodes[parent_idx].next = BuildNodeNext::Children {
+                        first_child,
+                        count: count - 1,
+                    };
+                    return;
+                }
+                prev_id = self.get_node(prev_id).next_sibling;
+            }
+        }
+
+        panic!("node is not part of the tree");
+    }
+
     pub fn redirect(&mut self, node_id: BuildNodeId, target: BuildNodeId) {
         // NOTE: cannot redirect to a redirecting node since that would break the parsing tree
         // construction algorithm.
@@ -173,26 +225,38 @@ impl BuildTree {
                 return;
             }
 
-            let start = parsing_nodes.len();
-            let end = start + count;
-
-            parsing_nodes.extend(
-                iter::repeat(ParsingNode {
-                    node: Node::new(NodeKind::Literal(SmallString::default())),
-                    children: 0..0,
-                })
-                .take(count),
-            );
-
+            let mut children = SmallVec::<[BuildNodeId; 16]>::with_capacity(count);
             let mut node_id = BuildNodeId(first_child.get());
-            for i in start..end {
+            for _ in 0..count {
                 assert_ne!(node_id, BuildNodeId::INVALID);
+                children.push(node_id);
+                node_id = build_tree.get_node(node_id).next_sibling;
+            }
+            assert_eq!(node_id, BuildNodeId::INVALID);
 
-                let base = parsing_nodes.len();
-                parsing_nodes[i].node = build_tree.get_node(node_id).node.clone();
+            // Put literal nodes before argument nodes, so they are checked first. The order is
+            // settled before inserting, so that the recorded parsing tree indices stay valid.
+            partition(&mut children, |&node_id| {
+                matches!(build_tree.get_node(node_id).node.kind, NodeKind::Literal(_))
+            });
+
+            // The build tree is consumed, so the nodes can be moved instead of cloned
+            let start = parsing_nodes.len();
+            parsing_nodes.extend(children.iter().enumerate().map(|(i, &node_id)| {
+                let build_node = build_tree.get_node_mut(node_id);
+                build_node.parsing_tree_idx = start + i;
+                ParsingNode {
+                    node: mem::replace(&mut build_node.node, Node::literal(SmallString::default())),
+                    literal: None,
+                    children: 0..0,
+                    redirect: false,
+                }
+            }));
 
+            for (i, &node_id) in children.iter().enumerate() {
                 match build_tree.get_node(node_id).next {
                     BuildNodeNext::Children { first_child, count } => {
+                        let base = parsing_nodes.len();
                         insert_children(
                             build_tree,
                             parsing_nodes,
@@ -200,28 +264,18 @@ impl BuildTree {
                             first_child,
                             count,
                         );
-
-                        parsing_nodes[i].children = base..(base + count);
+                        parsing_nodes[start + i].children = base..(base + count);
                     }
                     BuildNodeNext::Redirect(target) => {
-                        redirected_nodes.push((i, BuildNodeId::new(target)));
+                        redirected_nodes.push((start + i, BuildNodeId::new(target)));
                     }
                 }
-
-                let node = build_tree.get_node_mut(node_id);
-                node.parsing_tree_idx = i;
-                node_id = node.next_sibling;
             }
-
-            assert_eq!(node_id, BuildNodeId::INVALID);
-
-            let nodes = &mut parsing_nodes[start..(start + count)];
-
-            // Put literal nodes before argument nodes, so they are checked first
-            partition(nodes, |node| matches!(node.node.kind, NodeKind::Literal(_)));
         }
 
         let mut parsing_tree = ParsingTree::default();
+        // Apart from the root, cleared and removed nodes, every node ends up in the parsing tree once
+        parsing_tree.nodes.reserve_exact(self.nodes.len() - 1);
         let mut redirected_nodes = Vec::new();
 
         let root_node = self.get_node(BuildNodeId::ROOT);
@@ -241,6 +295,7 @@ impl BuildTree {
         // NOTE: the `redirect` function guarantees that nodes never redirect to already
         // redirecting nodes, therefore the children ranges of the targets should be valid.
         for (parsing_node_idx, target_id) in redirected_nodes {
+            parsing_tree.nodes[parsing_node_idx].redirect = true;
             if target_id == BuildNodeId::ROOT {
                 parsing_tree.nodes[parsing_node_idx].children = 0..count;
             } else {
@@ -251,6 +306,7 @@ impl BuildTree {
             }
         }
 
+        parsing_tree.intern_literals();
         parsing_tree
     }
 }
@@ -277,3 +333,35 @@ fn partition<T>(data: &mut [T], predicate: impl Fn(&T) -> bool) -> usize {
         data.swap(l, r);
     }
 }
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::BuildTree;
+    use crate::parse::argument::ArgumentRegistry;
+
+    fn vanilla_build_tree() -> BuildTree {
+        let mut tree = BuildTree::default();
+        let commands_json = include_str!("../../../commands.json");
+        crate::import::import(commands_json, &mut tree, &ArgumentRegistry::default()).unwrap();
+        tree
+    }
+
+    #[test]
+    fn remove_node() {
+        let num_roots = vanilla_build_tree().into_parsing_tree().num_roots;
+
+        let mut tree = vanilla_build_tree();
+        let execute = tree.find_node_id(["execute"]).unwrap();
+        tree.remove_node(execute);
+        // The namespaced alias redirects to `execute`, so it has to be removed as well
+        let alias = tree.find_node_id(["minecraft:execute"]).unwrap();
+        tree.remove_node(alias);
+        assert_eq!(tree.find_node_id(["execute"]), None);
+        assert_eq!(tree.find_node_id(["execute", "run"]), None);
+        assert!(tree.find_node_id(["say"]).is_some());
+
+        let parsing_tree = tree.into_parsing_tree();
+        assert_eq!(parsing_tree.num_roots, num_roots - 2);
+        assert_eq!(parsing_tree.find_node(["execute"]), None);
+    }
+}
@@ -0,0 +1,92 @@
+//! A binary cache for parsing trees, which avoids rebuilding the tree from `commands.json` on
+//! every start.
+
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, BufWriter},
+    path::Path,
+};
+
+use crate::ParsingTree;
+
+/// Caches written by a different version of this crate are not read, since the layout of the tree
+/// may have changed.
+const CACHE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Loads the parsing tree from the cache if it is newer than the `commands.json` file. Otherwise
+/// the tree is built from `commands.json` and written to the cache.
+///
+/// Failing to read or write the cache is not an error, the tree is rebuilt instead.
+pub fn load_tree_cached(commands_json_path: &Path, cache_path: &Path) -> io::Result<ParsingTree> {
+    if is_up_to_date(commands_json_path, cache_path) {
+        match read_cache(cache_path) {
+            Ok(tree) => return Ok(tree),
+            Err(err) => tracing::warn!(%err, "failed to read parsing tree cache"),
+        }
+    }
+
+    let tree = crate::load_tree(&fs::read_to_string(commands_json_path)?)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    if let Err(err) = write_cache(&tree, cache_path) {
+        tracing::warn!(%err, "failed to write parsing tree cache");
+    }
+    Ok(tree)
+}
+
+/// Reads a parsing tree written by [`write_cache`].
+pub fn read_cache(path: &Path) -> io::Result<ParsingTree> {
+    let (version, mut tree): (String, ParsingTree) =
+        bincode::deserialize_from(BufReader::new(File::open(path)?)).map_err(io::Error::other)?;
+    if version != CACHE_VERSION {
+        return Err(io::Error::other(format!(
+            "cache was written by version {version}"
+        )));
+    }
+    tree.intern_literals();
+    Ok(tree)
+}
+
+/// Writes the parsing tree to a binary cache file.
+///
+/// Fails if the tree contains custom arguments, since they cannot be serialized.
+pub fn write_cache(tree: &ParsingTree, path: &Path) -> io::Result<()> {
+    bincode::serialize_into(BufWriter::new(File::create(path)?), &(CACHE_VERSION, tree))
+        .map_err(io::Error::other)
+}
+
+fn is_up_to_date(commands_json_path: &Path, cache_path: &Path) -> bool {
+    let modified = |path: &Path| fs::metadata(path).and_then(|metadata| metadata.modified());
+    match (modified(commands_json_path), modified(cache_path)) {
+        (Ok(json), Ok(cache)) => cache >= json,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process, sync::Arc};
+
+    use super::{read_cache, write_cache};
+    use crate::test_util;
+
+    #[test]
+    fn cached_tree_parses_like_built_tree() {
+        let built = test_util::tree();
+        let path = env::temp_dir().join(format!("dpc-cache-test-{}.bin", process::id()));
+        write_cache(&built, &path).unwrap();
+        let cached = Arc::new(read_cache(&path).unwrap());
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(format!("{cached:?}"), format!("{built:?}"));
+
+        let source = "execute as @a[tag=foo] at @s run tp @s ~ ~1 ~\n\
+            scoreboard players operation @s obj += #two obj\n\
+            data modify storage foo:bar a set value {x:[1b, 2b]}\n\
+            particle dust{color:[1.0, 0.0, 0.0], scale:1} ~ ~ ~\n\
+            scoreboard player list\n";
+        let from_built = crate::parse_source(built, None, source.to_owned());
+        let from_cached = crate::parse_source(cached, None, source.to_owned());
+        assert_eq!(from_cached.result, from_built.result);
+        assert_eq!(from_cached.diagnostics().len(), 1);
+    }
+}
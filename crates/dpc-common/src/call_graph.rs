@@ -0,0 +1,243 @@
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::{
+    ParsingTree,
+    intern::Interner,
+    parse::{
+        argument::FunctionRef,
+        cst::{self, ArgumentValue, Block, Command},
+    },
+    span::Span,
+};
+
+/// A reference from one function to another, made by a `function` argument.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FunctionCall {
+    /// The span of the `function` argument.
+    pub span: Span,
+    pub target: FunctionRef,
+    /// Whether the call is made within an `execute` command, which decides if and how often the
+    /// function runs.
+    pub conditional: bool,
+    /// Whether the function is run later, e.g. by `schedule function`, instead of immediately by
+    /// the `function` command.
+    pub deferred: bool,
+}
+
+/// Returns all calls to other functions made by the commands of the block, including nested
+/// blocks, in source order.
+pub fn function_calls(block: &Block, tree: &ParsingTree) -> Vec<FunctionCall> {
+    struct CallVisitor<'a> {
+        tree: &'a ParsingTree,
+        in_execute: bool,
+        calls: Vec<FunctionCall>,
+    }
+
+    impl cst::Visitor for CallVisitor<'_> {
+        fn visit_command(&mut self, command: &Command) {
+            let path = command.literal_path(self.tree);
+            let command_name = path
+                .first()
+                .map(|name| name.strip_prefix("minecraft:").unwrap_or(name));
+
+            for arg in &command.args {
+                if let ArgumentValue::Function(target) = arg.value {
+                    self.calls.push(FunctionCall {
+                        span: arg.span,
+                        target,
+                        conditional: self.in_execute,
+                        deferred: command_name != Some("function"),
+                    });
+                }
+            }
+
+            let outer = self.in_execute;
+            self.in_execute |= command_name == Some("execute");
+            cst::walk_command(self, command);
+            self.in_execute = outer;
+        }
+    }
+
+    let mut visitor = CallVisitor {
+        tree,
+        in_execute: false,
+        calls: Vec::new(),
+    };
+    cst::walk_block(&mut visitor, block);
+    visitor.calls
+}
+
+/// The members of function tags, keyed by the id of the tag without the leading `#`, e.g.
+/// `minecraft:load`. Members starting with `#` are nested tags.
+pub type FunctionTags = FxHashMap<String, Vec<String>>;
+
+/// The functions called by each function of a pack, keyed by function id.
+///
+/// Calls to function tags are resolved to the members of the tag, if the tag is known. Calls to
+/// unknown tags are kept as `#namespace:path`.
+#[derive(Debug, Default)]
+pub struct CallGraph {
+    calls: FxHashMap<String, FxHashSet<String>>,
+    tags: FunctionTags,
+}
+
+impl CallGraph {
+    /// The tags whose functions are run by the game itself.
+    pub const ENTRY_POINTS: [&str; 2] = ["#minecraft:load", "#minecraft:tick"];
+
+    /// Builds the call graph of the functions, given as pairs of id and parsed source.
+    pub fn new<'a>(
+        functions: impl IntoIterator<Item = (&'a str, &'a Block)>,
+        tree: &ParsingTree,
+        interner: &impl Interner,
+        tags: FunctionTags,
+    ) -> Self {
+        let mut graph = Self {
+            calls: FxHashMap::default(),
+            tags,
+        };
+
+        for (function, block) in functions {
+            let mut targets = FxHashSet::default();
+            for call in function_calls(block, tree) {
+                let Some(location) = call.target.location.and_then(|loc| loc.resolve(interner))
+                else {
+                    continue;
+                };
+                match call.target.is_tag {
+                    true => graph.resolve_tag(&location, &mut targets),
+                    false => {
+                        targets.insert(location);
+                    }
+                }
+            }
+            graph.calls.insert(function.to_owned(), targets);
+        }
+
+        graph
+    }
+
+    /// Returns the ids of all functions in the graph.
+    pub fn functions(&self) -> impl Iterator<Item = &str> {
+        self.calls.keys().map(String::as_str)
+    }
+
+    /// Returns whether the function is part of the graph.
+    pub fn contains(&self, function: &str) -> bool {
+        self.calls.contains_key(function)
+    }
+
+    /// Returns the functions called by the function, or nothing if the function is unknown.
+    pub fn calls(&self, function: &str) -> impl Iterator<Item = &str> {
+        self.calls
+            .get(function)
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+    }
+
+    /// Returns the function tags the graph was built with.
+    pub fn tags(&self) -> &FunctionTags {
+        &self.tags
+    }
+
+    /// Returns all functions which are run, directly or indirectly, when the roots are run. Roots
+    /// may be functions or `#tags`. The root functions themselves are included.
+    pub fn reachable_from<'a>(
+        &self,
+        roots: impl IntoIterator<Item = &'a str>,
+    ) -> FxHashSet<String> {
+        let mut stack = Vec::new();
+        for root in roots {
+            match root.strip_prefix('#') {
+                Some(tag) => {
+                    let mut members = FxHashSet::default();
+                    self.resolve_tag(tag, &mut members);
+                    stack.extend(members);
+                }
+                None => stack.push(root.to_owned()),
+            }
+        }
+
+        let mut reachable = FxHashSet::default();
+        while let Some(function) = stack.pop() {
+            // Unknown tags remain unresolved, they are not functions
+            if function.starts_with('#') || reachable.contains(&function) {
+                continue;
+            }
+            stack.extend(self.calls(&function).map(str::to_owned));
+            reachable.insert(function);
+        }
+        reachable
+    }
+
+    /// Returns all functions run by the `#minecraft:load` and `#minecraft:tick` tags.
+    pub fn reachable_from_entry_points(&self) -> FxHashSet<String> {
+        self.reachable_from(Self::ENTRY_POINTS)
+    }
+
+    /// Adds the functions of the tag to `functions`, following nested tags.
+    fn resolve_tag(&self, tag: &str, functions: &mut FxHashSet<String>) {
+        let mut visited = FxHashSet::default();
+        let mut stack = vec![tag];
+        while let Some(tag) = stack.pop() {
+            if !visited.insert(tag) {
+                continue;
+            }
+            let Some(members) = self.tags.get(tag) else {
+                functions.insert(format!("#{tag}"));
+                continue;
+            };
+            for member in members {
+                match member.strip_prefix('#') {
+                    Some(nested) => stack.push(nested),
+                    None => {
+                        functions.insert(member.clone());
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::{CallGraph, FunctionTags};
+    use crate::{Compiler, test_util};
+
+    #[test]
+    fn two_function_pack() {
+        let mut compiler = Compiler::new(test_util::tree());
+        let main = compiler.compile_file(
+            "main.mcfunction".into(),
+            "function foo:helper\nfunction #foo:hooks\n".to_owned(),
+        );
+        let helper = compiler.compile_file(
+            "helper.mcfunction".into(),
+            "execute if entity @s run function foo:main\n".to_owned(),
+        );
+        assert!(main.diagnostics.is_empty() && helper.diagnostics.is_empty());
+
+        let mut tags = FunctionTags::default();
+        tags.insert("minecraft:load".to_owned(), vec!["foo:main".to_owned()]);
+        let graph = CallGraph::new(
+            [
+                ("foo:main", main.result.as_ref().unwrap()),
+                ("foo:helper", helper.result.as_ref().unwrap()),
+            ],
+            compiler.tree(),
+            compiler.interner(),
+            tags,
+        );
+
+        let mut main_calls: Vec<_> = graph.calls("foo:main").collect();
+        main_calls.sort_unstable();
+        assert_eq!(main_calls, ["#foo:hooks", "foo:helper"]);
+        assert_eq!(graph.calls("foo:helper").collect::<Vec<_>>(), ["foo:main"]);
+        assert_eq!(graph.calls("foo:unknown").count(), 0);
+
+        let mut reachable: Vec<_> = graph.reachable_from_entry_points().into_iter().collect();
+        reachable.sort_unstable();
+        assert_eq!(reachable, ["foo:helper", "foo:main"]);
+    }
+}
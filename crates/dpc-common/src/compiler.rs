@@ -0,0 +1,213 @@
+use std::{mem, path::PathBuf, sync::Arc};
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+#[cfg(feature = "rayon")]
+use crate::intern::{SymbolRemap, merge_interners};
+use crate::{
+    ParsingTree,
+    diagnostics::Diagnostic,
+    intern::StaticInterner,
+    parse::{ParseContext, ParseOptions, cst, errors::ParseError},
+    parsed::collect_diagnostics,
+    source::SourceFile,
+};
+
+/// Parses the files of a datapack one after another. All files share the parsing tree and a
+/// single interner, so symbols stay valid across files and can be compared between them.
+pub struct Compiler {
+    tree: Arc<ParsingTree>,
+    interner: StaticInterner,
+    pub options: ParseOptions,
+}
+
+/// A file parsed by a [`Compiler`]. Its symbols are resolved with [`Compiler::interner`].
+pub struct CompiledFile {
+    pub source: SourceFile,
+    pub result: Result<cst::Block, ParseError>,
+    /// The diagnostics of all parse errors, sorted by their position.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl Compiler {
+    pub fn new(tree: Arc<ParsingTree>) -> Self {
+        Self {
+            tree,
+            interner: StaticInterner::new(),
+            options: ParseOptions::default(),
+        }
+    }
+
+    pub fn tree(&self) -> &Arc<ParsingTree> {
+        &self.tree
+    }
+
+    /// Returns the interner shared by all files compiled so far.
+    pub fn interner(&self) -> &StaticInterner {
+        &self.interner
+    }
+
+    pub fn interner_mut(&mut self) -> &mut StaticInterner {
+        &mut self.interner
+    }
+
+    pub fn compile_file(&mut self, path: PathBuf, source: String) -> CompiledFile {
+        let (source, result) = self.parse_file(path, source);
+        self.finish_file(source, result)
+    }
+
+    /// Parses the file without collecting its diagnostics, which is done by
+    /// [`finish_file`](Self::finish_file). Both steps together are
+    /// [`compile_file`](Self::compile_file), split up so they can be timed separately.
+    pub fn parse_file(
+        &mut self,
+        path: PathBuf,
+        source: String,
+    ) -> (SourceFile, Result<cst::Block, ParseError>) {
+        let source = SourceFile::new(Some(path), source);
+
+        let mut ctx = ParseContext::new(&source, Arc::clone(&self.tree));
+        ctx.interner = mem::take(&mut self.interner);
+        ctx.options = self.options.clone();
+        let result = ctx.parse();
+        self.interner = mem::take(&mut ctx.interner);
+
+        (source, result)
+    }
+
+    /// Collects the diagnostics of a file parsed by [`parse_file`](Self::parse_file).
+    pub fn finish_file(
+        &self,
+        source: SourceFile,
+        result: Result<cst::Block, ParseError>,
+    ) -> CompiledFile {
+        let diagnostics = collect_diagnostics(&source, &self.tree, &result);
+        CompiledFile {
+            source,
+            result,
+            diagnostics,
+        }
+    }
+}
+
+/// Files parsed by [`compile_files_parallel`], each with its own interner.
+#[cfg(feature = "rayon")]
+pub struct ParallelCompilation {
+    pub files: Vec<CompiledFile>,
+    /// The interners the symbols of the files were created with, in the order of the files.
+    pub interners: Vec<StaticInterner>,
+}
+
+#[cfg(feature = "rayon")]
+impl ParallelCompilation {
+    /// Merges the interners of all files into one. Returns the merged interner along with a
+    /// remap table per file, which translates the symbols of the file into those of the merged
+    /// interner.
+    pub fn merge_interners(&self) -> (StaticInterner, Vec<SymbolRemap>) {
+        merge_interners(&self.interners)
+    }
+}
+
+/// Parses the files in parallel with the default options. The interner cannot be shared between
+/// threads, so every file is parsed with its own interner. Use
+/// [`ParallelCompilation::merge_interners`] to compare symbols across files.
+#[cfg(feature = "rayon")]
+pub fn compile_files_parallel(
+    tree: Arc<ParsingTree>,
+    files: Vec<(PathBuf, String)>,
+) -> ParallelCompilation {
+    let (files, interners) = files
+        .into_par_iter()
+        .map(|(path, source)| {
+            let mut compiler = Compiler::new(Arc::clone(&tree));
+            let file = compiler.compile_file(path, source);
+            (file, compiler.interner)
+        })
+        .unzip();
+    ParallelCompilation { files, interners }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::Compiler;
+    use crate::{
+        intern::Interner,
+        parse::cst::{ArgumentValue, Block},
+        test_util,
+    };
+
+    /// Returns the symbol of the first objective in the block.
+    fn objective(block: &Block) -> crate::intern::Symbol {
+        let mut args = block.all_commands().flat_map(|command| &command.args);
+        args.find_map(|arg| match arg.value {
+            ArgumentValue::Objective(objective) => Some(objective.name),
+            _ => None,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn symbols_are_shared_between_files() {
+        let mut compiler = Compiler::new(test_util::tree());
+        let first = compiler.compile_file(
+            "first.mcfunction".into(),
+            "scoreboard players set @s points 1\n".to_owned(),
+        );
+        let first_symbol = objective(first.result.as_ref().unwrap());
+
+        let (source, result) = compiler.parse_file(
+            "second.mcfunction".into(),
+            "scoreboard players add @a points 5\nsay done\n".to_owned(),
+        );
+        // The symbol of the first file can still be resolved while the second one is processed
+        assert_eq!(compiler.interner().resolve(first_symbol), Some("points"));
+        let second = compiler.finish_file(source, result);
+
+        assert!(first.diagnostics.is_empty());
+        assert!(second.diagnostics.is_empty());
+        assert_eq!(objective(second.result.as_ref().unwrap()), first_symbol);
+        assert_eq!(compiler.interner().resolve(first_symbol), Some("points"));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_compilation_matches_sequential() {
+        use crate::{intern::Interner, parse::sexpr::to_sexpr};
+
+        let files: Vec<_> = [
+            "scoreboard players set @s points 1\nsay hi\n",
+            "scoreboard players add @a kills 2\nscoreboard players add @a points 3\n",
+            "execute as @a run\n    scoreboard players reset @s kills\ntp @s ~ ~1 ~\n",
+        ]
+        .iter()
+        .enumerate()
+        .map(|(idx, text)| (format!("{idx}.mcfunction").into(), text.to_string()))
+        .collect();
+
+        let parallel = super::compile_files_parallel(test_util::tree(), files.clone());
+        let (merged, remaps) = parallel.merge_interners();
+
+        let mut compiler = Compiler::new(test_util::tree());
+        for (idx, (path, text)) in files.into_iter().enumerate() {
+            let sequential = compiler.compile_file(path, text);
+            let file = &parallel.files[idx];
+            let interner = &parallel.interners[idx];
+            assert_eq!(file.source.text(), sequential.source.text());
+            assert_eq!(file.diagnostics.len(), sequential.diagnostics.len());
+
+            let (block, sequential_block) = (
+                file.result.as_ref().unwrap(),
+                sequential.result.as_ref().unwrap(),
+            );
+            assert_eq!(
+                to_sexpr(block, compiler.tree(), interner),
+                to_sexpr(sequential_block, compiler.tree(), compiler.interner())
+            );
+            let symbol = remaps[idx].get(objective(block)).unwrap();
+            assert_eq!(symbol, objective(sequential_block));
+            assert_eq!(merged.resolve(symbol), compiler.interner().resolve(symbol));
+        }
+        assert_eq!(merged.len(), compiler.interner().len());
+    }
+}
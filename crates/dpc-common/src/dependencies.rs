@@ -0,0 +1,190 @@
+//! The resources a pack references without defining them, e.g. for packaging it along with the
+//! packs it depends on.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    ParsingTree,
+    call_graph::{CallGraph, function_calls},
+    intern::Interner,
+    parse::{
+        argument::{ResourceLocation, ResourceRef, Selector, SelectorOption},
+        cst::{self, Block},
+    },
+};
+
+/// The external resources referenced by a pack, grouped by registry, e.g.
+/// `{"minecraft:loot_table": ["foo:chest"]}`. References to tags start with `#`.
+#[derive(Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(transparent))]
+pub struct DependencyManifest {
+    pub registries: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl DependencyManifest {
+    /// Returns the referenced entries of the registry, in order.
+    pub fn get(&self, registry: &str) -> impl Iterator<Item = &str> {
+        self.registries
+            .get(registry)
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+    }
+
+    fn insert(&mut self, registry: &str, location: String, is_tag: bool) {
+        let prefix = if is_tag { "#" } else { "" };
+        self.registries
+            .entry(registry.to_owned())
+            .or_default()
+            .insert(format!("{prefix}{location}"));
+    }
+
+    /// Returns the manifest as pretty-printed JSON.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+}
+
+/// Collects the resources referenced by the functions of a pack, given as their parsed sources,
+/// which the pack does not define itself.
+///
+/// Functions and function tags are looked up in the call graph of the pack. The pack's other
+/// resources are not known, so all other references are assumed to be external. Untyped
+/// `resource_location` arguments are left out, since their registry is unknown.
+pub fn external_dependencies<'a>(
+    blocks: impl IntoIterator<Item = &'a Block>,
+    tree: &ParsingTree,
+    interner: &impl Interner,
+    graph: &CallGraph,
+) -> DependencyManifest {
+    struct ResourceVisitor<'a, I> {
+        interner: &'a I,
+        manifest: DependencyManifest,
+    }
+
+    impl<I: Interner> ResourceVisitor<'_, I> {
+        fn insert(&mut self, registry: &str, location: Option<ResourceLocation>, is_tag: bool) {
+            if let Some(location) = location.and_then(|loc| loc.resolve(self.interner)) {
+                self.manifest.insert(registry, location, is_tag);
+            }
+        }
+    }
+
+    impl<I: Interner> cst::Visitor for ResourceVisitor<'_, I> {
+        fn visit_resource(&mut self, resource: &ResourceRef) {
+            if let Some(registry) = self.interner.resolve(resource.registry) {
+                self.insert(registry, resource.location, resource.is_tag);
+            }
+        }
+
+        fn visit_selector(&mut self, selector: &Selector) {
+            for option in &selector.options {
+                match *option {
+                    SelectorOption::Predicate { id, .. } => {
+                        self.insert("minecraft:predicate", id, false)
+                    }
+                    SelectorOption::Type { is_tag, id, .. } => {
+                        self.insert("minecraft:entity_type", id, is_tag)
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+
+    let mut visitor = ResourceVisitor {
+        interner,
+        manifest: DependencyManifest::default(),
+    };
+    for block in blocks {
+        cst::walk_block(&mut visitor, block);
+
+        for call in function_calls(block, tree) {
+            let Some(target) = call.target.location.and_then(|loc| loc.resolve(interner)) else {
+                continue;
+            };
+            let is_defined = match call.target.is_tag {
+                true => graph.tags().contains_key(&target),
+                false => graph.contains(&target),
+            };
+            if !is_defined {
+                visitor
+                    .manifest
+                    .insert("minecraft:function", target, call.target.is_tag);
+            }
+        }
+    }
+    visitor.manifest
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::{DependencyManifest, external_dependencies};
+    use crate::{Compiler, call_graph::CallGraph, test_util};
+
+    /// Compiles the functions as one pack with the tags and returns its dependencies.
+    fn dependencies(functions: &[(&str, &str)], tags: &[(&str, &str)]) -> DependencyManifest {
+        let mut compiler = Compiler::new(test_util::tree());
+        let files: Vec<_> = functions
+            .iter()
+            .map(|(id, source)| {
+                let file = compiler.compile_file(id.into(), (*source).to_owned());
+                assert!(file.diagnostics.is_empty(), "{:?}", file.diagnostics);
+                (*id, file.result.unwrap())
+            })
+            .collect();
+        let tags = tags
+            .iter()
+            .map(|(tag, member)| ((*tag).to_owned(), vec![(*member).to_owned()]))
+            .collect();
+        let functions = files.iter().map(|(id, block)| (*id, block));
+        let graph = CallGraph::new(functions, compiler.tree(), compiler.interner(), tags);
+        let blocks = files.iter().map(|(_, block)| block);
+        external_dependencies(blocks, compiler.tree(), compiler.interner(), &graph)
+    }
+
+    #[test]
+    fn undefined_resources_are_collected() {
+        let a = "loot give @s loot foo:chests/missing\n\
+                 function foo:b\n\
+                 function foo:gone\n\
+                 function #foo:tick\n\
+                 execute if predicate foo:p run kill @e[predicate=!foo:q,type=#foo:mobs]\n\
+                 loot give @s loot {pools: []}\n";
+        let b = "execute in minecraft:the_nether run function #foo:load\n";
+        let manifest = dependencies(&[("foo:a", a), ("foo:b", b)], &[("foo:load", "foo:a")]);
+
+        let registries: Vec<(&str, Vec<&str>)> = manifest
+            .registries
+            .keys()
+            .map(|registry| (&**registry, manifest.get(registry).collect()))
+            .collect();
+        assert_eq!(
+            registries,
+            [
+                ("minecraft:dimension", vec!["minecraft:the_nether"]),
+                ("minecraft:entity_type", vec!["#foo:mobs"]),
+                ("minecraft:function", vec!["#foo:tick", "foo:gone"]),
+                ("minecraft:loot_table", vec!["foo:chests/missing"]),
+                ("minecraft:predicate", vec!["foo:p", "foo:q"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn pack_without_references() {
+        let manifest = dependencies(&[("foo:a", "say hi\n")], &[]);
+        assert_eq!(manifest, DependencyManifest::default());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn manifest_as_json() {
+        let manifest = dependencies(&[("foo:a", "loot give @s loot foo:missing\n")], &[]);
+        assert_eq!(
+            manifest.to_json(),
+            "{\n  \"minecraft:loot_table\": [\n    \"foo:missing\"\n  ]\n}"
+        );
+    }
+}
@@ -1,10 +1,15 @@
-use std::{borrow::Cow, ops::Range};
+use std::{borrow::Cow, fmt::Write, ops::Range};
 
-use crate::span::Span;
+use crate::{
+    source::{self, FileId},
+    span::Span,
+};
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Diagnostic {
     level: Level,
+    file: Option<FileId>,
     span: Span,
     message: Cow<'static, str>,
     labels: Vec<Label>,
@@ -15,6 +20,7 @@ impl Diagnostic {
     pub fn new(level: Level, span: Span, message: impl Into<Cow<'static, str>>) -> Self {
         Self {
             level,
+            file: None,
             span,
             message: message.into(),
             labels: Vec::new(),
@@ -27,7 +33,13 @@ impl Diagnostic {
     }
 
     pub fn warn(span: Span, message: impl Into<Cow<'static, str>>) -> Self {
-        Self::new(Level::Error, span, message)
+        Self::new(Level::Warn, span, message)
+    }
+
+    /// Sets the file of the diagnostic's span, for diagnostics with labels in several files.
+    pub fn with_file(mut self, file: FileId) -> Self {
+        self.file = Some(file);
+        self
     }
 
     pub fn with_label(mut self, label: Label) -> Self {
@@ -51,6 +63,10 @@ impl Diagnostic {
         self.level
     }
 
+    pub fn file(&self) -> Option<FileId> {
+        self.file
+    }
+
     pub fn span(&self) -> Span {
         self.span
     }
@@ -59,15 +75,32 @@ impl Diagnostic {
         &self.message
     }
 
+    pub fn labels(&self) -> &[Label] {
+        &self.labels
+    }
+
     pub fn sub(&self) -> &[SubDiagnostic] {
         &self.sub_diagnostics
     }
 
+    /// Builds a report for the diagnostic in `source`. Spans which are inverted or out of bounds are
+    /// clamped to the source.
     pub fn to_ariadne_report<'a>(
         &self,
         filename: &'a str,
+        source: &str,
     ) -> ariadne::Report<'static, (&'a str, Range<usize>)> {
-        use ariadne::{Color, Report, ReportKind};
+        self.to_multi_file_ariadne_report(|_| (filename, source))
+    }
+
+    /// Builds a report whose labels may point into different files. The `file` function maps the
+    /// file of each span to its name and text, where `None` is the file the diagnostic was emitted
+    /// for.
+    pub fn to_multi_file_ariadne_report<'a, 's>(
+        &self,
+        file: impl Fn(Option<FileId>) -> (&'a str, &'s str),
+    ) -> ariadne::Report<'static, (&'a str, Range<usize>)> {
+        use ariadne::{Color, Config, IndexType, Report, ReportKind};
 
         let (kind, color) = match self.level {
             Level::Error => (ReportKind::Error, Color::Red),
@@ -76,14 +109,19 @@ impl Diagnostic {
             Level::Help => (ReportKind::Custom("Help", Color::Green), Color::Green),
         };
 
-        let span = (filename, self.span.into());
+        let report_span = |file_id, span| {
+            let (filename, source) = file(file_id);
+            (filename, clamp_to_source(span, source).as_range())
+        };
 
-        let mut report = Report::build(kind, span);
+        // Spans are byte offsets, while ariadne counts characters by default
+        let mut report = Report::build(kind, report_span(self.file, self.span))
+            .with_config(Config::default().with_index_type(IndexType::Byte));
         report.set_message(self.message.clone());
 
         for label in &self.labels {
             report.add_label(
-                ariadne::Label::new((filename, label.span.into()))
+                ariadne::Label::new(report_span(label.file.or(self.file), label.span))
                     .with_message(label.message.clone())
                     .with_color(color),
             );
@@ -93,15 +131,168 @@ impl Diagnostic {
             match sub.level {
                 Level::Info => report.add_note(sub.message.clone()),
                 Level::Help => report.set_help(sub.message.clone()),
-                _ => (),
+                // Ariadne only knows notes and help, so the level is kept as part of the note
+                Level::Warn | Level::Error => {
+                    report.add_note(format!("{}: {}", sub.level.as_str(), sub.message))
+                }
             }
         }
 
         report.finish()
     }
+
+    /// Renders the diagnostic as a GitHub Actions workflow command, so that it is shown as an
+    /// annotation, e.g. `::error file=foo.mcfunction,line=1,col=5::Invalid literal`.
+    pub fn render_github(&self, source: &str, filename: &str) -> String {
+        let command = match self.level {
+            Level::Error => "error",
+            Level::Warn => "warning",
+            Level::Info | Level::Help => "notice",
+        };
+        let (line, column) = line_col(source, clamp_to_source(self.span, source).start);
+        format!(
+            "::{command} file={},line={line},col={column}::{}",
+            escape_github(filename, true),
+            escape_github(&strip_ansi(&self.message), false),
+        )
+    }
+
+    /// Renders the diagnostic without colors in the form `file:line:col: level: message`,
+    /// followed by the affected source lines with the labels underlined. Labels pointing into
+    /// other files are omitted.
+    pub fn render_plain(&self, source: &str, filename: &str) -> String {
+        let mut out = String::new();
+
+        let span = clamp_to_source(self.span, source);
+        let (line, column) = line_col(source, span.start);
+        writeln!(
+            out,
+            "{filename}:{line}:{column}: {}: {}",
+            self.level.as_str(),
+            strip_ansi(&self.message),
+        )
+        .unwrap();
+
+        if self.labels.is_empty() {
+            render_underline(&mut out, source, span, None);
+        }
+        for label in &self.labels {
+            if label.file.is_some_and(|file| Some(file) != self.file) {
+                continue;
+            }
+            let span = clamp_to_source(label.span, source);
+            render_underline(&mut out, source, span, Some(&label.message));
+        }
+
+        for sub in &self.sub_diagnostics {
+            writeln!(
+                out,
+                "  = {}: {}",
+                sub.level.as_str(),
+                strip_ansi(&sub.message)
+            )
+            .unwrap();
+        }
+
+        out
+    }
+}
+
+/// Escapes data for GitHub workflow commands. Property values additionally need `,` and `:`
+/// escaped.
+fn escape_github(string: &str, is_property: bool) -> String {
+    let mut escaped = String::with_capacity(string.len());
+    for chr in string.chars() {
+        match chr {
+            '%' => escaped.push_str("%25"),
+            '\r' => escaped.push_str("%0D"),
+            '\n' => escaped.push_str("%0A"),
+            ':' if is_property => escaped.push_str("%3A"),
+            ',' if is_property => escaped.push_str("%2C"),
+            _ => escaped.push(chr),
+        }
+    }
+    escaped
+}
+
+/// Clamps the span to the source and moves its ends onto character boundaries, so that it can be
+/// used for slicing.
+fn clamp_to_source(span: Span, source: &str) -> Span {
+    let span = span.clamp(source.len());
+    Span::new(
+        source.floor_char_boundary(span.start),
+        source.ceil_char_boundary(span.end),
+    )
+}
+
+/// Returns the 1-based line and column of the byte offset. Columns are counted in characters.
+fn line_col(source: &str, idx: usize) -> (usize, usize) {
+    let (line, column) = source::line_col(source, idx).expect("offset is clamped to the source");
+    (line, column + 1)
+}
+
+fn render_underline(out: &mut String, source: &str, span: Span, message: Option<&str>) {
+    // The text of each line without its line break, and the offset of the next line
+    let mut lines = Vec::new();
+    let mut line_start = 0;
+    for line_end in source::find_line_endings(source) {
+        let text_end =
+            match source[..line_end].ends_with('\r') && source[line_end..].starts_with('\n') {
+                true => line_end - 1,
+                false => line_end,
+            };
+        lines.push((line_start..text_end, line_end + 1));
+        line_start = line_end + 1;
+    }
+    lines.push((line_start..source.len(), source.len() + 1));
+
+    // A line break belongs to the line it ends, so spans ending at it stay on that line
+    let first = lines.partition_point(|(_, next_start)| *next_start <= span.start);
+    let last = lines
+        .partition_point(|(_, next_start)| *next_start < span.end)
+        .clamp(first, lines.len() - 1);
+    let gutter = (last + 1).to_string().len();
+
+    for (index, (line, _)) in lines.iter().enumerate().take(last + 1).skip(first) {
+        let underline_start = span.start.clamp(line.start, line.end);
+        let underline_end = span.end.clamp(underline_start, line.end);
+        let offset = source[line.start..underline_start].chars().count();
+        let width = source[underline_start..underline_end]
+            .chars()
+            .count()
+            .max(1);
+
+        writeln!(out, "{:>gutter$} | {}", index + 1, &source[line.clone()]).unwrap();
+        write!(out, "{:gutter$} | {:offset$}{}", "", "", "^".repeat(width)).unwrap();
+        if index == last
+            && let Some(message) = message
+        {
+            write!(out, " {}", strip_ansi(message)).unwrap();
+        }
+        out.push('\n');
+    }
+}
+
+/// Removes the ANSI color codes which are part of some messages.
+fn strip_ansi(string: &str) -> Cow<'_, str> {
+    if !string.contains('\x1b') {
+        return Cow::Borrowed(string);
+    }
+
+    let mut stripped = String::with_capacity(string.len());
+    let mut chars = string.chars();
+    while let Some(chr) = chars.next() {
+        if chr == '\x1b' {
+            chars.by_ref().find(char::is_ascii_alphabetic);
+        } else {
+            stripped.push(chr);
+        }
+    }
+    Cow::Owned(stripped)
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct SubDiagnostic {
     level: Level,
     message: Cow<'static, str>,
@@ -118,7 +309,9 @@ impl SubDiagnostic {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Label {
+    file: Option<FileId>,
     span: Span,
     message: Cow<'static, str>,
 }
@@ -126,16 +319,287 @@ pub struct Label {
 impl Label {
     pub fn new(span: Span, message: impl Into<Cow<'static, str>>) -> Self {
         Self {
+            file: None,
             span,
             message: message.into(),
         }
     }
+
+    /// Creates a label pointing into a different file than the diagnostic it is attached to.
+    pub fn in_file(file: FileId, span: Span, message: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            file: Some(file),
+            ..Self::new(span, message)
+        }
+    }
+
+    pub fn file(&self) -> Option<FileId> {
+        self.file
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum Level {
     Error,
     Warn,
     Info,
     Help,
 }
+
+impl Level {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warn => "warning",
+            Self::Info => "info",
+            Self::Help => "help",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Diagnostic, Label, Level, strip_ansi};
+    use crate::{
+        source::{FileId, SourceFile, SourceMap},
+        span::Span,
+    };
+
+    const SOURCE: &str = "help foo\nhelp bär\n";
+
+    fn render_ariadne(diagnostic: &Diagnostic) -> String {
+        let mut ariadne = Vec::new();
+        diagnostic
+            .to_ariadne_report("test.mcfunction", SOURCE)
+            .write(
+                ("test.mcfunction", ariadne::Source::from(SOURCE)),
+                &mut ariadne,
+            )
+            .unwrap();
+        String::from_utf8(ariadne).unwrap()
+    }
+
+    fn render_all(diagnostic: &Diagnostic) -> (String, String) {
+        render_ariadne(diagnostic);
+        (
+            diagnostic.render_plain(SOURCE, "test.mcfunction"),
+            diagnostic.render_github(SOURCE, "test.mcfunction"),
+        )
+    }
+
+    #[test]
+    fn inverted_span_is_rendered() {
+        let span = Span::new(7, 5);
+        let diagnostic = Diagnostic::error(span, "Inverted").with_label(Label::new(span, "here"));
+        let (plain, github) = render_all(&diagnostic);
+        assert!(plain.starts_with("test.mcfunction:1:8: error: Inverted\n"));
+        assert_eq!(
+            github,
+            "::error file=test.mcfunction,line=1,col=8::Inverted"
+        );
+    }
+
+    #[test]
+    fn out_of_bounds_span_is_rendered() {
+        let span = Span::new(15, 40);
+        let diagnostic =
+            Diagnostic::warn(span, "Out of bounds").with_label(Label::new(span, "here"));
+        let (plain, github) = render_all(&diagnostic);
+        assert!(plain.starts_with("test.mcfunction:2:7: warning: Out of bounds\n"));
+        assert!(github.starts_with("::warning file=test.mcfunction,line=2,col=7::"));
+
+        let past_end = Diagnostic::error(Span::new(100, 90), "Past the end");
+        let (plain, _) = render_all(&past_end);
+        assert!(plain.starts_with("test.mcfunction:3:1: error: Past the end\n"));
+    }
+
+    #[test]
+    fn warnings_are_reported_as_warnings() {
+        let diagnostic = Diagnostic::warn(Span::new(0, 4), "msg");
+        assert_eq!(diagnostic.level(), Level::Warn);
+        assert!(render_ariadne(&diagnostic).contains("Warning"));
+    }
+
+    #[test]
+    fn sub_diagnostics_of_all_levels_are_rendered() {
+        let span = Span::new(0, 4);
+        let diagnostic = Diagnostic::error(span, "msg")
+            .with_label(Label::new(span, "here"))
+            .with_sub(Level::Error, "an error")
+            .with_sub(Level::Warn, "a warning")
+            .with_sub(Level::Info, "some info")
+            .with_help("some help");
+        let report = render_ariadne(&diagnostic);
+        for message in [
+            "error: an error",
+            "warning: a warning",
+            "some info",
+            "some help",
+        ] {
+            assert!(
+                report.contains(message),
+                "{message:?} is missing in {report}"
+            );
+        }
+    }
+
+    #[test]
+    fn single_line_span_is_rendered_plain() {
+        // `ä` takes two bytes but is underlined by a single caret
+        let span = Span::new(14, 18);
+        let diagnostic =
+            Diagnostic::error(span, "Unknown word").with_label(Label::new(span, "here"));
+        assert_eq!(
+            diagnostic.render_plain(SOURCE, "test.mcfunction"),
+            "\
+test.mcfunction:2:6: error: Unknown word
+2 | help bär
+  |      ^^^ here
+"
+        );
+    }
+
+    #[test]
+    fn multi_line_span_is_rendered_plain() {
+        let expected = "\
+test.mcfunction:1:6: error: Too long
+1 | help foo
+  |      ^^^
+2 | help bär
+  | ^^^^ here
+";
+        for source in [
+            "help foo\nhelp bär\n",
+            "help foo\r\nhelp bär\r\n",
+            "help foo\rhelp bär\r",
+        ] {
+            let end = source.find(" bär").unwrap();
+            let span = Span::new(5, end);
+            let diagnostic =
+                Diagnostic::error(span, "Too long").with_label(Label::new(span, "here"));
+            assert_eq!(
+                diagnostic.render_plain(source, "test.mcfunction"),
+                expected,
+                "{source:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn span_ending_at_line_break_stays_on_its_line() {
+        let source = "say a\r\nsay b";
+        let span = Span::new(4, 7);
+        let diagnostic = Diagnostic::error(span, "msg").with_label(Label::new(span, "here"));
+        assert_eq!(
+            diagnostic.render_plain(source, "test.mcfunction"),
+            "\
+test.mcfunction:1:5: error: msg
+1 | say a
+  |     ^ here
+"
+        );
+    }
+
+    #[test]
+    fn multi_file_report_has_labels_in_both_files() {
+        let mut sources = SourceMap::default();
+        let caller = sources.add_file(SourceFile::new(
+            Some("a.mcfunction".into()),
+            "function foo:b\n".to_owned(),
+        ));
+        let callee = sources.add_file(SourceFile::new(
+            Some("b.mcfunction".into()),
+            "say b\nfunction foo:a\n".to_owned(),
+        ));
+
+        let diagnostic = Diagnostic::error(Span::new(0, 14), "Recursive function call")
+            .with_file(caller)
+            .with_label(Label::new(Span::new(9, 14), "calls foo:b"))
+            .with_label(Label::in_file(
+                callee,
+                Span::new(15, 20),
+                "which calls foo:a",
+            ));
+        let name = |file: FileId| match file == caller {
+            true => "a.mcfunction",
+            false => "b.mcfunction",
+        };
+        let report = diagnostic.to_multi_file_ariadne_report(|file| {
+            let file = file.expect("diagnostic has a file");
+            (name(file), sources.get(file).unwrap().text())
+        });
+
+        let mut output = Vec::new();
+        let texts = sources
+            .files()
+            .map(|(id, file)| (name(id), file.text().to_owned()));
+        report.write(ariadne::sources(texts), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let output = strip_ansi(&output);
+
+        for expected in [
+            "a.mcfunction:1:1",
+            "b.mcfunction:2:",
+            "function foo:b",
+            "function foo:a",
+            "calls foo:b",
+            "which calls foo:a",
+        ] {
+            assert!(
+                output.contains(expected),
+                "{expected:?} is missing in {output}"
+            );
+        }
+    }
+
+    #[test]
+    fn out_of_range_spans_are_clamped_in_ariadne_reports() {
+        // The first label starts inside `ä`, the second one lies past the end of the source
+        let diagnostic = Diagnostic::error(Span::new(100, 120), "Out of range")
+            .with_label(Label::new(Span::new(16, 40), "inside"))
+            .with_label(Label::new(Span::new(100, 120), "past the end"));
+        let report = render_ariadne(&diagnostic);
+        let report = strip_ansi(&report);
+        assert!(report.contains("test.mcfunction:2:"), "{report}");
+        for expected in ["help bär", "inside", "past the end"] {
+            assert!(
+                report.contains(expected),
+                "{expected:?} is missing in {report}"
+            );
+        }
+    }
+
+    #[test]
+    fn github_annotations() {
+        let diagnostic = Diagnostic::error(Span::new(14, 18), "Unknown word");
+        assert_eq!(
+            diagnostic.render_github(SOURCE, "data/foo/function/bar.mcfunction"),
+            "::error file=data/foo/function/bar.mcfunction,line=2,col=6::Unknown word"
+        );
+
+        let info = Diagnostic::new(Level::Info, Span::new(0, 4), "Note");
+        assert_eq!(
+            info.render_github(SOURCE, "a.mcfunction"),
+            "::notice file=a.mcfunction,line=1,col=1::Note"
+        );
+    }
+
+    #[test]
+    fn github_annotations_are_escaped() {
+        let diagnostic = Diagnostic::warn(Span::new(0, 4), "100% wrong:\nsee, here\r");
+        assert_eq!(
+            diagnostic.render_github(SOURCE, "a,b:c%.mcfunction"),
+            "::warning file=a%2Cb%3Ac%25.mcfunction,line=1,col=1::100%25 wrong:%0Asee, here%0D"
+        );
+    }
+}
@@ -0,0 +1,210 @@
+use serde::Deserialize;
+use serde_json::{Value, value::RawValue};
+
+use crate::{
+    call_graph::FunctionTags,
+    diagnostics::{Diagnostic, Label},
+    parse::argument::split_resource_location,
+    span::Span,
+};
+
+/// The contents of a function tag file, e.g. `data/minecraft/tags/function/load.json`.
+#[derive(Debug, Default, PartialEq)]
+pub struct FunctionTag {
+    /// Whether the tag replaces the values of tags with the same id from lower priority packs,
+    /// instead of adding to them.
+    pub replace: bool,
+    pub values: Vec<FunctionTagEntry>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct FunctionTagEntry {
+    /// The id of the function or tag, always including the namespace, without a leading `#`.
+    pub id: String,
+    pub is_tag: bool,
+    /// Whether loading the tag fails if the entry does not exist.
+    pub required: bool,
+    /// The span of the entry in the JSON source.
+    pub span: Span,
+}
+
+impl FunctionTag {
+    /// Returns the values in the form used by [`FunctionTags`], with tags prefixed by `#`.
+    pub fn members(&self) -> Vec<String> {
+        self.values
+            .iter()
+            .map(|entry| match entry.is_tag {
+                true => format!("#{}", entry.id),
+                false => entry.id.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Collects the members of the parsed tags, given as pairs of tag id and tag, for building a
+/// [`CallGraph`](crate::call_graph::CallGraph). Tags with the same id are merged, unless the later
+/// one replaces the earlier one.
+pub fn collect_function_tags<'a>(
+    tags: impl IntoIterator<Item = (&'a str, &'a FunctionTag)>,
+) -> FunctionTags {
+    let mut function_tags = FunctionTags::default();
+    for (id, tag) in tags {
+        let members = function_tags.entry(id.to_owned()).or_default();
+        if tag.replace {
+            members.clear();
+        }
+        members.extend(tag.members());
+    }
+    function_tags
+}
+
+#[derive(Deserialize)]
+struct JsonFunctionTag<'a> {
+    #[serde(default)]
+    replace: bool,
+    #[serde(borrow)]
+    values: Vec<&'a RawValue>,
+}
+
+/// Parses a function tag file. Malformed entries are reported and left out of the tag. If the
+/// file itself is malformed, an empty tag is returned along with the error.
+pub fn parse_function_tag(json: &str) -> (FunctionTag, Vec<Diagnostic>) {
+    let tag: JsonFunctionTag = match serde_json::from_str(json) {
+        Ok(tag) => tag,
+        Err(err) => {
+            let offset = offset_of(json, err.line(), err.column());
+            let span = Span::new(offset, json.ceil_char_boundary(offset + 1));
+            let diagnostic = Diagnostic::error(span, "Invalid function tag")
+                .with_label(Label::new(span, err.to_string()));
+            return (FunctionTag::default(), vec![diagnostic]);
+        }
+    };
+
+    let mut diagnostics = Vec::new();
+    let mut values = Vec::new();
+    for raw in tag.values {
+        // Raw values borrow from the source, so their position is known
+        let start = raw.get().as_ptr() as usize - json.as_ptr() as usize;
+        let span = Span::new(start, start + raw.get().len());
+
+        let (id, required) = match serde_json::from_str(raw.get()) {
+            Ok(Value::String(id)) => (id, true),
+            Ok(Value::Object(object)) => match (object.get("id"), object.get("required")) {
+                (Some(Value::String(id)), None) => (id.clone(), true),
+                (Some(Value::String(id)), Some(Value::Bool(required))) => (id.clone(), *required),
+                _ => {
+                    diagnostics.push(invalid_entry(
+                        span,
+                        "Expected a string `id` and an optional boolean `required`",
+                    ));
+                    continue;
+                }
+            },
+            _ => {
+                diagnostics.push(invalid_entry(
+                    span,
+                    "Expected a function id, a `#tag` or an object with an `id`",
+                ));
+                continue;
+            }
+        };
+
+        let (is_tag, location) = match id.strip_prefix('#') {
+            Some(location) => (true, location),
+            None => (false, id.as_str()),
+        };
+        let Some((namespace, path)) = split_resource_location(location) else {
+            diagnostics.push(invalid_entry(span, "Invalid resource location"));
+            continue;
+        };
+
+        values.push(FunctionTagEntry {
+            id: format!("{namespace}:{path}"),
+            is_tag,
+            required,
+            span,
+        });
+    }
+
+    let tag = FunctionTag {
+        replace: tag.replace,
+        values,
+    };
+    (tag, diagnostics)
+}
+
+fn invalid_entry(span: Span, message: &'static str) -> Diagnostic {
+    Diagnostic::error(span, "Invalid function tag entry").with_label(Label::new(span, message))
+}
+
+/// Converts the 1-based line and column of a JSON error into a byte offset.
+fn offset_of(json: &str, line: usize, column: usize) -> usize {
+    let line_start: usize = json
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum();
+    json[line_start..]
+        .char_indices()
+        .nth(column.saturating_sub(1))
+        .map_or(json.len(), |(i, _)| line_start + i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FunctionTagEntry, collect_function_tags, parse_function_tag};
+    use crate::span::Span;
+
+    #[test]
+    fn nested_tag_reference() {
+        let json = r##"{"values": ["foo:init", "#foo:setup", {"id": "bar", "required": false}]}"##;
+        let (tag, diagnostics) = parse_function_tag(json);
+        assert!(diagnostics.is_empty());
+        assert!(!tag.replace);
+        assert_eq!(
+            tag.values,
+            [
+                FunctionTagEntry {
+                    id: "foo:init".to_owned(),
+                    is_tag: false,
+                    required: true,
+                    span: Span::new(12, 22),
+                },
+                FunctionTagEntry {
+                    id: "foo:setup".to_owned(),
+                    is_tag: true,
+                    required: true,
+                    span: Span::new(24, 36),
+                },
+                FunctionTagEntry {
+                    id: "minecraft:bar".to_owned(),
+                    is_tag: false,
+                    required: false,
+                    span: Span::new(38, 70),
+                },
+            ]
+        );
+
+        let tags = collect_function_tags([("minecraft:load", &tag)]);
+        assert_eq!(
+            tags["minecraft:load"],
+            ["foo:init", "#foo:setup", "minecraft:bar"]
+        );
+    }
+
+    #[test]
+    fn malformed_entries_are_reported() {
+        let json = r##"{"values": ["foo:ok", 1, "#Bad Tag", {"required": true}]}"##;
+        let (tag, diagnostics) = parse_function_tag(json);
+        assert_eq!(tag.members(), ["foo:ok"]);
+        let spans: Vec<_> = diagnostics.iter().map(|d| d.span()).collect();
+        assert_eq!(
+            spans,
+            [Span::new(22, 23), Span::new(25, 35), Span::new(37, 55)]
+        );
+
+        let (tag, diagnostics) = parse_function_tag("{\"values\": [}");
+        assert_eq!(tag.values, []);
+        assert_eq!(diagnostics.len(), 1);
+    }
+}
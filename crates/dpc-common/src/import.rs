@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt};
 
 use rustc_hash::FxHashMap;
 use serde::Deserialize;
@@ -7,7 +7,7 @@ use serde_json::Value;
 use crate::{
     BuildTree, Node,
     build_tree::BuildNodeId,
-    parse::argument::{Argument, StringKind},
+    parse::argument::{Argument, ArgumentRegistry, StringKind},
 };
 
 #[derive(Deserialize)]
@@ -34,11 +34,66 @@ struct JsonNode {
     redirect: Vec<String>,
 }
 
-pub fn import(json: &str, tree: &mut BuildTree) {
-    let node: JsonNode = serde_json::from_str(json).unwrap();
+/// An error while importing a `commands.json` file, usually caused by a file from a newer
+/// Minecraft version than this crate supports.
+#[derive(Debug)]
+pub enum ImportError {
+    /// The file is not valid JSON or does not describe a command tree.
+    Json(serde_json::Error),
+    /// The top-level node is not an unexecutable root node, or a root node is nested.
+    InvalidRoot,
+    /// An argument uses a parser which is neither built in nor registered.
+    UnknownParser(String),
+    /// A resource argument lacks its `registry` property.
+    MissingRegistry { parser: String },
+    /// A property of an argument has the wrong type or an unknown value.
+    InvalidProperty { parser: String, property: String },
+    /// A redirect points to a node which does not exist.
+    UnknownRedirectTarget(Vec<String>),
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json(err) => write!(f, "invalid command tree: {err}"),
+            Self::InvalidRoot => f.write_str("invalid root node"),
+            Self::UnknownParser(parser) => write!(f, "unknown parser: {parser}"),
+            Self::MissingRegistry { parser } => {
+                write!(f, "missing registry property for {parser} parser")
+            }
+            Self::InvalidProperty { parser, property } => {
+                write!(f, "invalid {property} property for {parser} parser")
+            }
+            Self::UnknownRedirectTarget(path) => write!(f, "unknown redirect target: {path:?}"),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Json(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for ImportError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Json(err)
+    }
+}
 
-    assert!(matches!(node.kind, JsonNodeKind::Root));
-    assert!(!node.executable);
+pub fn import(
+    json: &str,
+    tree: &mut BuildTree,
+    registry: &ArgumentRegistry,
+) -> Result<(), ImportError> {
+    let node: JsonNode = serde_json::from_str(json)?;
+
+    if !matches!(node.kind, JsonNodeKind::Root) || node.executable {
+        return Err(ImportError::InvalidRoot);
+    }
 
     let mut stack: Vec<(BuildNodeId, &JsonNode)> = Vec::new();
     stack.push((BuildNodeId::ROOT, &node));
@@ -48,10 +103,13 @@ pub fn import(json: &str, tree: &mut BuildTree) {
     while let Some((parent_id, parent)) = stack.pop() {
         for (child_name, child) in &parent.children {
             let mut node = match &child.kind {
-                JsonNodeKind::Root => panic!("encountered root node as child of another node"),
+                JsonNodeKind::Root => return Err(ImportError::InvalidRoot),
                 JsonNodeKind::Literal => Node::literal(child_name.as_str()),
                 JsonNodeKind::Argument { parser, properties } => {
-                    let param = construct_param(parser.as_str(), properties);
+                    let param = match registry.construct(parser, properties) {
+                        Some(param) => param,
+                        None => construct_param(parser, properties)?,
+                    };
                     Node::argument(child_name.as_str(), param)
                 }
             };
@@ -62,93 +120,131 @@ pub fn import(json: &str, tree: &mut BuildTree) {
 
             let id = tree.insert(parent_id, node);
 
-            if !child.redirect.is_empty() {
+            // A node may both be executable and redirect, in which case the command can end at
+            // the node or continue with the children of the target. Like brigadier, the children
+            // of redirecting nodes are ignored, the redirect replaces them.
+            if child.redirect.is_empty() {
+                stack.push((id, child));
+            } else {
                 redirects.push((id, child.redirect.as_slice()));
             }
-
-            stack.push((id, child));
         }
     }
 
     for (source, target_path) in redirects {
         let target = tree
             .find_node_id(target_path)
-            .unwrap_or_else(|| panic!("unknown redirect target: {target_path:?}"));
+            .ok_or_else(|| ImportError::UnknownRedirectTarget(target_path.to_vec()))?;
         tree.redirect(source, target);
     }
+
+    Ok(())
 }
 
-fn construct_param(parser: &str, properties: &HashMap<String, Value>) -> Argument {
+fn construct_param(
+    parser: &str,
+    properties: &HashMap<String, Value>,
+) -> Result<Argument, ImportError> {
+    /// Returns the property converted by `f`, or `None` if it is missing.
+    fn get_property<T>(
+        parser: &str,
+        properties: &HashMap<String, Value>,
+        property: &str,
+        f: impl FnOnce(&Value) -> Option<T>,
+    ) -> Result<Option<T>, ImportError> {
+        let Some(value) = properties.get(property) else {
+            return Ok(None);
+        };
+        match f(value) {
+            Some(value) => Ok(Some(value)),
+            None => Err(ImportError::InvalidProperty {
+                parser: parser.to_owned(),
+                property: property.to_owned(),
+            }),
+        }
+    }
+
     fn get_min_max<T>(
+        parser: &str,
         properties: &HashMap<String, Value>,
         f: fn(&Value) -> Option<T>,
         default_min: T,
         default_max: T,
-    ) -> (T, T) {
-        let min = properties
-            .get("min")
-            .map(|v| f(v).unwrap())
-            .unwrap_or(default_min);
-        let max = properties
-            .get("max")
-            .map(|v| f(v).unwrap())
-            .unwrap_or(default_max);
-        (min, max)
-    }
-
-    fn get_registry(properties: &HashMap<String, Value>) -> Box<str> {
-        properties
-            .get("registry")
-            .expect("missing registry property")
-            .as_str()
-            .expect("registry must be a string")
-            .into()
-    }
-
-    match parser {
+    ) -> Result<(T, T), ImportError> {
+        let min = get_property(parser, properties, "min", f)?.unwrap_or(default_min);
+        let max = get_property(parser, properties, "max", f)?.unwrap_or(default_max);
+        Ok((min, max))
+    }
+
+    fn get_registry(
+        parser: &str,
+        properties: &HashMap<String, Value>,
+    ) -> Result<Box<str>, ImportError> {
+        get_property(parser, properties, "registry", |v| {
+            v.as_str().map(Box::from)
+        })?
+        .ok_or_else(|| ImportError::MissingRegistry {
+            parser: parser.to_owned(),
+        })
+    }
+
+    /// Returns the value of a string property, which must be one of `values`. The first value is
+    /// the default.
+    fn get_choice(
+        parser: &str,
+        properties: &HashMap<String, Value>,
+        property: &str,
+        values: &[&str],
+    ) -> Result<usize, ImportError> {
+        let choice = get_property(parser, properties, property, |v| {
+            values.iter().position(|value| Some(*value) == v.as_str())
+        })?;
+        Ok(choice.unwrap_or(0))
+    }
+
+    let argument = match parser {
         "brigadier:bool" => Argument::Bool,
         "brigadier:double" => {
-            let (min, max) = get_min_max(properties, Value::as_f64, f64::MIN, f64::MAX);
+            let (min, max) = get_min_max(parser, properties, Value::as_f64, f64::MIN, f64::MAX)?;
             Argument::Double { min, max }
         }
         "brigadier:float" => {
             let mapper = |v: &Value| v.as_f64().map(|num| num as f32);
-            let (min, max) = get_min_max(properties, mapper, f32::MIN, f32::MAX);
+            let (min, max) = get_min_max(parser, properties, mapper, f32::MIN, f32::MAX)?;
             Argument::Float { min, max }
         }
         "brigadier:integer" => {
             let mapper = |v: &Value| v.as_i64().map(|num| num as i32);
-            let (min, max) = get_min_max(properties, mapper, i32::MIN, i32::MAX);
+            let (min, max) = get_min_max(parser, properties, mapper, i32::MIN, i32::MAX)?;
             Argument::Integer { min, max }
         }
         "brigadier:string" => {
-            let kind = match properties.get("type").map(|v| v.as_str().unwrap()) {
-                None | Some("word") => StringKind::SingleWord,
-                Some("phrase") => StringKind::QuotablePhrase,
-                Some("greedy") => StringKind::GreedyPhrase,
-                invalid => panic!("invalid type for minecraft:string parser: {invalid:?}"),
+            let kind = match get_choice(parser, properties, "type", &["word", "phrase", "greedy"])?
+            {
+                0 => StringKind::SingleWord,
+                1 => StringKind::QuotablePhrase,
+                _ => StringKind::GreedyPhrase,
             };
             Argument::String(kind)
         }
         "minecraft:angle" => Argument::Angle,
-        "minecraft:block_pos" => Argument::BlockPos,
+        "minecraft:block_pos" => {
+            let (min, max) = get_min_max(parser, properties, Value::as_f64, f64::MIN, f64::MAX)?;
+            Argument::BlockPos { min, max }
+        }
         "minecraft:block_predicate" => Argument::BlockPredicate,
         "minecraft:block_state" => Argument::BlockState,
         "minecraft:color" => Argument::Color,
-        "minecraft:column_pos" => Argument::ColumnPos,
+        "minecraft:column_pos" => {
+            let (min, max) = get_min_max(parser, properties, Value::as_f64, f64::MIN, f64::MAX)?;
+            Argument::ColumnPos { min, max }
+        }
         "minecraft:component" => Argument::Component,
         "minecraft:dimension" => Argument::Dimension,
         "minecraft:entity" => {
-            let single = match properties.get("amount").map(|v| v.as_str().unwrap()) {
-                None | Some("multiple") => false,
-                Some("single") => true,
-                invalid => panic!("invalid amount for minecraft:entity parser: {invalid:?}"),
-            };
-            let players_only = match properties.get("type").map(|v| v.as_str().unwrap()) {
-                None | Some("entities") => false,
-                Some("players") => true,
-                invalid => panic!("invalid type for minecraft:entity parser: {invalid:?}"),
-            };
+            let single = get_choice(parser, properties, "amount", &["multiple", "single"])? == 1;
+            let players_only =
+                get_choice(parser, properties, "type", &["entities", "players"])? == 1;
             Argument::Entity {
                 single,
                 players_only,
@@ -176,25 +272,21 @@ fn construct_param(parser: &str, properties: &HashMap<String, Value>) -> Argumen
         "minecraft:operation" => Argument::Operation,
         "minecraft:particle" => Argument::Particle,
         "minecraft:resource" => Argument::Resource {
-            registry: get_registry(properties),
+            registry: get_registry(parser, properties)?,
         },
         "minecraft:resource_key" => Argument::ResourceKey {
-            registry: get_registry(properties),
+            registry: get_registry(parser, properties)?,
         },
         "minecraft:resource_location" => Argument::ResourceLocation,
         "minecraft:resource_or_tag" => Argument::ResourceOrTag {
-            registry: get_registry(properties),
+            registry: get_registry(parser, properties)?,
         },
         "minecraft:resource_or_tag_key" => Argument::ResourceOrTagKey {
-            registry: get_registry(properties),
+            registry: get_registry(parser, properties)?,
         },
         "minecraft:rotation" => Argument::Rotation,
         "minecraft:score_holder" => {
-            let single = match properties.get("amount").map(|v| v.as_str().unwrap()) {
-                None | Some("multiple") => false,
-                Some("single") => true,
-                amount => panic!("invalid amount for minecraft:score_holder parser: {amount:?}"),
-            };
+            let single = get_choice(parser, properties, "amount", &["multiple", "single"])? == 1;
             Argument::ScoreHolder { single }
         }
         "minecraft:scoreboard_slot" => Argument::ScoreboardSlot,
@@ -204,14 +296,102 @@ fn construct_param(parser: &str, properties: &HashMap<String, Value>) -> Argumen
         "minecraft:template_mirror" => Argument::TemplateMirror,
         "minecraft:template_rotation" => Argument::TemplateRotation,
         "minecraft:time" => {
-            let min = properties
-                .get("min")
-                .map(|v| v.as_i64().unwrap() as i32)
-                .unwrap_or(0);
-            Argument::Time { min }
+            let min = get_property(parser, properties, "min", |v| {
+                v.as_i64().map(|num| num as i32)
+            })?;
+            Argument::Time {
+                min: min.unwrap_or(0),
+            }
+        }
+        "minecraft:vec2" => {
+            let (min, max) = get_min_max(parser, properties, Value::as_f64, f64::MIN, f64::MAX)?;
+            Argument::Vec2 { min, max }
+        }
+        "minecraft:vec3" => {
+            let (min, max) = get_min_max(parser, properties, Value::as_f64, f64::MIN, f64::MAX)?;
+            Argument::Vec3 { min, max }
         }
-        "minecraft:vec2" => Argument::Vec2,
-        "minecraft:vec3" => Argument::Vec3,
-        _ => panic!("unknown parser: {parser}"),
+        _ => return Err(ImportError::UnknownParser(parser.to_owned())),
+    };
+    Ok(argument)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ImportError, import};
+    use crate::{BuildTree, parse::argument::ArgumentRegistry};
+
+    fn import_json(json: &str) -> Result<(), ImportError> {
+        import(
+            json,
+            &mut BuildTree::default(),
+            &ArgumentRegistry::default(),
+        )
+    }
+
+    fn import_argument(parser: &str, properties: &str) -> Result<(), ImportError> {
+        let json = format!(
+            r#"{{"type": "root", "children": {{"cmd": {{"type": "literal", "children": {{
+                "arg": {{"type": "argument", "parser": "{parser}", "properties": {properties},
+                         "executable": true}}
+            }}}}}}}}"#
+        );
+        import_json(&json)
+    }
+
+    #[test]
+    fn known_parser_is_imported() {
+        assert!(import_argument("brigadier:integer", r#"{"min": 0}"#).is_ok());
+    }
+
+    #[test]
+    fn unknown_parser_is_an_error() {
+        let err = import_argument("mod:custom", "{}").unwrap_err();
+        assert!(matches!(&err, ImportError::UnknownParser(parser) if parser == "mod:custom"));
+        assert_eq!(err.to_string(), "unknown parser: mod:custom");
+    }
+
+    #[test]
+    fn invalid_properties_are_errors() {
+        let err = import_argument("minecraft:resource", "{}").unwrap_err();
+        assert!(matches!(err, ImportError::MissingRegistry { .. }));
+
+        let err = import_argument("brigadier:integer", r#"{"min": "zero"}"#).unwrap_err();
+        assert!(matches!(
+            err,
+            ImportError::InvalidProperty { parser, property }
+                if parser == "brigadier:integer" && property == "min"
+        ));
+
+        let err = import_json(r#"{"type": "root", "children": "#).unwrap_err();
+        assert!(matches!(err, ImportError::Json(_)));
+    }
+
+    #[test]
+    fn children_of_redirecting_nodes_are_ignored() {
+        // `again` is executable, redirects to `cmd` and lists a child of its own
+        let json = r#"{"type": "root", "children": {"cmd": {"type": "literal", "children": {
+            "end": {"type": "literal", "executable": true},
+            "again": {"type": "literal", "executable": true, "redirect": ["cmd"], "children": {
+                "ignored": {"type": "literal", "executable": true}
+            }}
+        }}}}"#;
+        let mut tree = BuildTree::default();
+        import(json, &mut tree, &ArgumentRegistry::default()).unwrap();
+        let tree = std::sync::Arc::new(tree.into_parsing_tree());
+
+        let errors = |text: &str| {
+            let parsed = crate::parse_source(std::sync::Arc::clone(&tree), None, text.to_owned());
+            parsed
+                .diagnostics()
+                .iter()
+                .map(|diagnostic| diagnostic.message().to_owned())
+                .collect::<Vec<_>>()
+        };
+        // The command may end at the redirecting node or continue with the redirect target
+        assert!(errors("cmd again").is_empty());
+        assert!(errors("cmd again end").is_empty());
+        assert!(errors("cmd again again end").is_empty());
+        assert_eq!(errors("cmd again ignored"), ["Invalid literal"]);
     }
 }
@@ -7,7 +7,7 @@ use std::{
 use hashbrown::{HashMap, hash_map::RawEntryMut};
 use rustc_hash::FxHasher;
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Symbol(pub NonZeroU32);
 
 impl fmt::Debug for Symbol {
@@ -19,6 +19,20 @@ impl fmt::Debug for Symbol {
 pub trait Interner {
     fn intern(&mut self, string: &str) -> Symbol;
     fn resolve(&self, symbol: Symbol) -> Option<&str>;
+    /// Interns all strings, e.g. to pre-seed the interner with keywords. The symbols are returned
+    /// in the order of the strings.
+    fn intern_all<'a>(&mut self, strings: impl IntoIterator<Item = &'a str>) -> Vec<Symbol>
+    where
+        Self: Sized,
+    {
+        strings
+            .into_iter()
+            .map(|string| self.intern(string))
+            .collect()
+    }
+    /// # Safety
+    ///
+    /// The symbol must have been created by this interner.
     unsafe fn resolve_unchecked(&self, symbol: Symbol) -> &str {
         self.resolve(symbol).unwrap()
     }
@@ -31,9 +45,13 @@ struct Buffer {
 }
 
 impl Buffer {
-    fn new() -> Self {
+    const MIN_SIZE: usize = 4096;
+
+    /// Creates a buffer whose first chunk holds at least `capacity` bytes. Later chunks grow from
+    /// there.
+    fn with_capacity(capacity: usize) -> Self {
         Self {
-            current: vec![0; 4096].into_boxed_slice(),
+            current: vec![0; capacity.max(Self::MIN_SIZE).next_power_of_two()].into_boxed_slice(),
             length: 0,
             full: Vec::new(),
         }
@@ -95,17 +113,96 @@ impl<H: Default> StaticInterner<H> {
     pub fn new() -> Self {
         Self::with_hasher(H::default())
     }
+
+    /// Creates an interner with room for `capacity` distinct strings, so that interning them does
+    /// not reallocate.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_hasher(capacity, H::default())
+    }
 }
 
 impl<H> StaticInterner<H> {
+    /// The number of bytes reserved per string by [`Self::with_capacity`]. Most identifiers in
+    /// datapacks are shorter than this.
+    const EXPECTED_STRING_LEN: usize = 16;
+
     pub fn with_hasher(build_hasher: H) -> Self {
+        Self::with_capacity_and_hasher(0, build_hasher)
+    }
+
+    pub fn with_capacity_and_hasher(capacity: usize, build_hasher: H) -> Self {
         Self {
             build_hasher,
-            symbols: HashMap::with_hasher(()),
-            entries: Vec::new(),
-            buffer: Buffer::new(),
+            symbols: HashMap::with_capacity_and_hasher(capacity, ()),
+            entries: Vec::with_capacity(capacity),
+            buffer: Buffer::with_capacity(capacity.saturating_mul(Self::EXPECTED_STRING_LEN)),
         }
     }
+
+    /// Returns the number of distinct strings which have been interned.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns all interned strings along with their symbols, in the order they were interned.
+    pub fn iter(&self) -> impl Iterator<Item = (Symbol, &str)> {
+        self.entries.iter().enumerate().map(|(idx, entry)| {
+            let id = u32::try_from(idx + 1).unwrap();
+            // Safety: The entries are valid for as long as the interner is borrowed
+            (Symbol(NonZeroU32::new(id).unwrap()), unsafe {
+                entry.as_str()
+            })
+        })
+    }
+}
+
+impl<H: BuildHasher> StaticInterner<H> {
+    /// Returns the symbol of the string if it has been interned, without interning it.
+    pub fn get(&self, string: &str) -> Option<Symbol> {
+        let hash = self.build_hasher.hash_one(string);
+        self.symbols
+            .raw_entry()
+            .from_hash(hash, |(_, view)| unsafe { string == view.as_str() })
+            .map(|((symbol, _), ())| *symbol)
+    }
+}
+
+/// Maps the symbols of one interner to those of a merged interner, see [`merge_interners`].
+#[derive(Debug, Clone, Default)]
+pub struct SymbolRemap {
+    symbols: Vec<Symbol>,
+}
+
+impl SymbolRemap {
+    /// Returns the symbol in the merged interner, or `None` if the symbol was not created by the
+    /// original interner.
+    pub fn get(&self, symbol: Symbol) -> Option<Symbol> {
+        let idx = usize::try_from(symbol.0.get() - 1).unwrap();
+        self.symbols.get(idx).copied()
+    }
+}
+
+/// Interns the strings of all interners into a single one, e.g. after parsing files in parallel
+/// with an interner each. Returns the merged interner along with a remap table per interner, in
+/// the order of the interners.
+pub fn merge_interners<'a, H: BuildHasher + Default + 'a>(
+    interners: impl IntoIterator<Item = &'a StaticInterner<H>>,
+) -> (StaticInterner<H>, Vec<SymbolRemap>) {
+    let mut merged = StaticInterner::<H>::new();
+    let remaps = interners
+        .into_iter()
+        .map(|interner| SymbolRemap {
+            symbols: interner
+                .iter()
+                .map(|(_, string)| merged.intern(string))
+                .collect(),
+        })
+        .collect();
+    (merged, remaps)
 }
 
 impl<H: BuildHasher> Interner for StaticInterner<H> {
@@ -155,3 +252,52 @@ impl<H: BuildHasher> Interner for StaticInterner<H> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Interner, StaticInterner};
+
+    #[test]
+    fn intern_all_equals_interning_one_by_one() {
+        let strings = ["execute", "run", "as", "execute", "", "ü"];
+
+        let mut bulk: StaticInterner = StaticInterner::new();
+        let bulk_symbols = bulk.intern_all(strings);
+
+        let mut single: StaticInterner = StaticInterner::new();
+        let single_symbols: Vec<_> = strings.iter().map(|string| single.intern(string)).collect();
+
+        assert_eq!(bulk_symbols, single_symbols);
+        assert_eq!(bulk_symbols[0], bulk_symbols[3]);
+        assert_eq!(bulk.len(), 5);
+        assert!(bulk.iter().eq(single.iter()));
+    }
+
+    #[test]
+    fn get_does_not_intern() {
+        let mut interner: StaticInterner = StaticInterner::new();
+        let run = interner.intern("run");
+        assert_eq!(interner.get("run"), Some(run));
+        assert_eq!(interner.get("as"), None);
+        assert_eq!(interner.len(), 1);
+    }
+
+    #[test]
+    fn intern_many_strings() {
+        let strings: Vec<String> = (0..50_000).map(|idx| format!("string_{idx}")).collect();
+
+        let mut interner: StaticInterner = StaticInterner::with_capacity(strings.len());
+        let symbols = interner.intern_all(strings.iter().map(String::as_str));
+        assert_eq!(interner.len(), strings.len());
+        // Interning the strings a second time creates no new symbols
+        assert_eq!(
+            interner.intern_all(strings.iter().map(String::as_str)),
+            symbols
+        );
+        assert_eq!(interner.len(), strings.len());
+
+        for (string, symbol) in strings.iter().zip(symbols) {
+            assert_eq!(interner.resolve(symbol), Some(string.as_str()));
+        }
+    }
+}
@@ -1,26 +1,62 @@
 mod build_tree;
+#[cfg(feature = "cache")]
+pub mod cache;
+pub mod call_graph;
+mod compiler;
+pub mod dependencies;
 pub mod diagnostics;
+#[cfg(feature = "serde")]
+pub mod function_tag;
+#[cfg(feature = "serde")]
 mod import;
-mod intern;
+pub mod intern;
+pub mod lint;
 mod node;
 pub mod parse;
+mod parsed;
 mod parsing_tree;
 mod smallstring;
 pub mod source;
 pub mod span;
+pub mod stats;
+#[cfg(all(test, feature = "serde"))]
+mod test_util;
 
 pub use build_tree::{BuildNodeId, BuildTree};
+pub use compiler::{CompiledFile, Compiler};
+#[cfg(feature = "rayon")]
+pub use compiler::{ParallelCompilation, compile_files_parallel};
+#[cfg(feature = "serde")]
+pub use import::ImportError;
 pub use node::{Node, NodeKind};
-pub use parsing_tree::{ParsingNode, ParsingTree};
+pub use parsed::{Parsed, parse_source};
+pub use parsing_tree::{
+    Completion, CompletionKind, IndexedCommand, Parameter, ParsingNode, ParsingTree, Signature,
+};
 pub use smallstring::SmallString;
 
-pub fn load_tree() -> ParsingTree {
+#[cfg(feature = "serde")]
+use crate::parse::argument::ArgumentRegistry;
+
+#[cfg(feature = "serde")]
+/// Builds the parsing tree from the contents of a `commands.json` file as generated by the
+/// Minecraft data generator.
+pub fn load_tree(commands_json: &str) -> Result<ParsingTree, ImportError> {
+    load_tree_with_registry(commands_json, &ArgumentRegistry::default())
+}
+
+#[cfg(feature = "serde")]
+pub fn load_tree_with_registry(
+    commands_json: &str,
+    registry: &ArgumentRegistry,
+) -> Result<ParsingTree, ImportError> {
     let mut build_tree = BuildTree::default();
-    import::import(
-        &std::fs::read_to_string("commands.json").unwrap(),
-        &mut build_tree,
-    );
+    import::import(commands_json, &mut build_tree, registry)?;
 
+    // `execute run` and `return run` redirect back to the root in vanilla. They are replaced by
+    // block nodes, so they can be followed either by a single inline command or by an indented
+    // block of commands. Blocks are parsed like top-level commands, therefore both forms nest,
+    // e.g. `execute if entity @s run return run function foo:bar`.
     let execute_run_node = build_tree.find_node_id(["execute", "run"]).unwrap();
     build_tree.clear_node(execute_run_node);
     build_tree.insert(execute_run_node, Node::block());
@@ -29,5 +65,123 @@ pub fn load_tree() -> ParsingTree {
     build_tree.clear_node(return_run_node);
     build_tree.insert(return_run_node, Node::block());
 
-    build_tree.into_parsing_tree()
+    Ok(build_tree.into_parsing_tree())
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use crate::{
+        ParsingTree,
+        intern::Interner,
+        parse::{
+            ParseContext,
+            cst::{self, Argument, ArgumentValue, Command},
+        },
+        source::SourceFile,
+        span::Span,
+        test_util,
+    };
+
+    /// Returns the literal paths of all commands, including nested ones, in source order.
+    fn command_paths(text: &str) -> Vec<String> {
+        struct PathVisitor<'a> {
+            tree: &'a ParsingTree,
+            paths: Vec<String>,
+        }
+
+        impl cst::Visitor for PathVisitor<'_> {
+            fn visit_command(&mut self, command: &Command) {
+                self.paths.push(command.literal_path(self.tree).join(" "));
+                cst::walk_command(self, command);
+            }
+        }
+
+        let parsed = test_util::parse(text);
+        assert!(parsed.diagnostics().is_empty());
+        let mut visitor = PathVisitor {
+            tree: &parsed.tree,
+            paths: Vec::new(),
+        };
+        cst::walk_block(&mut visitor, parsed.result.as_ref().unwrap());
+        visitor.paths
+    }
+
+    #[test]
+    fn return_inside_execute_run() {
+        assert_eq!(
+            command_paths("execute run return 0"),
+            ["execute run", "return"]
+        );
+        assert_eq!(
+            command_paths("execute run return fail"),
+            ["execute run", "return fail"]
+        );
+        assert_eq!(
+            command_paths("execute run return run say hi"),
+            ["execute run", "return run", "say"]
+        );
+    }
+
+    #[test]
+    fn return_run_in_indented_blocks() {
+        let text = "execute run\n    return run\n        say hi\n    return 1\n";
+        assert_eq!(
+            command_paths(text),
+            ["execute run", "return run", "say", "return"]
+        );
+    }
+
+    #[test]
+    fn message_is_parsed_as_text() {
+        struct TextVisitor(Vec<crate::intern::Symbol>);
+
+        impl cst::Visitor for TextVisitor {
+            fn visit_argument(&mut self, argument: &Argument) {
+                if let ArgumentValue::String(text) = &argument.value {
+                    self.0.extend(text.value);
+                }
+                cst::walk_argument(self, argument);
+            }
+        }
+
+        let parsed = test_util::parse("execute run return run say hi @a");
+        assert!(parsed.diagnostics().is_empty());
+        let mut visitor = TextVisitor(Vec::new());
+        cst::walk_block(&mut visitor, parsed.result.as_ref().unwrap());
+        let texts: Vec<_> = visitor
+            .0
+            .iter()
+            .map(|&text| parsed.interner.resolve(text))
+            .collect();
+        assert_eq!(texts, [Some("hi @a")]);
+    }
+
+    #[test]
+    fn tabs_are_expanded() {
+        struct SpanVisitor(Vec<Span>);
+
+        impl cst::Visitor for SpanVisitor {
+            fn visit_command(&mut self, command: &Command) {
+                self.0.push(command.span());
+                cst::walk_command(self, command);
+            }
+        }
+
+        let text = "execute run\n\tsay a\n\texecute run\n\t    say b\n\t\tsay c\nsay d\n";
+        let source = SourceFile::new(None, text.to_owned());
+        let mut ctx = ParseContext::new(&source, test_util::tree());
+        ctx.options.expand_tabs = Some(4);
+        let block = ctx.parse().unwrap();
+        let mut visitor = SpanVisitor(Vec::new());
+        cst::walk_block(&mut visitor, &block);
+        let spans = visitor.0;
+        assert_eq!(spans.len(), 6);
+        // Nested commands start right after their tabs in the original source
+        assert_eq!(spans[1], Span::new(13, 18));
+        assert_eq!(&text[spans[1].start - 1..spans[1].start], "\t");
+        assert_eq!(spans[3].start, text.find("say b").unwrap());
+        assert_eq!(spans[4].start, text.find("say c").unwrap());
+        assert_eq!(&text[spans[4].start - 2..spans[4].start], "\t\t");
+        assert_eq!(spans[5].start, text.find("say d").unwrap());
+    }
 }
@@ -0,0 +1,74 @@
+use crate::{
+    diagnostics::{Diagnostic, Label},
+    parse::cst::{self, Argument, Block},
+    span::Span,
+};
+
+/// Warns about blank lines between the items of nested blocks. Blank lines at the top level of a
+/// file are allowed.
+pub fn lint_blank_lines_in_blocks(block: &Block, source: &str) -> Vec<Diagnostic> {
+    struct BlankLinesVisitor<'a> {
+        source: &'a str,
+        diagnostics: Vec<Diagnostic>,
+    }
+
+    impl cst::Visitor for BlankLinesVisitor<'_> {
+        fn visit_argument(&mut self, argument: &Argument) {
+            if let Some(block) = argument.block() {
+                for items in block.items.windows(2) {
+                    let gap_start = items[0].span().end;
+                    let gap = &self.source[gap_start..items[1].span().start];
+                    let (Some(first), Some(last)) = (gap.find('\n'), gap.rfind('\n')) else {
+                        continue;
+                    };
+                    if first == last {
+                        continue;
+                    }
+
+                    let span = Span::new(gap_start + first + 1, gap_start + last);
+                    self.diagnostics.push(
+                        Diagnostic::warn(span, "Blank line in block")
+                            .with_label(Label::new(span, "Blocks must not contain blank lines")),
+                    );
+                }
+            }
+            cst::walk_argument(self, argument);
+        }
+    }
+
+    let mut visitor = BlankLinesVisitor {
+        source,
+        diagnostics: Vec::new(),
+    };
+    cst::walk_block(&mut visitor, block);
+    visitor.diagnostics
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::lint_blank_lines_in_blocks;
+    use crate::{diagnostics::Diagnostic, span::Span, test_util};
+
+    fn blank_lines(text: &str) -> Vec<Span> {
+        let parsed = test_util::parse(text);
+        assert!(parsed.diagnostics().is_empty());
+        let lints = lint_blank_lines_in_blocks(parsed.result.as_ref().unwrap(), text);
+        lints.iter().map(Diagnostic::span).collect()
+    }
+
+    #[test]
+    fn blank_line_between_block_commands() {
+        let text = "execute run\n    say a\n\n    say b\n";
+        assert_eq!(blank_lines(text), [Span::new(22, 22)]);
+
+        // Whitespace-only lines count as blank lines as well
+        let text = "execute run\n    say a\n    \n    \n    say b\n";
+        assert_eq!(blank_lines(text), [Span::new(22, 31)]);
+    }
+
+    #[test]
+    fn blank_lines_at_the_top_level_are_allowed() {
+        assert!(blank_lines("say a\n\nsay b\n").is_empty());
+        assert!(blank_lines("execute run\n    say a\n\nsay b\n").is_empty());
+    }
+}
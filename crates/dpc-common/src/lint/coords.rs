@@ -0,0 +1,147 @@
+use std::ops::RangeInclusive;
+
+use super::command_is;
+use crate::{
+    ParsingTree,
+    diagnostics::{Diagnostic, Label},
+    parse::{
+        argument::Coordinates,
+        cst::{self, ArgumentValue, Block, Command},
+    },
+    span::Span,
+};
+
+/// The range of Y coordinates in which blocks can be placed in the overworld.
+const BUILD_HEIGHT: RangeInclusive<f64> = -64.0..=319.0;
+
+/// Checks a block position argument of the command with the given literal path for obviously
+/// wrong values.
+///
+/// Currently this only checks that fully absolute positions used by `setblock` and `fill` are
+/// within the build height. Relative and local coordinates are never reported.
+pub fn validate_coordinates(
+    coords: &Coordinates<3>,
+    span: Span,
+    command_path: &[&str],
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    if !command_is(command_path, &["setblock"]) && !command_is(command_path, &["fill"]) {
+        return diagnostics;
+    }
+
+    let Coordinates::World(coords) = coords else {
+        return diagnostics;
+    };
+
+    if coords.iter().any(|coord| coord.relative) {
+        return diagnostics;
+    }
+
+    if let Some(y) = coords[1].value.value
+        && !BUILD_HEIGHT.contains(&y)
+    {
+        diagnostics.push(
+            Diagnostic::warn(span, "Position is outside of the build height").with_label(
+                Label::new(
+                    span,
+                    format!(
+                        "Y coordinate must be between {} and {}",
+                        BUILD_HEIGHT.start(),
+                        BUILD_HEIGHT.end(),
+                    ),
+                ),
+            ),
+        );
+    }
+
+    diagnostics
+}
+
+/// Runs [`validate_coordinates`] on every coordinate argument in the block.
+pub fn lint_coordinates(block: &Block, tree: &ParsingTree) -> Vec<Diagnostic> {
+    struct CoordinatesVisitor<'a> {
+        tree: &'a ParsingTree,
+        diagnostics: Vec<Diagnostic>,
+    }
+
+    impl cst::Visitor for CoordinatesVisitor<'_> {
+        fn visit_command(&mut self, command: &Command) {
+            let path = command.literal_path(self.tree);
+            for argument in &command.args {
+                if let ArgumentValue::Coordinates3(coords) = &argument.value {
+                    self.diagnostics
+                        .extend(validate_coordinates(coords, argument.span, &path));
+                }
+            }
+            cst::walk_command(self, command);
+        }
+    }
+
+    let mut visitor = CoordinatesVisitor {
+        tree,
+        diagnostics: Vec::new(),
+    };
+    cst::walk_block(&mut visitor, block);
+    visitor.diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::validate_coordinates;
+    use crate::{
+        diagnostics::Level,
+        parse::argument::{Coordinates, Double, WorldCoordinate},
+        span::Span,
+    };
+
+    fn absolute(x: f64, y: f64, z: f64) -> Coordinates<3> {
+        Coordinates::World([x, y, z].map(|value| WorldCoordinate {
+            value: Double::new(value),
+            relative: false,
+        }))
+    }
+
+    fn validate(coords: &Coordinates<3>, command_path: &[&str]) -> Vec<(Level, String)> {
+        validate_coordinates(coords, Span::new(9, 16), command_path)
+            .iter()
+            .map(|diagnostic| (diagnostic.level(), diagnostic.message().to_owned()))
+            .collect()
+    }
+
+    #[test]
+    fn y_outside_of_build_height() {
+        let expected = vec![(
+            Level::Warn,
+            "Position is outside of the build height".to_owned(),
+        )];
+        assert_eq!(
+            validate(&absolute(0.0, 320.0, 0.0), &["setblock"]),
+            expected
+        );
+        assert_eq!(validate(&absolute(0.0, -65.0, 0.0), &["fill"]), expected);
+    }
+
+    #[test]
+    fn y_within_build_height() {
+        assert!(validate(&absolute(0.0, 319.0, 0.0), &["setblock"]).is_empty());
+        assert!(validate(&absolute(0.0, -64.0, 0.0), &["minecraft:fill"]).is_empty());
+    }
+
+    #[test]
+    fn relative_and_local_coordinates_are_skipped() {
+        let Coordinates::World(mut coords) = absolute(0.0, 400.0, 0.0) else {
+            unreachable!();
+        };
+        coords[0].relative = true;
+        assert!(validate(&Coordinates::World(coords), &["setblock"]).is_empty());
+
+        let local = Coordinates::Local([0.0, 400.0, 0.0].map(Double::new));
+        assert!(validate(&local, &["setblock"]).is_empty());
+    }
+
+    #[test]
+    fn other_commands_are_skipped() {
+        assert!(validate(&absolute(0.0, 1000.0, 0.0), &["tp"]).is_empty());
+    }
+}
@@ -0,0 +1,107 @@
+use rustc_hash::FxHashMap;
+
+use crate::{
+    diagnostics::{Diagnostic, Label},
+    parse::cst::{self, Argument, Block, Item},
+    span::Span,
+};
+
+/// Warns about commands which repeat an earlier command of the same block, which is usually a
+/// copy-paste mistake. Commands are compared by their source text, ignoring differences in
+/// whitespace. Comments are never reported.
+pub fn lint_duplicate_commands(block: &Block, source: &str) -> Vec<Diagnostic> {
+    struct DuplicatesVisitor<'a> {
+        source: &'a str,
+        diagnostics: Vec<Diagnostic>,
+    }
+
+    impl DuplicatesVisitor<'_> {
+        fn check_block(&mut self, block: &Block) {
+            let mut seen = FxHashMap::<String, Span>::default();
+            for item in &block.items {
+                let Item::Command(command) = item else {
+                    continue;
+                };
+                let span = command.span();
+                let text = self.source[span.as_range()]
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" ");
+
+                if let Some(&first) = seen.get(&text) {
+                    self.diagnostics.push(
+                        Diagnostic::warn(span, "Duplicate command")
+                            .with_label(Label::new(span, "This command is repeated"))
+                            .with_label(Label::new(first, "It was first used here")),
+                    );
+                } else {
+                    seen.insert(text, span);
+                }
+            }
+        }
+    }
+
+    impl cst::Visitor for DuplicatesVisitor<'_> {
+        fn visit_argument(&mut self, argument: &Argument) {
+            if let Some(block) = argument.block() {
+                self.check_block(block);
+            }
+            cst::walk_argument(self, argument);
+        }
+    }
+
+    let mut visitor = DuplicatesVisitor {
+        source,
+        diagnostics: Vec::new(),
+    };
+    visitor.check_block(block);
+    cst::walk_block(&mut visitor, block);
+    visitor.diagnostics
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::lint_duplicate_commands;
+    use crate::{diagnostics::Level, span::Span, test_util};
+
+    /// Returns the span of each warning and the spans of its labels.
+    fn duplicates(text: &str) -> Vec<(Span, Vec<Span>)> {
+        let parsed = test_util::parse(text);
+        assert!(parsed.diagnostics().is_empty());
+        let block = parsed.result.as_ref().unwrap();
+        lint_duplicate_commands(block, text)
+            .iter()
+            .map(|diagnostic| {
+                assert_eq!(diagnostic.level(), Level::Warn);
+                let labels = diagnostic.labels().iter().map(|label| label.span());
+                (diagnostic.span(), labels.collect())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn identical_commands() {
+        assert_eq!(
+            duplicates("say hi\nsay hi\n"),
+            [(Span::new(7, 13), vec![Span::new(7, 13), Span::new(0, 6)])]
+        );
+        assert!(duplicates("say hi\nsay ho\n").is_empty());
+    }
+
+    #[test]
+    fn whitespace_is_ignored() {
+        assert_eq!(duplicates("tp @s ~ ~1 ~\ntp  @s ~   ~1 ~\n").len(), 1);
+    }
+
+    #[test]
+    fn nested_blocks_and_comments() {
+        assert!(duplicates("# todo\n# todo\nsay a\nexecute run\n    say a\n").is_empty());
+        assert_eq!(
+            duplicates("execute as @a run\n    say a\n    say a\n"),
+            [(
+                Span::new(32, 37),
+                vec![Span::new(32, 37), Span::new(22, 27)]
+            )]
+        );
+    }
+}
@@ -0,0 +1,121 @@
+use super::command_is;
+use crate::{
+    ParsingTree,
+    diagnostics::{Diagnostic, Label},
+    parse::cst::{self, Argument, Command},
+    span::Span,
+};
+
+/// Warns about `execute` clauses which are exact duplicates of the clause directly before them,
+/// e.g. `execute as @s as @s run ...`.
+pub fn lint_duplicate_execute_clauses(
+    block: &cst::Block,
+    tree: &ParsingTree,
+    source: &str,
+) -> Vec<Diagnostic> {
+    struct ExecuteVisitor<'a> {
+        tree: &'a ParsingTree,
+        source: &'a str,
+        diagnostics: Vec<Diagnostic>,
+    }
+
+    impl ExecuteVisitor<'_> {
+        /// Splits the arguments of an `execute` command into its clauses. Every subcommand
+        /// redirects back to `execute`, so a clause starts with each argument which is a child of
+        /// the `execute` node.
+        fn clauses<'c>(&self, command: &'c Command) -> Vec<&'c [Argument]> {
+            let Some(execute) = self.tree.find_node(["execute"]) else {
+                return Vec::new();
+            };
+            let subcommands = self.tree.nodes[execute].children.clone();
+
+            let mut clauses = Vec::new();
+            let mut start = 1;
+            for (idx, arg) in command.args.iter().enumerate().skip(2) {
+                if subcommands.contains(&arg.lin_node_id) {
+                    clauses.push(&command.args[start..idx]);
+                    start = idx;
+                }
+            }
+            if start < command.args.len() {
+                clauses.push(&command.args[start..]);
+            }
+            clauses
+        }
+
+        fn clause_eq(&self, a: &[Argument], b: &[Argument]) -> bool {
+            a.len() == b.len()
+                && a.iter().zip(b).all(|(a, b)| {
+                    a.lin_node_id == b.lin_node_id
+                        && self.source[a.span.as_range()] == self.source[b.span.as_range()]
+                })
+        }
+    }
+
+    impl cst::Visitor for ExecuteVisitor<'_> {
+        fn visit_command(&mut self, command: &Command) {
+            if command_is(&command.literal_path(self.tree), &["execute"]) {
+                let clauses = self.clauses(command);
+                for pair in clauses.windows(2) {
+                    if !self.clause_eq(pair[0], pair[1]) {
+                        continue;
+                    }
+
+                    let span = |clause: &[Argument]| {
+                        Span::new(clause[0].span.start, clause[clause.len() - 1].span.end)
+                    };
+                    let (previous, duplicate) = (span(pair[0]), span(pair[1]));
+                    self.diagnostics.push(
+                        Diagnostic::warn(duplicate, "Redundant execute clause")
+                            .with_label(Label::new(duplicate, "This clause is redundant"))
+                            .with_label(Label::new(previous, "It duplicates this clause")),
+                    );
+                }
+            }
+            cst::walk_command(self, command);
+        }
+    }
+
+    let mut visitor = ExecuteVisitor {
+        tree,
+        source,
+        diagnostics: Vec::new(),
+    };
+    cst::walk_block(&mut visitor, block);
+    visitor.diagnostics
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::lint_duplicate_execute_clauses;
+    use crate::{diagnostics::Diagnostic, span::Span, test_util};
+
+    fn duplicates(text: &str) -> Vec<Span> {
+        let parsed = test_util::parse(text);
+        assert!(parsed.diagnostics().is_empty());
+        let lints =
+            lint_duplicate_execute_clauses(parsed.result.as_ref().unwrap(), &parsed.tree, text);
+        lints.iter().map(Diagnostic::span).collect()
+    }
+
+    #[test]
+    fn duplicated_as_clause() {
+        assert_eq!(
+            duplicates("execute as @s as @s run say hi"),
+            [Span::new(14, 19)]
+        );
+        assert_eq!(
+            duplicates(
+                "execute run\n    execute if score @s o matches 1 if score @s o matches 1 run say hi"
+            ),
+            [Span::new(48, 71)]
+        );
+    }
+
+    #[test]
+    fn different_or_separated_clauses_are_allowed() {
+        assert!(duplicates("execute as @s as @a run say hi").is_empty());
+        assert!(duplicates("execute as @s at @s run say hi").is_empty());
+        assert!(duplicates("execute as @s at @s as @s run say hi").is_empty());
+    }
+}
@@ -0,0 +1,231 @@
+use super::command_is;
+use crate::{
+    ParsingTree,
+    call_graph::{CallGraph, function_calls},
+    diagnostics::{Diagnostic, Label, Level},
+    intern::Interner,
+    parse::cst::{self, Block, Command},
+    span::Span,
+};
+
+/// Warns about functions which unconditionally call themselves, which never terminates.
+///
+/// `function` is the id of the function the block belongs to, e.g. `foo:loop`. Calls within
+/// `execute` commands are considered to be guarded by a condition, and so are calls after a
+/// `return` command, which may end the function before the call is reached.
+pub fn lint_self_recursion(
+    function: &str,
+    block: &Block,
+    tree: &ParsingTree,
+    interner: &impl Interner,
+) -> Vec<Diagnostic> {
+    struct ReturnVisitor<'a> {
+        tree: &'a ParsingTree,
+        first_return: Option<usize>,
+    }
+
+    impl cst::Visitor for ReturnVisitor<'_> {
+        fn visit_command(&mut self, command: &Command) {
+            let path = command.literal_path(self.tree);
+            if self.first_return.is_none()
+                && command_is(&path, &["return"])
+                && !command_is(&path, &["return", "run"])
+            {
+                self.first_return = Some(command.span().start);
+            }
+            cst::walk_command(self, command);
+        }
+    }
+
+    let mut visitor = ReturnVisitor {
+        tree,
+        first_return: None,
+    };
+    cst::walk_block(&mut visitor, block);
+    let first_return = visitor.first_return.unwrap_or(usize::MAX);
+
+    function_calls(block, tree)
+        .into_iter()
+        .filter(|call| {
+            !call.conditional
+                && !call.deferred
+                && !call.target.is_tag
+                && call.span.start < first_return
+                && call
+                    .target
+                    .location
+                    .and_then(|location| location.resolve(interner))
+                    .is_some_and(|target| target == function)
+        })
+        .map(|call| {
+            Diagnostic::warn(call.span, "Function calls itself unconditionally").with_label(
+                Label::new(
+                    call.span,
+                    "This call always happens, so the function never ends",
+                ),
+            )
+        })
+        .collect()
+}
+
+/// Warns about calls to functions and function tags which are not part of the pack, including
+/// functions run later by `schedule function`. Tags are only checked if the graph knows any tags.
+pub fn lint_unknown_functions(
+    block: &Block,
+    tree: &ParsingTree,
+    interner: &impl Interner,
+    graph: &CallGraph,
+) -> Vec<Diagnostic> {
+    function_calls(block, tree)
+        .into_iter()
+        .filter_map(|call| {
+            let target = call.target.location?.resolve(interner)?;
+            let known = match call.target.is_tag {
+                true => graph.tags().is_empty() || graph.tags().contains_key(&target),
+                false => graph.contains(&target),
+            };
+            let prefix = if call.target.is_tag { "#" } else { "" };
+            (!known).then(|| {
+                Diagnostic::warn(call.span, "Unknown function").with_label(Label::new(
+                    call.span,
+                    format!("`{prefix}{target}` is not part of the pack"),
+                ))
+            })
+        })
+        .collect()
+}
+
+/// Reports functions which are never run. A function is considered to be used if it is run from
+/// one of the entry points, is called by another function or is a member of a function tag.
+///
+/// Functions may also be run from outside the pack, e.g. by players, so the entry points are
+/// configurable. Usually they include [`CallGraph::ENTRY_POINTS`]. The diagnostics are returned
+/// along with the id of the function they belong to, and point to the start of its file.
+pub fn lint_unreachable_functions<'a>(
+    graph: &CallGraph,
+    entry_points: impl IntoIterator<Item = &'a str>,
+) -> Vec<(String, Diagnostic)> {
+    let mut used = graph.reachable_from(entry_points);
+    for function in graph.functions() {
+        used.extend(
+            graph
+                .calls(function)
+                .filter(|&callee| callee != function)
+                .map(str::to_owned),
+        );
+    }
+    for members in graph.tags().values() {
+        used.extend(members.iter().cloned());
+    }
+
+    let mut unused: Vec<_> = graph
+        .functions()
+        .filter(|function| !used.contains(*function))
+        .collect();
+    unused.sort_unstable();
+
+    unused
+        .into_iter()
+        .map(|function| {
+            let span = Span::new(0, 0);
+            let diagnostic = Diagnostic::new(Level::Info, span, "Function is never run").with_sub(
+                Level::Info,
+                format!("`{function}` is not called by any function or function tag"),
+            );
+            (function.to_owned(), diagnostic)
+        })
+        .collect()
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::{lint_self_recursion, lint_unknown_functions, lint_unreachable_functions};
+    use crate::{
+        Compiler,
+        call_graph::{CallGraph, FunctionTags},
+        diagnostics::Level,
+        span::Span,
+        test_util,
+    };
+
+    fn self_recursion(text: &str) -> Vec<(Level, Span)> {
+        let parsed = test_util::parse(text);
+        assert!(parsed.diagnostics().is_empty());
+        let block = parsed.result.as_ref().unwrap();
+        lint_self_recursion("foo:loop", block, &parsed.tree, &parsed.interner)
+            .iter()
+            .map(|diagnostic| (diagnostic.level(), diagnostic.span()))
+            .collect()
+    }
+
+    #[test]
+    fn unconditional_self_call() {
+        assert_eq!(
+            self_recursion("say hi\nfunction foo:loop\n"),
+            [(Level::Warn, Span::new(16, 24))]
+        );
+    }
+
+    #[test]
+    fn self_call_under_execute_if() {
+        assert!(
+            self_recursion("execute if score @s n matches 1.. run function foo:loop").is_empty()
+        );
+        assert!(self_recursion("execute if entity @s run\n    function foo:loop\n").is_empty());
+    }
+
+    #[test]
+    fn other_calls_and_calls_after_return() {
+        assert!(self_recursion("function foo:other\nfunction #foo:loop").is_empty());
+        assert!(self_recursion("return 0\nfunction foo:loop").is_empty());
+    }
+
+    #[test]
+    fn dead_and_reachable_functions() {
+        let mut compiler = Compiler::new(test_util::tree());
+        let files = [
+            ("foo:main", "function foo:used\n"),
+            ("foo:used", "say used\n"),
+            ("foo:dead", "say dead\nfunction foo:dead\n"),
+        ]
+        .map(|(id, text)| (id, compiler.compile_file(id.into(), text.to_owned())));
+
+        let mut tags = FunctionTags::default();
+        tags.insert("minecraft:tick".to_owned(), vec!["foo:main".to_owned()]);
+        let functions = files
+            .iter()
+            .map(|(id, file)| (*id, file.result.as_ref().unwrap()));
+        let graph = CallGraph::new(functions, compiler.tree(), compiler.interner(), tags);
+
+        let unreachable = lint_unreachable_functions(&graph, CallGraph::ENTRY_POINTS);
+        assert_eq!(unreachable.len(), 1);
+        let (function, diagnostic) = &unreachable[0];
+        assert_eq!(function, "foo:dead");
+        assert_eq!(diagnostic.level(), Level::Info);
+    }
+
+    #[test]
+    fn unknown_functions_and_tags() {
+        let parsed = test_util::parse(
+            "function foo:a
+function #foo:t
+function #foo:u
+",
+        );
+        let block = parsed.result.as_ref().unwrap();
+        let functions = [("foo:a", block)];
+        let unknown = |tags: FunctionTags| {
+            let graph = CallGraph::new(functions, &parsed.tree, &parsed.interner, tags);
+            lint_unknown_functions(block, &parsed.tree, &parsed.interner, &graph)
+                .iter()
+                .map(|diagnostic| diagnostic.span())
+                .collect::<Vec<_>>()
+        };
+
+        // Without any known tags, only functions are checked
+        assert!(unknown(FunctionTags::default()).is_empty());
+        let mut tags = FunctionTags::default();
+        tags.insert("foo:t".to_owned(), vec!["foo:a".to_owned()]);
+        assert_eq!(unknown(tags), [Span::new(40, 46)]);
+    }
+}
@@ -0,0 +1,76 @@
+pub use blank_lines::lint_blank_lines_in_blocks;
+pub use coords::{lint_coordinates, validate_coordinates};
+pub use duplicates::lint_duplicate_commands;
+pub use execute::lint_duplicate_execute_clauses;
+pub use functions::{lint_self_recursion, lint_unknown_functions, lint_unreachable_functions};
+pub use scoreboard::{lint_negative_score_change, lint_wildcard_operation_source};
+pub use validate::ValidationContext;
+pub use whitespace::lint_trailing_whitespace;
+
+mod blank_lines;
+mod coords;
+mod duplicates;
+mod execute;
+mod functions;
+mod scoreboard;
+mod validate;
+mod whitespace;
+
+use crate::{ParsingTree, diagnostics::Diagnostic, parse::cst::Block};
+
+/// Runs all lints which only depend on a single file, so they can be reported along with its parse
+/// errors. The diagnostics are sorted by their position.
+///
+/// Lints which depend on the other functions of a pack, like [`lint_unreachable_functions`], are
+/// not included.
+pub fn lint_file(block: &Block, tree: &ParsingTree, source: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = lint_trailing_whitespace(source);
+    diagnostics.extend(lint_blank_lines_in_blocks(block, source));
+    diagnostics.extend(lint_duplicate_commands(block, source));
+    diagnostics.extend(lint_duplicate_execute_clauses(block, tree, source));
+    diagnostics.extend(lint_coordinates(block, tree));
+    diagnostics.extend(lint_negative_score_change(block, tree));
+    diagnostics.extend(lint_wildcard_operation_source(block, tree));
+    diagnostics.sort_by_key(Diagnostic::span);
+    diagnostics
+}
+
+/// Returns whether the literal path of a command starts with the expected literals. The first
+/// literal may be prefixed with the `minecraft:` namespace.
+fn command_is(path: &[&str], expected: &[&str]) -> bool {
+    let Some((first, rest)) = path.split_first() else {
+        return expected.is_empty();
+    };
+    let first = first.strip_prefix("minecraft:").unwrap_or(first);
+    std::iter::once(first)
+        .chain(rest.iter().copied())
+        .take(expected.len())
+        .eq(expected.iter().copied())
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::lint_file;
+    use crate::{diagnostics::Level, test_util};
+
+    #[test]
+    fn lints_are_warnings_sorted_by_position() {
+        let parsed = test_util::parse("help foo  \nhelp bar\nhelp bar\t\n");
+        let block = parsed.result.as_ref().unwrap();
+        let diagnostics = lint_file(block, &parsed.tree, parsed.source.text());
+
+        let found: Vec<_> = diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.level(), diagnostic.message()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (Level::Warn, "Trailing whitespace"),
+                (Level::Warn, "Duplicate command"),
+                (Level::Warn, "Trailing whitespace"),
+            ]
+        );
+        assert!(diagnostics.is_sorted_by_key(|diagnostic| diagnostic.span()));
+    }
+}
@@ -0,0 +1,199 @@
+use super::command_is;
+use crate::{
+    NodeKind, ParsingTree,
+    diagnostics::{Diagnostic, Label},
+    parse::{
+        argument::ScoreHolder,
+        cst::{self, ArgumentValue, Block, Command},
+    },
+};
+
+/// Warns about `scoreboard players operation` commands using `*` as the source score holder.
+///
+/// `*` stands for every tracked score holder, so the operation is applied once per holder, which
+/// is rarely what was intended.
+pub fn lint_wildcard_operation_source(block: &Block, tree: &ParsingTree) -> Vec<Diagnostic> {
+    struct OperationVisitor<'a> {
+        tree: &'a ParsingTree,
+        diagnostics: Vec<Diagnostic>,
+    }
+
+    impl cst::Visitor for OperationVisitor<'_> {
+        fn visit_command(&mut self, command: &Command) {
+            if command_is(
+                &command.literal_path(self.tree),
+                &["scoreboard", "players", "operation"],
+            ) {
+                let wildcard_source = command.args.iter().find(|arg| {
+                    self.tree.get_node(arg.lin_node_id).is_some_and(|node| {
+                        matches!(&node.kind, NodeKind::Argument { name, .. } if &**name == "source")
+                    }) && matches!(arg.value, ArgumentValue::ScoreHolder(ScoreHolder::Wildcard))
+                });
+
+                if let Some(arg) = wildcard_source {
+                    self.diagnostics.push(
+                        Diagnostic::warn(arg.span, "Wildcard used as operation source").with_label(
+                            Label::new(
+                                arg.span,
+                                "The operation is applied once for every tracked score holder",
+                            ),
+                        ),
+                    );
+                }
+            }
+            cst::walk_command(self, command);
+        }
+    }
+
+    let mut visitor = OperationVisitor {
+        tree,
+        diagnostics: Vec::new(),
+    };
+    cst::walk_block(&mut visitor, block);
+    visitor.diagnostics
+}
+
+/// Warns about `scoreboard players add` and `remove` commands with a negative score, which are
+/// better written as the opposite command with a positive score.
+pub fn lint_negative_score_change(block: &Block, tree: &ParsingTree) -> Vec<Diagnostic> {
+    struct ScoreChangeVisitor<'a> {
+        tree: &'a ParsingTree,
+        diagnostics: Vec<Diagnostic>,
+    }
+
+    impl cst::Visitor for ScoreChangeVisitor<'_> {
+        fn visit_command(&mut self, command: &Command) {
+            let path = command.literal_path(self.tree);
+            let (action, opposite) = if command_is(&path, &["scoreboard", "players", "add"]) {
+                ("add", "remove")
+            } else if command_is(&path, &["scoreboard", "players", "remove"]) {
+                ("remove", "add")
+            } else {
+                return cst::walk_command(self, command);
+            };
+
+            // Scores out of the grammar's bounds are already reported as parse errors
+            let negative_score = command.args.iter().find_map(|arg| match arg.value {
+                ArgumentValue::Integer(integer) if !arg.has_errors() => integer
+                    .value
+                    .filter(|value| *value < 0)
+                    .map(|value| (arg.span, value)),
+                _ => None,
+            });
+
+            if let Some((span, score)) = negative_score {
+                self.diagnostics.push(
+                    Diagnostic::warn(
+                        span,
+                        format!("Negative score in `scoreboard players {action}`"),
+                    )
+                    .with_label(Label::new(span, "The score must not be negative"))
+                    .with_help(format!(
+                        "Use `scoreboard players {opposite}` with {} instead",
+                        score.unsigned_abs()
+                    )),
+                );
+            }
+            cst::walk_command(self, command);
+        }
+    }
+
+    let mut visitor = ScoreChangeVisitor {
+        tree,
+        diagnostics: Vec::new(),
+    };
+    cst::walk_block(&mut visitor, block);
+    visitor.diagnostics
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use std::sync::Arc;
+
+    use super::{lint_negative_score_change, lint_wildcard_operation_source};
+    use crate::{
+        ParsingTree,
+        diagnostics::{Diagnostic, Level},
+        span::Span,
+        test_util,
+    };
+
+    fn lint(tree: Arc<ParsingTree>, text: &str) -> (Vec<String>, Vec<String>) {
+        let parsed = crate::parse_source(tree, None, text.to_owned());
+        let block = parsed.result.as_ref().unwrap();
+        let messages = |diagnostics: Vec<Diagnostic>| {
+            diagnostics
+                .iter()
+                .map(|diagnostic| diagnostic.message().to_owned())
+                .collect()
+        };
+        let lints = lint_negative_score_change(block, &parsed.tree);
+        assert!(lints.iter().all(|lint| lint.level() == Level::Warn));
+        (messages(parsed.diagnostics()), messages(lints))
+    }
+
+    /// Returns a tree in which `scoreboard players add` and `remove` accept negative scores, like
+    /// in older versions.
+    fn tree_with_negative_scores() -> Arc<ParsingTree> {
+        let mut json: serde_json::Value =
+            serde_json::from_str(include_str!("../../../../commands.json")).unwrap();
+        for action in ["add", "remove"] {
+            let score = &mut json["children"]["scoreboard"]["children"]["players"]["children"]
+                [action]["children"]["targets"]["children"]["objective"]["children"]["score"];
+            score.as_object_mut().unwrap().remove("properties");
+        }
+        Arc::new(crate::load_tree(&json.to_string()).unwrap())
+    }
+
+    #[test]
+    fn out_of_bounds_score_is_only_a_parse_error() {
+        let (errors, lints) = lint(test_util::tree(), "scoreboard players add x o -5");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("Number out of bounds"), "{errors:?}");
+        assert!(lints.is_empty());
+    }
+
+    #[test]
+    fn negative_score_suggests_opposite_command() {
+        let tree = tree_with_negative_scores();
+        let (errors, lints) = lint(Arc::clone(&tree), "scoreboard players add x o -5");
+        assert!(errors.is_empty());
+        assert_eq!(lints, ["Negative score in `scoreboard players add`"]);
+
+        let (_, lints) = lint(
+            tree,
+            "scoreboard players remove x o -5\nscoreboard players add x o 5",
+        );
+        assert_eq!(lints, ["Negative score in `scoreboard players remove`"]);
+    }
+
+    fn wildcard_sources(text: &str) -> Vec<Span> {
+        let parsed = test_util::parse(text);
+        assert!(parsed.diagnostics().is_empty());
+        let lints = lint_wildcard_operation_source(parsed.result.as_ref().unwrap(), &parsed.tree);
+        assert!(lints.iter().all(|lint| {
+            lint.level() == Level::Warn && lint.message() == "Wildcard used as operation source"
+        }));
+        lints.iter().map(Diagnostic::span).collect()
+    }
+
+    #[test]
+    fn wildcard_operation_source() {
+        assert_eq!(
+            wildcard_sources("scoreboard players operation a o += * o"),
+            [Span::new(36, 37)]
+        );
+        assert_eq!(
+            wildcard_sources("execute as @a run\n    scoreboard players operation @s o = * o"),
+            [Span::new(58, 59)]
+        );
+    }
+
+    #[test]
+    fn wildcard_operation_target_is_allowed() {
+        assert!(wildcard_sources("scoreboard players operation * o += a o").is_empty());
+        assert!(wildcard_sources("scoreboard players operation a o += @a o").is_empty());
+        // Only the parsed score holder counts, not a quoted or longer name
+        assert!(wildcard_sources("scoreboard players operation a o += *x o").is_empty());
+    }
+}
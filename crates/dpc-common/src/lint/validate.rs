@@ -0,0 +1,142 @@
+use rustc_hash::FxHashMap;
+
+use crate::{
+    diagnostics::Diagnostic,
+    intern::StaticInterner,
+    parse::cst::{self, Argument, ArgumentValue, ArgumentValueKind, Block},
+    span::Span,
+};
+
+type Validator =
+    Box<dyn Fn(&ArgumentValue, Span, &StaticInterner, &mut Vec<Diagnostic>) + Send + Sync>;
+
+/// A set of project-specific rules for argument values, e.g. naming conventions.
+///
+/// Validators are registered for a kind of argument value and are called for every argument of
+/// that kind, including arguments in nested blocks.
+#[derive(Default)]
+pub struct ValidationContext {
+    validators: FxHashMap<ArgumentValueKind, Vec<Validator>>,
+}
+
+impl ValidationContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(
+        &mut self,
+        kind: ArgumentValueKind,
+        validator: impl Fn(&ArgumentValue, Span, &StaticInterner, &mut Vec<Diagnostic>)
+        + Send
+        + Sync
+        + 'static,
+    ) {
+        self.validators
+            .entry(kind)
+            .or_default()
+            .push(Box::new(validator));
+    }
+
+    pub fn validate(&self, block: &Block, interner: &StaticInterner) -> Vec<Diagnostic> {
+        struct ValidationVisitor<'a> {
+            validation: &'a ValidationContext,
+            interner: &'a StaticInterner,
+            diagnostics: Vec<Diagnostic>,
+        }
+
+        impl cst::Visitor for ValidationVisitor<'_> {
+            fn visit_argument(&mut self, argument: &Argument) {
+                let validators = self.validation.validators.get(&argument.value.kind());
+                for validator in validators.into_iter().flatten() {
+                    validator(
+                        &argument.value,
+                        argument.span,
+                        self.interner,
+                        &mut self.diagnostics,
+                    );
+                }
+                cst::walk_argument(self, argument);
+            }
+        }
+
+        let mut visitor = ValidationVisitor {
+            validation: self,
+            interner,
+            diagnostics: Vec::new(),
+        };
+        cst::walk_block(&mut visitor, block);
+        visitor.diagnostics
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::ValidationContext;
+    use crate::{
+        diagnostics::Diagnostic,
+        intern::Interner,
+        parse::cst::{ArgumentValue, ArgumentValueKind},
+        span::Span,
+        test_util,
+    };
+
+    fn uppercase_objectives() -> ValidationContext {
+        let mut validation = ValidationContext::new();
+        validation.register(
+            ArgumentValueKind::Objective,
+            |value, span, interner, diagnostics| {
+                let ArgumentValue::Objective(objective) = value else {
+                    return;
+                };
+                let name = interner.resolve(objective.name).unwrap();
+                if name.chars().any(char::is_uppercase) {
+                    diagnostics.push(Diagnostic::warn(
+                        span,
+                        format!("Uppercase objective {name}"),
+                    ));
+                }
+            },
+        );
+        validation
+    }
+
+    #[test]
+    fn validator_fires_in_nested_blocks() {
+        let text = "\
+scoreboard players set @s Top 1
+execute as @a run
+    scoreboard players set @s ok 1
+    execute if entity @s run
+        scoreboard players add @s Nested 1
+";
+        let parsed = test_util::parse(text);
+        assert!(parsed.diagnostics().is_empty());
+        let diagnostics =
+            uppercase_objectives().validate(parsed.result.as_ref().unwrap(), &parsed.interner);
+        let found: Vec<_> = diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.message(), &text[diagnostic.span().as_range()]))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("Uppercase objective Top", "Top"),
+                ("Uppercase objective Nested", "Nested")
+            ]
+        );
+    }
+
+    #[test]
+    fn validators_only_receive_their_kind() {
+        let mut validation = ValidationContext::new();
+        validation.register(ArgumentValueKind::Integer, |value, span, _, diagnostics| {
+            assert!(matches!(value, ArgumentValue::Integer(_)));
+            diagnostics.push(Diagnostic::warn(span, "integer"));
+        });
+        let parsed = test_util::parse("execute run\n    scoreboard players set @s Obj 5\n");
+        let diagnostics = validation.validate(parsed.result.as_ref().unwrap(), &parsed.interner);
+        let spans: Vec<_> = diagnostics.iter().map(Diagnostic::span).collect();
+        assert_eq!(spans, [Span::new(46, 47)]);
+    }
+}
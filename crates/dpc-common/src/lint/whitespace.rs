@@ -0,0 +1,59 @@
+use crate::{
+    diagnostics::{Diagnostic, Label},
+    span::Span,
+};
+
+/// Warns about spaces and tabs at the end of lines, including lines which consist only of
+/// whitespace.
+pub fn lint_trailing_whitespace(source: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    let mut line_start = 0;
+    for line in source.split_inclusive('\n') {
+        let content = line.trim_end_matches(['\n', '\r']);
+        let trimmed = content.trim_end_matches([' ', '\t']);
+        if trimmed.len() < content.len() {
+            let span = Span::new(line_start + trimmed.len(), line_start + content.len());
+            diagnostics.push(
+                Diagnostic::warn(span, "Trailing whitespace")
+                    .with_label(Label::new(span, "Remove this whitespace")),
+            );
+        }
+        line_start += line.len();
+    }
+
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::lint_trailing_whitespace;
+    use crate::{diagnostics::Diagnostic, span::Span};
+
+    fn trailing(source: &str) -> Vec<Span> {
+        let lints = lint_trailing_whitespace(source);
+        assert!(
+            lints
+                .iter()
+                .all(|lint| lint.message() == "Trailing whitespace")
+        );
+        lints.iter().map(Diagnostic::span).collect()
+    }
+
+    #[test]
+    fn trailing_spaces_after_a_command() {
+        assert_eq!(trailing("say hi  \nsay bye\n"), [Span::new(6, 8)]);
+        assert_eq!(
+            trailing("say hi \t\r\nsay bye \t"),
+            [Span::new(6, 8), Span::new(17, 19)]
+        );
+        // Lines with only whitespace are reported as a whole
+        assert_eq!(trailing("say hi\n   \nsay bye"), [Span::new(7, 10)]);
+    }
+
+    #[test]
+    fn clean_lines_are_not_reported() {
+        assert!(trailing("say hi\nexecute run\n    say bye\r\n\n").is_empty());
+        assert!(trailing("").is_empty());
+    }
+}
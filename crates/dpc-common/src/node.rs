@@ -3,6 +3,7 @@ use std::fmt;
 use crate::{parse::argument::Argument, smallstring::SmallString};
 
 #[derive(Clone)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub enum NodeKind {
     Literal(SmallString),
     Argument { name: SmallString, arg: Argument },
@@ -20,6 +21,7 @@ impl fmt::Debug for NodeKind {
 }
 
 #[derive(Clone)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub struct Node {
     pub kind: NodeKind,
     pub executable: bool,
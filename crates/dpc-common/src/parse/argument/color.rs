@@ -1,9 +1,29 @@
+use std::fmt;
+
 use super::ParseArgContext;
-use crate::parse::errors::{InvalidColorError, ParseError};
+use crate::parse::errors::{
+    InvalidColorError, InvalidHexColorError, InvalidHexColorErrorKind, ParseError,
+};
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct Color {
-    pub color: Option<ChatColor>,
+    pub color: Option<ColorValue>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorValue {
+    Named(ChatColor),
+    /// A `#RRGGBB` color.
+    Hex(u32),
+}
+
+impl fmt::Display for ColorValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Named(color) => f.write_str(color.as_str()),
+            Self::Hex(rgb) => write!(f, "#{rgb:06x}"),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -83,6 +103,24 @@ pub fn parse(ctx: &mut ParseArgContext<'_, '_>) -> Color {
         .reader
         .parse_with_span(|reader| reader.read_until(char::is_whitespace));
 
+    if let Some(digits) = name.strip_prefix('#') {
+        let kind = if digits.len() != 6 {
+            InvalidHexColorErrorKind::WrongLength
+        } else if !digits.chars().all(|chr| chr.is_ascii_hexdigit()) {
+            InvalidHexColorErrorKind::InvalidDigits
+        } else {
+            let rgb = u32::from_str_radix(digits, 16).unwrap();
+            return Color {
+                color: Some(ColorValue::Hex(rgb)),
+            };
+        };
+        ctx.error(ParseError::InvalidHexColor(InvalidHexColorError {
+            span: span.into(),
+            kind,
+        }));
+        return Color { color: None };
+    }
+
     let color = ChatColor::from_string(name);
 
     if color.is_none() {
@@ -91,5 +129,37 @@ pub fn parse(ctx: &mut ParseArgContext<'_, '_>) -> Color {
         }));
     }
 
-    Color { color }
+    Color {
+        color: color.map(ColorValue::Named),
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::{ChatColor, Color, ColorValue};
+    use crate::{parse::cst::ArgumentValue, test_util};
+
+    fn parse_color(color: &str) -> (Option<ColorValue>, usize) {
+        let parsed = test_util::parse(&format!("team modify t color {color}"));
+        let arg = test_util::find_arg(&parsed, |value| matches!(value, ArgumentValue::Color(_)));
+        let ArgumentValue::Color(Color { color }) = arg.value else {
+            unreachable!();
+        };
+        (color, parsed.diagnostics().len())
+    }
+
+    #[test]
+    fn named_and_hex_colors() {
+        assert_eq!(
+            parse_color("red"),
+            (Some(ColorValue::Named(ChatColor::Red)), 0)
+        );
+        assert_eq!(parse_color("#ff8800"), (Some(ColorValue::Hex(0xff8800)), 0));
+    }
+
+    #[test]
+    fn invalid_hex_colors() {
+        assert_eq!(parse_color("#fff"), (None, 1));
+        assert_eq!(parse_color("#gggggg"), (None, 1));
+    }
 }
@@ -10,13 +10,13 @@ use crate::{
     span::Span,
 };
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct WorldCoordinate {
     pub value: Double,
     pub relative: bool,
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub enum Coordinates<const N: usize> {
     World([WorldCoordinate; N]),
     Local([Double; N]),
@@ -26,9 +26,11 @@ fn parse_local_coordinates<const N: usize>(ctx: &mut ParseArgContext<'_, '_>) ->
     let start = ctx.reader.get_pos();
 
     let mut coords = [Double::ZERO; N];
+    let mut mixed = None;
 
     for coord in &mut coords {
         ctx.reader.skip_whitespace();
+        let coord_start = ctx.reader.get_pos();
 
         if !ctx.reader.has_more() {
             ctx.error(ParseError::IncompleteLocalCoordinates(
@@ -39,32 +41,43 @@ fn parse_local_coordinates<const N: usize>(ctx: &mut ParseArgContext<'_, '_>) ->
             break;
         }
 
-        if ctx.reader.peek() == Some('^') {
-            ctx.reader.advance();
-        } else if ctx.reader.peek() == Some('~') {
-            ctx.error(ParseError::MixedCoordinates(MixedCoordiantesError {
-                span: Span::new(ctx.reader.get_pos(), ctx.reader.get_next_pos()),
-            }));
-            ctx.reader.advance();
-        } else {
-            ctx.error(ParseError::ExpectedLocalCoordinate(
-                ExpectedLocalCoordinateError {
-                    span: Span::new(ctx.reader.get_pos(), ctx.reader.get_next_pos()),
-                },
-            ));
+        let mut is_mixed = false;
+        if let Err(span) = ctx.reader.expect('^') {
+            // Relative and absolute world components are merged into one mixed coordinates error
+            let is_absolute = ctx
+                .reader
+                .peek()
+                .is_some_and(|chr| chr.is_ascii_digit() || matches!(chr, '-' | '+' | '.'));
+            if ctx.reader.eat('~') || is_absolute {
+                is_mixed = true;
+            } else {
+                ctx.error(ParseError::ExpectedLocalCoordinate(
+                    ExpectedLocalCoordinateError { span },
+                ));
+            }
         }
 
         if ctx.reader.peek().is_some_and(|chr| !chr.is_whitespace()) {
             *coord = parse_double(ctx, f64::MIN, f64::MAX);
         }
+
+        if is_mixed {
+            extend_mixed(&mut mixed, coord_start, ctx.reader.get_pos());
+        }
     }
 
+    report_mixed(ctx, mixed);
     Coordinates::Local(coords)
 }
 
+/// Parses world coordinates, using `number_parser` for the number of each component. Absolute
+/// components must lie within `min..=max`. Relative components are offsets, so the bounds are not
+/// applied to them.
 fn parse_world_coordinates<const N: usize>(
     ctx: &mut ParseArgContext<'_, '_>,
-    mut number_parser: impl FnMut(&mut ParseArgContext<'_, '_>, bool) -> Double,
+    min: f64,
+    max: f64,
+    mut number_parser: impl FnMut(&mut ParseArgContext<'_, '_>, bool, f64, f64) -> Double,
 ) -> Coordinates<N> {
     let start = ctx.reader.get_pos();
 
@@ -72,9 +85,11 @@ fn parse_world_coordinates<const N: usize>(
         value: Double::ZERO,
         relative: false,
     }; N];
+    let mut mixed = None;
 
     for coord in &mut coords {
         ctx.reader.skip_whitespace();
+        let coord_start = ctx.reader.get_pos();
 
         if !ctx.reader.has_more() {
             ctx.error(ParseError::IncompleteLocalCoordinates(
@@ -85,53 +100,213 @@ fn parse_world_coordinates<const N: usize>(
             break;
         }
 
-        if ctx.reader.peek() == Some('~') {
+        let is_mixed = ctx.reader.peek() == Some('^');
+        if ctx.reader.eat('~') {
             coord.relative = true;
-            ctx.reader.advance();
-        } else if ctx.reader.peek() == Some('^') {
-            ctx.error(ParseError::MixedCoordinates(MixedCoordiantesError {
-                span: Span::new(ctx.reader.get_pos(), ctx.reader.get_next_pos()),
-            }));
+        } else if is_mixed {
             ctx.reader.advance();
         }
 
         if ctx.reader.peek().is_some_and(|chr| !chr.is_whitespace()) || !coord.relative {
-            coord.value = number_parser(ctx, coord.relative);
+            coord.value = match coord.relative {
+                true => number_parser(ctx, true, f64::MIN, f64::MAX),
+                false => number_parser(ctx, false, min, max),
+            };
+        }
+
+        if is_mixed {
+            extend_mixed(&mut mixed, coord_start, ctx.reader.get_pos());
         }
     }
 
+    report_mixed(ctx, mixed);
     Coordinates::World(coords)
 }
 
-pub fn parse_block_pos(ctx: &mut ParseArgContext<'_, '_>) -> Coordinates<3> {
+/// Extends the span of the components with the wrong kind of coordinate by another component.
+/// They are reported as a single error, instead of one error per component.
+fn extend_mixed(mixed: &mut Option<Span>, start: usize, end: usize) {
+    *mixed = Some(match *mixed {
+        Some(span) => Span::new(span.start, end),
+        None => Span::new(start, end),
+    });
+}
+
+fn report_mixed(ctx: &mut ParseArgContext<'_, '_>, mixed: Option<Span>) {
+    if let Some(span) = mixed {
+        ctx.error(ParseError::MixedCoordinates(MixedCoordiantesError { span }));
+    }
+}
+
+/// Parses a component of a block position. Absolute components are integers, relative ones may
+/// be fractional.
+fn parse_block_component(
+    ctx: &mut ParseArgContext<'_, '_>,
+    relative: bool,
+    min: f64,
+    max: f64,
+) -> Double {
+    match relative {
+        true => parse_double(ctx, min, max),
+        false => {
+            // Float to integer casts saturate, so unbounded ranges map to the full i32 range
+            let integer = parse_integer(ctx, min.ceil() as i32, max.floor() as i32);
+            Double {
+                value: integer.value.map(|value| value as f64),
+            }
+        }
+    }
+}
+
+pub fn parse_block_pos(ctx: &mut ParseArgContext<'_, '_>, min: f64, max: f64) -> Coordinates<3> {
     match ctx.reader.peek() {
         Some('^') => parse_local_coordinates(ctx),
-        _ => parse_world_coordinates(ctx, |ctx, relative| match relative {
-            true => parse_double(ctx, f64::MIN, f64::MAX),
-            false => {
-                let integer = parse_integer(ctx, i32::MIN, i32::MAX);
-                Double {
-                    value: integer.value.map(|value| value as f64),
-                }
-            }
-        }),
+        _ => parse_world_coordinates(ctx, min, max, parse_block_component),
     }
 }
 
-pub fn parse_vec3(ctx: &mut ParseArgContext<'_, '_>) -> Coordinates<3> {
+pub fn parse_vec3(ctx: &mut ParseArgContext<'_, '_>, min: f64, max: f64) -> Coordinates<3> {
     match ctx.reader.peek() {
         Some('^') => parse_local_coordinates(ctx),
-        _ => parse_world_coordinates(ctx, |ctx, _| parse_double(ctx, f64::MIN, f64::MAX)),
+        _ => parse_world_coordinates(ctx, min, max, |ctx, _, min, max| {
+            parse_double(ctx, min, max)
+        }),
     }
 }
 
-pub fn parse_vec2(ctx: &mut ParseArgContext<'_, '_>) -> Coordinates<2> {
+/// Parses two double components. Unlike column positions, these are never integers, e.g. both
+/// `spreadplayers` and `worldborder center` accept `0.5 0.5`.
+pub fn parse_vec2(ctx: &mut ParseArgContext<'_, '_>, min: f64, max: f64) -> Coordinates<2> {
     match ctx.reader.peek() {
         Some('^') => parse_local_coordinates(ctx),
-        _ => parse_world_coordinates(ctx, |ctx, _| parse_double(ctx, f64::MIN, f64::MAX)),
+        _ => parse_world_coordinates(ctx, min, max, |ctx, _, min, max| {
+            parse_double(ctx, min, max)
+        }),
     }
 }
 
-pub fn parse_column_pos(ctx: &mut ParseArgContext<'_, '_>) -> Coordinates<2> {
-    parse_world_coordinates(ctx, |ctx, _| parse_double(ctx, f64::MIN, f64::MAX))
+/// Parses the x and z components of a block position, e.g. for `forceload`. Like block positions,
+/// absolute components are integers, but local coordinates are not allowed.
+pub fn parse_column_pos(ctx: &mut ParseArgContext<'_, '_>, min: f64, max: f64) -> Coordinates<2> {
+    parse_world_coordinates(ctx, min, max, parse_block_component)
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{BuildTree, Parsed, parse::argument::ArgumentRegistry, span::Span};
+
+    /// Parses `cmd <text>` with a tree whose only argument uses the parser and properties.
+    fn parse_with(parser: &str, properties: &str, text: &str) -> Parsed {
+        let json = format!(
+            r#"{{"type": "root", "children": {{"cmd": {{"type": "literal", "children": {{
+                "pos": {{"type": "argument", "parser": "{parser}",
+                         "properties": {properties}, "executable": true}}
+            }}}}}}}}"#
+        );
+        let mut tree = BuildTree::default();
+        crate::import::import(&json, &mut tree, &ArgumentRegistry::default()).unwrap();
+        let tree = Arc::new(tree.into_parsing_tree());
+        crate::parse_source(tree, None, format!("cmd {text}"))
+    }
+
+    fn errors(parsed: &Parsed) -> Vec<(String, Span)> {
+        parsed
+            .diagnostics()
+            .iter()
+            .map(|diagnostic| (diagnostic.message().to_owned(), diagnostic.span()))
+            .collect()
+    }
+
+    #[test]
+    fn bounded_double_coordinates() {
+        let bounds = r#"{"min": -10.0, "max": 10.0}"#;
+        assert!(errors(&parse_with("minecraft:vec3", bounds, "1 -10 10.0")).is_empty());
+
+        let parsed = parse_with("minecraft:vec3", bounds, "1 10.5 -11");
+        let message = "Number out of bounds: must be at least -10 and at most 10";
+        assert_eq!(
+            errors(&parsed),
+            [
+                (message.to_owned(), Span::new(6, 10)),
+                (message.to_owned(), Span::new(11, 14)),
+            ]
+        );
+
+        let parsed = parse_with("minecraft:vec2", bounds, "20 0");
+        assert_eq!(errors(&parsed), [(message.to_owned(), Span::new(4, 6))]);
+    }
+
+    #[test]
+    fn bounds_do_not_apply_to_offsets() {
+        let bounds = r#"{"min": -10.0, "max": 10.0}"#;
+        assert!(errors(&parse_with("minecraft:vec3", bounds, "~20 ~-20 ~")).is_empty());
+        assert!(errors(&parse_with("minecraft:vec3", bounds, "^20 ^ ^-20")).is_empty());
+    }
+
+    #[test]
+    fn bounded_integer_coordinates() {
+        let bounds = r#"{"min": 0, "max": 255}"#;
+        assert!(errors(&parse_with("minecraft:block_pos", bounds, "0 255 ~300")).is_empty());
+
+        let parsed = parse_with("minecraft:block_pos", bounds, "0 256 0");
+        let message = "Number out of bounds: must be at least 0 and at most 255";
+        assert_eq!(errors(&parsed), [(message.to_owned(), Span::new(6, 9))]);
+
+        let parsed = parse_with("minecraft:column_pos", bounds, "-1 ~-1");
+        assert_eq!(errors(&parsed), [(message.to_owned(), Span::new(4, 6))]);
+    }
+
+    #[test]
+    fn coordinates_are_unbounded_by_default() {
+        let parsed = parse_with("minecraft:vec3", "{}", "-30000000 0 30000000.5");
+        assert!(errors(&parsed).is_empty());
+    }
+
+    #[test]
+    fn mixed_components_are_reported_once() {
+        let message = "Cannot mix world and local coordinates".to_owned();
+        let mixed = |text| errors(&parse_with("minecraft:vec3", "{}", text));
+        assert_eq!(mixed("^1 ~ 2"), [(message.clone(), Span::new(7, 10))]);
+        assert_eq!(mixed("^1 ~2 ~3"), [(message.clone(), Span::new(7, 12))]);
+        assert_eq!(mixed("^1 ~2 ^3"), [(message.clone(), Span::new(7, 9))]);
+        assert_eq!(mixed("~1 ^2 ^3"), [(message.clone(), Span::new(7, 12))]);
+        assert_eq!(mixed("1 ^2 3"), [(message.clone(), Span::new(6, 8))]);
+
+        // Other text in local coordinates is not a world coordinate
+        assert_eq!(
+            mixed("^1 x ^3")[0],
+            ("Expected local coordinate".to_owned(), Span::new(7, 8))
+        );
+    }
+
+    #[test]
+    fn consistent_coordinates_are_valid() {
+        for text in ["^1 ^2 ^3", "^ ^ ^", "~1 2 ~", "1 -2 .5"] {
+            assert!(
+                errors(&parse_with("minecraft:vec3", "{}", text)).is_empty(),
+                "{text}"
+            );
+        }
+    }
+
+    #[test]
+    fn absolute_block_components_are_integers() {
+        let invalid = |span| [("Invalid number".to_owned(), span)];
+        let block_pos = |text| errors(&parse_with("minecraft:block_pos", "{}", text));
+        assert_eq!(block_pos("1.5 2 3"), invalid(Span::new(4, 7)));
+        assert_eq!(block_pos("1 2 -.5"), invalid(Span::new(8, 11)));
+        assert!(block_pos("~1.5 2 ~-.5").is_empty());
+
+        let column_pos = |text| errors(&parse_with("minecraft:column_pos", "{}", text));
+        assert_eq!(column_pos("1 1.5"), invalid(Span::new(6, 9)));
+        assert!(column_pos("~1.5 -2").is_empty());
+    }
+
+    #[test]
+    fn double_components_may_be_fractional() {
+        let parsed = parse_with("minecraft:vec2", "{}", "1.5 -.5");
+        assert!(errors(&parsed).is_empty());
+    }
 }
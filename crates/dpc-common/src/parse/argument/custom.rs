@@ -0,0 +1,13 @@
+use std::fmt;
+
+use super::ParseArgContext;
+use crate::parse::{cst, errors::ParseError};
+
+/// An argument parser provided by the user of this crate, e.g. for parsers added by mods.
+///
+/// The [`fmt::Debug`] implementation is used to describe the argument in diagnostics.
+pub trait CustomArgument: fmt::Debug + Send + Sync {
+    /// Parses the argument. Parsers usually produce an [`cst::ArgumentValue::Custom`], but may
+    /// return any value.
+    fn parse(&self, ctx: &mut ParseArgContext<'_, '_>) -> Result<cst::ArgumentValue, ParseError>;
+}
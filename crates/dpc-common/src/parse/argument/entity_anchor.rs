@@ -0,0 +1,104 @@
+use std::fmt;
+
+use super::ParseArgContext;
+use crate::{
+    parse::errors::{InvalidEntityAnchorError, ParseError},
+    span::Span,
+};
+
+/// The part of an entity to face or to position at, as for `execute anchored`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EntityAnchor {
+    /// The anchor, or `None` if it is invalid.
+    pub kind: Option<EntityAnchorKind>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntityAnchorKind {
+    Eyes,
+    Feet,
+}
+
+impl EntityAnchorKind {
+    pub const ALL: [Self; 2] = [Self::Eyes, Self::Feet];
+
+    pub fn from_string(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == s)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Eyes => "eyes",
+            Self::Feet => "feet",
+        }
+    }
+}
+
+impl fmt::Display for EntityAnchorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+pub fn parse(ctx: &mut ParseArgContext<'_, '_>) -> EntityAnchor {
+    let (range, name) = ctx
+        .reader
+        .parse_with_span(|reader| reader.read_until(char::is_whitespace));
+    let span = Span::from(range);
+
+    let kind = EntityAnchorKind::from_string(name);
+    if kind.is_none() {
+        ctx.error(ParseError::InvalidEntityAnchor(InvalidEntityAnchorError {
+            span,
+        }));
+    }
+    EntityAnchor { kind, span }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::{EntityAnchor, EntityAnchorKind};
+    use crate::{Parsed, parse::cst::ArgumentValue, span::Span, test_util};
+
+    fn anchor(parsed: &Parsed) -> EntityAnchor {
+        let arg = test_util::find_arg(parsed, |value| {
+            matches!(value, ArgumentValue::EntityAnchor(_))
+        });
+        let ArgumentValue::EntityAnchor(anchor) = arg.value else {
+            unreachable!();
+        };
+        anchor
+    }
+
+    #[test]
+    fn valid_anchor() {
+        let parsed = test_util::parse("execute anchored eyes run say hi");
+        assert!(parsed.diagnostics().is_empty());
+        assert_eq!(
+            anchor(&parsed),
+            EntityAnchor {
+                kind: Some(EntityAnchorKind::Eyes),
+                span: Span::new(17, 21),
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_anchor() {
+        let parsed = test_util::parse("execute anchored head run say hi");
+        let diagnostics = parsed.diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message(), "Invalid entity anchor");
+        assert_eq!(diagnostics[0].span(), Span::new(17, 21));
+        assert_eq!(anchor(&parsed).kind, None);
+    }
+
+    #[test]
+    fn span_follows_edits() {
+        let mut parsed = test_util::parse("execute anchored feet run say hi\n");
+        parsed.edit(0..0, "say x\n");
+        assert!(parsed.diagnostics().is_empty());
+        assert_eq!(anchor(&parsed).span, Span::new(23, 27));
+    }
+}
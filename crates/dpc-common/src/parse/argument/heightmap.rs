@@ -0,0 +1,109 @@
+use std::fmt;
+
+use super::ParseArgContext;
+use crate::{
+    parse::errors::{InvalidHeightmapError, ParseError},
+    span::Span,
+};
+
+/// A heightmap, as for `execute positioned over`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Heightmap {
+    /// The kind of heightmap, or `None` if it is invalid.
+    pub kind: Option<HeightmapKind>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeightmapKind {
+    WorldSurface,
+    MotionBlocking,
+    MotionBlockingNoLeaves,
+    OceanFloor,
+}
+
+impl HeightmapKind {
+    pub const ALL: [Self; 4] = [
+        Self::WorldSurface,
+        Self::MotionBlocking,
+        Self::MotionBlockingNoLeaves,
+        Self::OceanFloor,
+    ];
+
+    pub fn from_string(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == s)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::WorldSurface => "world_surface",
+            Self::MotionBlocking => "motion_blocking",
+            Self::MotionBlockingNoLeaves => "motion_blocking_no_leaves",
+            Self::OceanFloor => "ocean_floor",
+        }
+    }
+}
+
+impl fmt::Display for HeightmapKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+pub fn parse(ctx: &mut ParseArgContext<'_, '_>) -> Heightmap {
+    let (range, name) = ctx
+        .reader
+        .parse_with_span(|reader| reader.read_until(char::is_whitespace));
+    let span = Span::from(range);
+
+    let kind = HeightmapKind::from_string(name);
+    if kind.is_none() {
+        ctx.error(ParseError::InvalidHeightmap(InvalidHeightmapError { span }));
+    }
+    Heightmap { kind, span }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::{Heightmap, HeightmapKind};
+    use crate::{Parsed, parse::cst::ArgumentValue, span::Span, test_util};
+
+    fn heightmap(parsed: &Parsed) -> Heightmap {
+        let arg = test_util::find_arg(parsed, |value| matches!(value, ArgumentValue::Heightmap(_)));
+        let ArgumentValue::Heightmap(heightmap) = arg.value else {
+            unreachable!();
+        };
+        heightmap
+    }
+
+    #[test]
+    fn valid_heightmap() {
+        let parsed = test_util::parse("execute positioned over world_surface run say hi");
+        assert!(parsed.diagnostics().is_empty());
+        assert_eq!(
+            heightmap(&parsed),
+            Heightmap {
+                kind: Some(HeightmapKind::WorldSurface),
+                span: Span::new(24, 37),
+            }
+        );
+    }
+
+    #[test]
+    fn invalid_heightmap() {
+        let parsed = test_util::parse("execute positioned over top run say hi");
+        let diagnostics = parsed.diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message(), "Invalid heightmap");
+        assert_eq!(diagnostics[0].span(), Span::new(24, 27));
+        assert_eq!(heightmap(&parsed).kind, None);
+    }
+
+    #[test]
+    fn span_follows_edits() {
+        let mut parsed = test_util::parse("execute positioned over ocean_floor run say hi\n");
+        parsed.edit(0..0, "say x\n");
+        assert!(parsed.diagnostics().is_empty());
+        assert_eq!(heightmap(&parsed).span, Span::new(30, 41));
+    }
+}
@@ -0,0 +1,226 @@
+use super::ParseArgContext;
+use crate::{
+    intern::{Interner, Symbol},
+    parse::errors::{ParseError, UnknownItemSlotError},
+    span::Span,
+};
+
+/// An item slot like `weapon.mainhand` or `container.5`, or a group of slots like `container.*`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ItemSlot {
+    pub name: Symbol,
+    pub span: Span,
+    /// The category of the slot, or `None` if the slot is unknown.
+    pub category: Option<ItemSlotCategory>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemSlotCategory {
+    Container,
+    Hotbar,
+    Inventory,
+    EnderChest,
+    Villager,
+    Horse,
+    Weapon,
+    Armor,
+    Player,
+    Contents,
+    Saddle,
+}
+
+impl ItemSlotCategory {
+    pub const ALL: [Self; 11] = [
+        Self::Container,
+        Self::Hotbar,
+        Self::Inventory,
+        Self::EnderChest,
+        Self::Villager,
+        Self::Horse,
+        Self::Weapon,
+        Self::Armor,
+        Self::Player,
+        Self::Contents,
+        Self::Saddle,
+    ];
+
+    /// Returns the prefix shared by all slots of the category.
+    pub fn prefix(self) -> &'static str {
+        match self {
+            Self::Container => "container",
+            Self::Hotbar => "hotbar",
+            Self::Inventory => "inventory",
+            Self::EnderChest => "enderchest",
+            Self::Villager => "villager",
+            Self::Horse => "horse",
+            Self::Weapon => "weapon",
+            Self::Armor => "armor",
+            Self::Player => "player",
+            Self::Contents => "contents",
+            Self::Saddle => "saddle",
+        }
+    }
+}
+
+/// Slots which are numbered from 0, along with the number of slots.
+const INDEXED_SLOTS: &[(&str, ItemSlotCategory, u32)] = &[
+    ("container", ItemSlotCategory::Container, 54),
+    ("hotbar", ItemSlotCategory::Hotbar, 9),
+    ("inventory", ItemSlotCategory::Inventory, 27),
+    ("enderchest", ItemSlotCategory::EnderChest, 27),
+    ("villager", ItemSlotCategory::Villager, 8),
+    ("horse", ItemSlotCategory::Horse, 15),
+    ("player.crafting", ItemSlotCategory::Player, 4),
+];
+
+const NAMED_SLOTS: &[(&str, ItemSlotCategory)] = &[
+    ("weapon", ItemSlotCategory::Weapon),
+    ("weapon.mainhand", ItemSlotCategory::Weapon),
+    ("weapon.offhand", ItemSlotCategory::Weapon),
+    ("armor.head", ItemSlotCategory::Armor),
+    ("armor.chest", ItemSlotCategory::Armor),
+    ("armor.legs", ItemSlotCategory::Armor),
+    ("armor.feet", ItemSlotCategory::Armor),
+    ("armor.body", ItemSlotCategory::Armor),
+    ("horse.saddle", ItemSlotCategory::Horse),
+    ("horse.chest", ItemSlotCategory::Horse),
+    ("player.cursor", ItemSlotCategory::Player),
+    ("contents", ItemSlotCategory::Contents),
+    ("saddle", ItemSlotCategory::Saddle),
+];
+
+/// Groups of slots, which are only allowed where several slots may be given.
+const SLOT_GROUPS: &[(&str, ItemSlotCategory)] = &[
+    ("container.*", ItemSlotCategory::Container),
+    ("hotbar.*", ItemSlotCategory::Hotbar),
+    ("inventory.*", ItemSlotCategory::Inventory),
+    ("enderchest.*", ItemSlotCategory::EnderChest),
+    ("villager.*", ItemSlotCategory::Villager),
+    ("horse.*", ItemSlotCategory::Horse),
+    ("weapon.*", ItemSlotCategory::Weapon),
+    ("armor.*", ItemSlotCategory::Armor),
+    ("player.crafting.*", ItemSlotCategory::Player),
+];
+
+/// Returns the category of the slot, or `None` if there is no such slot.
+fn resolve_slot(name: &str, allow_groups: bool) -> Option<ItemSlotCategory> {
+    let find = |slots: &[(&str, ItemSlotCategory)]| {
+        slots
+            .iter()
+            .find(|(slot, _)| *slot == name)
+            .map(|(_, category)| *category)
+    };
+
+    if let Some(category) = find(NAMED_SLOTS) {
+        return Some(category);
+    }
+    if allow_groups && let Some(category) = find(SLOT_GROUPS) {
+        return Some(category);
+    }
+
+    let (prefix, index) = name.rsplit_once('.')?;
+    // Indices are plain decimal numbers, so signs and leading zeros are not allowed
+    if index.is_empty()
+        || !index.chars().all(|chr| chr.is_ascii_digit())
+        || index.len() > 1 && index.starts_with('0')
+    {
+        return None;
+    }
+    let index: u32 = index.parse().ok()?;
+    INDEXED_SLOTS
+        .iter()
+        .find(|(slot, _, count)| *slot == prefix && index < *count)
+        .map(|(_, category, _)| *category)
+}
+
+/// Parses an item slot, or a group of slots if `allow_groups` is set, as for `item_slots`.
+pub fn parse_item_slot(ctx: &mut ParseArgContext<'_, '_>, allow_groups: bool) -> ItemSlot {
+    let (range, name) = ctx
+        .reader
+        .parse_with_span(|reader| reader.read_until(char::is_whitespace));
+    let span = Span::from(range);
+
+    let category = resolve_slot(name, allow_groups);
+    if category.is_none() {
+        ctx.error(ParseError::UnknownItemSlot(UnknownItemSlotError {
+            span,
+            allow_groups,
+        }));
+    }
+
+    ItemSlot {
+        name: ctx.interner.intern(name),
+        span,
+        category,
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::{ItemSlot, ItemSlotCategory};
+    use crate::{intern::Interner, parse::cst::ArgumentValue, span::Span, test_util};
+
+    /// Parses the first slot of the command, returning its name, category, span and whether it
+    /// has errors.
+    fn parse_slot(text: &str) -> (String, Option<ItemSlotCategory>, Span, bool) {
+        let parsed = test_util::parse(text);
+        let arg = test_util::find_arg(&parsed, |value| matches!(value, ArgumentValue::ItemSlot(_)));
+        let ArgumentValue::ItemSlot(ItemSlot {
+            name,
+            span,
+            category,
+        }) = arg.value
+        else {
+            unreachable!();
+        };
+        let name = parsed.interner.resolve(name).unwrap().to_owned();
+        (name, category, span, arg.has_errors())
+    }
+
+    #[test]
+    fn known_slots() {
+        let (name, category, span, has_errors) =
+            parse_slot("item replace entity @s weapon.mainhand from entity @p container.0");
+        assert_eq!(name, "weapon.mainhand");
+        assert_eq!(category, Some(ItemSlotCategory::Weapon));
+        assert_eq!(span, Span::new(23, 38));
+        assert!(!has_errors);
+
+        let (_, category, _, has_errors) =
+            parse_slot("item replace entity @s container.0 from entity @p weapon");
+        assert_eq!(category, Some(ItemSlotCategory::Container));
+        assert!(!has_errors);
+    }
+
+    #[test]
+    fn slot_groups_are_only_valid_for_item_slots() {
+        let (_, category, _, has_errors) = parse_slot("execute if items entity @s container.*");
+        assert_eq!(category, Some(ItemSlotCategory::Container));
+        assert!(!has_errors);
+
+        let (_, _, _, has_errors) =
+            parse_slot("item replace entity @s container.* from entity @p container.0");
+        assert!(has_errors);
+    }
+
+    #[test]
+    fn unknown_slot() {
+        let (name, category, _, has_errors) =
+            parse_slot("item replace entity @s pocket.1 from entity @p container.0");
+        assert_eq!(name, "pocket.1");
+        assert_eq!(category, None);
+        assert!(has_errors);
+    }
+
+    #[test]
+    fn slot_span_follows_edits() {
+        let mut parsed =
+            test_util::parse("item replace entity @s weapon.mainhand from entity @p container.0");
+        parsed.edit(0..0, "say x\n");
+        let arg = test_util::find_arg(&parsed, |value| matches!(value, ArgumentValue::ItemSlot(_)));
+        let ArgumentValue::ItemSlot(slot) = arg.value else {
+            unreachable!();
+        };
+        assert_eq!(slot.span, Span::new(29, 44));
+    }
+}
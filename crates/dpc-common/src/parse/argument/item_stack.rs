@@ -0,0 +1,148 @@
+use super::{
+    ParseArgContext, ResourceLocation, ResourceRef,
+    nbt::{self, Nbt},
+    resource::intern_resource_location,
+};
+use crate::{
+    intern::Interner,
+    parse::errors::{ExpectedNbtToken, ParseError},
+};
+
+/// An item with optional data components, e.g. `diamond_sword[damage=5,!food]`, as for `give`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemStack {
+    pub id: ResourceRef,
+    /// The components in the order they were written. Their schema depends on the component and
+    /// is not checked.
+    pub components: Vec<ItemComponent>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ItemComponent {
+    /// `key=value`, which sets the component.
+    Set {
+        key: Option<ResourceLocation>,
+        value: Nbt,
+    },
+    /// `!key`, which removes a default component of the item.
+    Removed { key: Option<ResourceLocation> },
+}
+
+pub fn parse(ctx: &mut ParseArgContext<'_, '_>) -> Result<ItemStack, ParseError> {
+    let (span, string) = ctx
+        .reader
+        .parse_with_span(|reader| reader.read_until(|chr| chr.is_whitespace() || chr == '['));
+    let location = intern_resource_location(ctx, string, span.into());
+    let id = ResourceRef {
+        registry: ctx.interner.intern("minecraft:item"),
+        location,
+        is_tag: false,
+    };
+
+    let components = match ctx.reader.eat('[') {
+        true => nbt::parse_elements(ctx, parse_component)?,
+        false => Vec::new(),
+    };
+    Ok(ItemStack { id, components })
+}
+
+fn parse_component(ctx: &mut ParseArgContext<'_, '_>) -> Result<ItemComponent, ParseError> {
+    let removed = ctx.reader.eat('!');
+    let (span, string) = ctx.reader.parse_with_span(|reader| {
+        reader.read_until(|chr| chr.is_whitespace() || matches!(chr, '=' | ',' | ']'))
+    });
+    let key = intern_resource_location(ctx, string, span.into());
+    if removed {
+        return Ok(ItemComponent::Removed { key });
+    }
+
+    ctx.reader.skip_whitespace();
+    if !ctx.reader.eat('=') {
+        return Err(nbt::unexpected(ctx.reader, ExpectedNbtToken::Equals));
+    }
+    ctx.reader.skip_whitespace();
+    let value = nbt::parse_value(ctx)?;
+    Ok(ItemComponent::Set { key, value })
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::{ItemComponent, ItemStack};
+    use crate::{
+        intern::Interner,
+        parse::{argument::Nbt, cst::ArgumentValue},
+        test_util,
+    };
+
+    fn item_stack(text: &str) -> (ItemStack, usize, crate::Parsed) {
+        let parsed = test_util::parse(text);
+        let arg = test_util::find_arg(&parsed, |value| {
+            matches!(value, ArgumentValue::ItemStack(_))
+        });
+        let ArgumentValue::ItemStack(stack) = &arg.value else {
+            unreachable!();
+        };
+        let errors = parsed.diagnostics().len();
+        (stack.clone(), errors, parsed)
+    }
+
+    #[test]
+    fn give_item() {
+        let (stack, errors, parsed) = item_stack("give @s diamond 1");
+        assert_eq!(errors, 0);
+        let id = stack.id.location.unwrap().resolve(&parsed.interner);
+        assert_eq!(id.as_deref(), Some("minecraft:diamond"));
+        assert_eq!(stack.components, []);
+    }
+
+    #[test]
+    fn item_with_components() {
+        let (stack, errors, parsed) =
+            item_stack("give @s diamond_sword[damage=5, !food,minecraft:custom_name='x'] 1");
+        assert_eq!(errors, 0);
+        let components: Vec<_> = stack
+            .components
+            .iter()
+            .map(|component| match component {
+                ItemComponent::Set { key, value } => (
+                    key.unwrap().resolve(&parsed.interner).unwrap(),
+                    Some(value.render(&parsed.interner)),
+                ),
+                ItemComponent::Removed { key } => {
+                    (key.unwrap().resolve(&parsed.interner).unwrap(), None)
+                }
+            })
+            .collect();
+        assert_eq!(
+            components,
+            [
+                ("minecraft:damage".to_owned(), Some("5".to_owned())),
+                ("minecraft:food".to_owned(), None),
+                ("minecraft:custom_name".to_owned(), Some("\"x\"".to_owned())),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_components() {
+        for text in ["give @s diamond[damage 5]", "give @s diamond[damage=5"] {
+            let diagnostics = test_util::parse(text).diagnostics();
+            assert_eq!(diagnostics.len(), 1, "{text}");
+            assert_eq!(diagnostics[0].message(), "Invalid NBT", "{text}");
+        }
+    }
+
+    #[test]
+    fn text_component() {
+        let parsed = test_util::parse("tellraw @a \"hi\"");
+        assert!(parsed.diagnostics().is_empty());
+        let arg = test_util::find_arg(&parsed, |value| matches!(value, ArgumentValue::Nbt(_)));
+        let ArgumentValue::Nbt(Nbt::String(text)) = arg.value else {
+            panic!("expected a string, got {:?}", arg.value);
+        };
+        assert_eq!(parsed.interner.resolve(text), Some("hi"));
+
+        let parsed = test_util::parse(r#"tellraw @a [{"text":"a","bold":true},"b"]"#);
+        assert!(parsed.diagnostics().is_empty());
+    }
+}
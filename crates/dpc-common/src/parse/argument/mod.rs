@@ -1,10 +1,26 @@
-use std::fmt;
+use std::{fmt, sync::Arc};
 
 pub use angle::Angle;
-pub use color::{ChatColor, Color};
+pub use color::{ChatColor, Color, ColorValue};
 pub use coords::{Coordinates, WorldCoordinate};
+pub use custom::CustomArgument;
+pub use entity_anchor::{EntityAnchor, EntityAnchorKind};
+pub use heightmap::{Heightmap, HeightmapKind};
+pub use item_slot::{ItemSlot, ItemSlotCategory};
+pub use item_stack::{ItemComponent, ItemStack};
+pub use nbt::{Nbt, NbtCompound, NbtPath, NbtPathNode};
+pub use particle::Particle;
 pub use primitives::{Boolean, Double, Float, Integer, Text};
+pub use range::IntRange;
+#[cfg(feature = "serde")]
+pub use registry::ArgumentRegistry;
+#[cfg(feature = "serde")]
+pub(crate) use resource::split_resource_location;
+pub use resource::{FunctionRef, ResourceLocation, ResourceRef};
+pub use scoreboard::{MAX_OBJECTIVE_NAME_LEN, Objective, Operation, Operator, Team};
+pub use selector::{EntityTarget, ScoreHolder, Selector, SelectorKind, SelectorOption};
 use smallvec::SmallVec;
+pub use time::{Time, TimeUnit};
 
 use super::{Reader, cst, errors::ParseError};
 use crate::intern::StaticInterner;
@@ -12,9 +28,24 @@ use crate::intern::StaticInterner;
 mod angle;
 mod color;
 mod coords;
+mod custom;
+mod entity_anchor;
+mod heightmap;
+mod item_slot;
+mod item_stack;
+mod nbt;
+mod particle;
 mod primitives;
+mod range;
+#[cfg(feature = "serde")]
+mod registry;
+mod resource;
+mod scoreboard;
+mod selector;
+mod time;
 
 #[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub enum StringKind {
     SingleWord,
     QuotablePhrase,
@@ -22,21 +53,41 @@ pub enum StringKind {
 }
 
 #[derive(Clone)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub enum Argument {
     Bool,
-    Double { min: f64, max: f64 },
-    Float { min: f32, max: f32 },
-    Integer { min: i32, max: i32 },
+    Double {
+        min: f64,
+        max: f64,
+    },
+    Float {
+        min: f32,
+        max: f32,
+    },
+    Integer {
+        min: i32,
+        max: i32,
+    },
     String(StringKind),
     Angle,
-    BlockPos,
+    /// The bounds apply to absolute components, relative and local ones are offsets.
+    BlockPos {
+        min: f64,
+        max: f64,
+    },
     BlockPredicate,
     BlockState,
     Color,
-    ColumnPos,
+    ColumnPos {
+        min: f64,
+        max: f64,
+    },
     Component,
     Dimension,
-    Entity { single: bool, players_only: bool },
+    Entity {
+        single: bool,
+        players_only: bool,
+    },
     EntityAnchor,
     Function,
     GameProfile,
@@ -58,22 +109,43 @@ pub enum Argument {
     ObjectiveCriteria,
     Operation,
     Particle,
-    Resource { registry: Box<str> },
-    ResourceKey { registry: Box<str> },
+    Resource {
+        registry: Box<str>,
+    },
+    ResourceKey {
+        registry: Box<str>,
+    },
     ResourceLocation,
-    ResourceOrTag { registry: Box<str> },
-    ResourceOrTagKey { registry: Box<str> },
+    ResourceOrTag {
+        registry: Box<str>,
+    },
+    ResourceOrTagKey {
+        registry: Box<str>,
+    },
     Rotation,
-    ScoreHolder { single: bool },
+    ScoreHolder {
+        single: bool,
+    },
     ScoreboardSlot,
     Style,
     Swizzle,
     Team,
     TemplateMirror,
     TemplateRotation,
-    Time { min: i32 },
-    Vec2,
-    Vec3,
+    Time {
+        min: i32,
+    },
+    Vec2 {
+        min: f64,
+        max: f64,
+    },
+    Vec3 {
+        min: f64,
+        max: f64,
+    },
+    /// Custom arguments are provided by an [`ArgumentRegistry`] and cannot be cached.
+    #[cfg_attr(feature = "cache", serde(skip))]
+    Custom(Arc<dyn CustomArgument>),
 }
 
 pub struct ParseArgContext<'a, 'src> {
@@ -83,7 +155,7 @@ pub struct ParseArgContext<'a, 'src> {
 }
 
 impl ParseArgContext<'_, '_> {
-    fn error(&mut self, error: ParseError) {
+    pub fn error(&mut self, error: ParseError) {
         self.errors.push(error);
     }
 }
@@ -108,60 +180,89 @@ impl Argument {
                 primitives::parse_text(ctx, *kind).map(cst::ArgumentValue::String)
             }
             Self::Angle => Ok(cst::ArgumentValue::Angle(angle::parse(ctx))),
-            Self::BlockPos => Ok(cst::ArgumentValue::Coordinates3(coords::parse_block_pos(
-                ctx,
-            ))),
+            Self::BlockPos { min, max } => Ok(cst::ArgumentValue::Coordinates3(
+                coords::parse_block_pos(ctx, *min, *max),
+            )),
             Self::BlockPredicate => todo!(),
             Self::BlockState => todo!(),
             Self::Color => Ok(cst::ArgumentValue::Color(color::parse(ctx))),
-            Self::ColumnPos => Ok(cst::ArgumentValue::Coordinates2(coords::parse_column_pos(
+            Self::ColumnPos { min, max } => Ok(cst::ArgumentValue::Coordinates2(
+                coords::parse_column_pos(ctx, *min, *max),
+            )),
+            Self::Component => nbt::parse_nbt_tag(ctx).map(cst::ArgumentValue::Nbt),
+            Self::Dimension => Ok(cst::ArgumentValue::Resource(resource::parse_resource_ref(
                 ctx,
+                "minecraft:dimension",
+                false,
             ))),
-            Self::Component => todo!(),
-            Self::Dimension => todo!(),
             Self::Entity {
-                single: _,
-                players_only: _,
-            } => {
-                todo!()
-            }
-            Self::EntityAnchor => todo!(),
-            Self::Function => todo!(),
+                single,
+                players_only,
+            } => Ok(cst::ArgumentValue::Entity(selector::parse_entity(
+                ctx,
+                *single,
+                *players_only,
+            ))),
+            Self::EntityAnchor => Ok(cst::ArgumentValue::EntityAnchor(entity_anchor::parse(ctx))),
+            Self::Function => Ok(cst::ArgumentValue::Function(resource::parse_function(ctx))),
             Self::GameProfile => todo!(),
             Self::Gamemode => todo!(),
-            Self::Heightmap => todo!(),
-            Self::IntRange => todo!(),
+            Self::Heightmap => Ok(cst::ArgumentValue::Heightmap(heightmap::parse(ctx))),
+            Self::IntRange => Ok(cst::ArgumentValue::IntRange(range::parse_int_range(ctx))),
             Self::ItemPredicate => todo!(),
-            Self::ItemSlot => todo!(),
-            Self::ItemSlots => todo!(),
-            Self::ItemStack => todo!(),
-            Self::LootModifier => todo!(),
-            Self::LootPredicate => todo!(),
-            Self::LootTable => todo!(),
-            Self::Message => todo!(),
-            Self::NbtCompoundTag => todo!(),
-            Self::NbtPath => todo!(),
-            Self::NbtTag => todo!(),
-            Self::Objective => todo!(),
+            Self::ItemSlot => Ok(cst::ArgumentValue::ItemSlot(item_slot::parse_item_slot(
+                ctx, false,
+            ))),
+            Self::ItemSlots => Ok(cst::ArgumentValue::ItemSlot(item_slot::parse_item_slot(
+                ctx, true,
+            ))),
+            Self::ItemStack => item_stack::parse(ctx).map(cst::ArgumentValue::ItemStack),
+            Self::LootModifier => {
+                resource::parse_resource_or_inline(ctx, "minecraft:item_modifier")
+            }
+            Self::LootPredicate => resource::parse_resource_or_inline(ctx, "minecraft:predicate"),
+            Self::LootTable => resource::parse_resource_or_inline(ctx, "minecraft:loot_table"),
+            // Selectors in messages are only resolved by the game, so they are kept as text
+            Self::Message => primitives::parse_text(ctx, StringKind::GreedyPhrase)
+                .map(cst::ArgumentValue::String),
+            Self::NbtCompoundTag => nbt::parse_nbt_compound(ctx).map(cst::ArgumentValue::Nbt),
+            Self::NbtPath => nbt::parse_nbt_path(ctx).map(cst::ArgumentValue::NbtPath),
+            Self::NbtTag => nbt::parse_nbt_tag(ctx).map(cst::ArgumentValue::Nbt),
+            Self::Objective => Ok(cst::ArgumentValue::Objective(scoreboard::parse_objective(
+                ctx,
+            ))),
             Self::ObjectiveCriteria => todo!(),
-            Self::Operation => todo!(),
-            Self::Particle => todo!(),
-            Self::Resource { registry: _ } => todo!(),
-            Self::ResourceKey { registry: _ } => todo!(),
-            Self::ResourceLocation => todo!(),
-            Self::ResourceOrTag { registry: _ } => todo!(),
-            Self::ResourceOrTagKey { registry: _ } => todo!(),
+            Self::Operation => Ok(cst::ArgumentValue::Operation(scoreboard::parse_operation(
+                ctx,
+            ))),
+            Self::Particle => particle::parse(ctx).map(cst::ArgumentValue::Particle),
+            Self::Resource { registry } | Self::ResourceKey { registry } => Ok(
+                cst::ArgumentValue::Resource(resource::parse_resource_ref(ctx, registry, false)),
+            ),
+            Self::ResourceLocation => Ok(cst::ArgumentValue::ResourceLocation(
+                resource::parse_resource_location(ctx),
+            )),
+            Self::ResourceOrTag { registry } | Self::ResourceOrTagKey { registry } => Ok(
+                cst::ArgumentValue::Resource(resource::parse_resource_ref(ctx, registry, true)),
+            ),
             Self::Rotation => todo!(),
-            Self::ScoreHolder { single: _ } => todo!(),
+            Self::ScoreHolder { single } => Ok(cst::ArgumentValue::ScoreHolder(
+                selector::parse_score_holder(ctx, *single),
+            )),
             Self::ScoreboardSlot => todo!(),
             Self::Style => todo!(),
             Self::Swizzle => todo!(),
-            Self::Team => todo!(),
+            Self::Team => Ok(cst::ArgumentValue::Team(scoreboard::parse_team(ctx))),
             Self::TemplateMirror => todo!(),
             Self::TemplateRotation => todo!(),
-            Self::Time { min: _ } => todo!(),
-            Self::Vec2 => Ok(cst::ArgumentValue::Coordinates2(coords::parse_vec2(ctx))),
-            Self::Vec3 => Ok(cst::ArgumentValue::Coordinates3(coords::parse_vec3(ctx))),
+            Self::Time { min } => Ok(cst::ArgumentValue::Time(time::parse(ctx, *min))),
+            Self::Vec2 { min, max } => Ok(cst::ArgumentValue::Coordinates2(coords::parse_vec2(
+                ctx, *min, *max,
+            ))),
+            Self::Vec3 { min, max } => Ok(cst::ArgumentValue::Coordinates3(coords::parse_vec3(
+                ctx, *min, *max,
+            ))),
+            Self::Custom(custom) => custom.parse(ctx),
         }
     }
 }
@@ -170,15 +271,7 @@ impl fmt::Debug for Argument {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::Bool => f.write_str("bool"),
-            Self::Double { min, max } => {
-                f.write_str("double")?;
-                match (*min, *max) {
-                    (f64::MIN, f64::MAX) => Ok(()),
-                    (_, f64::MAX) => write!(f, "(min={min:?})"),
-                    (f64::MIN, _) => write!(f, "(max={max:?})"),
-                    (_, _) => write!(f, "(min={min:?} max={max:?})"),
-                }
-            }
+            Self::Double { min, max } => fmt_f64_bounds(f, "double", *min, *max),
             Self::Float { min, max } => {
                 f.write_str("float")?;
                 match (*min, *max) {
@@ -201,11 +294,11 @@ impl fmt::Debug for Argument {
             Self::String(StringKind::QuotablePhrase) => f.write_str("string(kind=phrase)"),
             Self::String(StringKind::GreedyPhrase) => f.write_str("string(kind=greedy)"),
             Self::Angle => f.write_str("angle"),
-            Self::BlockPos => f.write_str("block_pos"),
+            Self::BlockPos { min, max } => fmt_f64_bounds(f, "block_pos", *min, *max),
             Self::BlockPredicate => f.write_str("block_predicate"),
             Self::BlockState => f.write_str("block_state"),
             Self::Color => f.write_str("color"),
-            Self::ColumnPos => f.write_str("column_pos"),
+            Self::ColumnPos { min, max } => fmt_f64_bounds(f, "column_pos", *min, *max),
             Self::Component => f.write_str("component"),
             Self::Dimension => f.write_str("dimension"),
             Self::Entity {
@@ -255,8 +348,20 @@ impl fmt::Debug for Argument {
             Self::TemplateMirror => f.write_str("template_mirror"),
             Self::TemplateRotation => f.write_str("template_rotation"),
             Self::Time { min } => write!(f, "time(min={min})"),
-            Self::Vec2 => f.write_str("vec2"),
-            Self::Vec3 => f.write_str("vec3"),
+            Self::Vec2 { min, max } => fmt_f64_bounds(f, "vec2", *min, *max),
+            Self::Vec3 { min, max } => fmt_f64_bounds(f, "vec3", *min, *max),
+            Self::Custom(custom) => custom.fmt(f),
         }
     }
 }
+
+/// Writes the name of an argument with bounds, omitting the bounds which are unbounded.
+fn fmt_f64_bounds(f: &mut fmt::Formatter<'_>, name: &str, min: f64, max: f64) -> fmt::Result {
+    f.write_str(name)?;
+    match (min, max) {
+        (f64::MIN, f64::MAX) => Ok(()),
+        (_, f64::MAX) => write!(f, "(min={min:?})"),
+        (f64::MIN, _) => write!(f, "(max={max:?})"),
+        (_, _) => write!(f, "(min={min:?} max={max:?})"),
+    }
+}
@@ -0,0 +1,609 @@
+use std::fmt::Write;
+
+use super::{ParseArgContext, primitives::read_quoted_string};
+use crate::{
+    intern::{Interner, Symbol},
+    parse::{
+        Reader,
+        errors::{
+            ExpectedNbtToken, ParseError, UnexpectedNbtTokenError, UnterminatedCompoundError,
+            UnterminatedStringError,
+        },
+    },
+    span::Span,
+};
+
+/// The entries of a compound tag, in the order they were written.
+pub type NbtCompound = Vec<(Symbol, Nbt)>;
+
+/// A tag in SNBT, the text format of NBT, e.g. `{id:"minecraft:stone",Count:1b}`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Nbt {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    String(Symbol),
+    List(Vec<Nbt>),
+    ByteArray(Vec<i8>),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+    Compound(NbtCompound),
+}
+
+impl Nbt {
+    /// Renders the tag as SNBT. Symbols unknown to the interner are rendered as `?`.
+    pub fn render(&self, interner: &impl Interner) -> String {
+        let mut out = String::new();
+        write_nbt(&mut out, self, interner);
+        out
+    }
+}
+
+/// A path into NBT data like `foo.bar[0].baz`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NbtPath {
+    pub nodes: Vec<NbtPathNode>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NbtPathNode {
+    /// `{...}` at the start of the path, which matches the root compound.
+    MatchRoot(NbtCompound),
+    /// `key`
+    Key(Symbol),
+    /// `key{...}`, which matches the key only if its value matches the compound.
+    MatchKey(Symbol, NbtCompound),
+    /// `[]`
+    AllElements,
+    /// `[index]`, where negative indices count from the end.
+    Index(i32),
+    /// `[{...}]`, which matches all elements that match the compound.
+    MatchElements(NbtCompound),
+}
+
+impl NbtPath {
+    /// Renders the path in the form it is written in. Symbols unknown to the interner are
+    /// rendered as `?`.
+    pub fn render(&self, interner: &impl Interner) -> String {
+        let mut out = String::new();
+        for (i, node) in self.nodes.iter().enumerate() {
+            let needs_dot =
+                i > 0 && matches!(node, NbtPathNode::Key(_) | NbtPathNode::MatchKey(..));
+            if needs_dot {
+                out.push('.');
+            }
+            match node {
+                NbtPathNode::MatchRoot(compound) => write_compound(&mut out, compound, interner),
+                NbtPathNode::Key(key) => write_key(&mut out, *key, interner),
+                NbtPathNode::MatchKey(key, compound) => {
+                    write_key(&mut out, *key, interner);
+                    write_compound(&mut out, compound, interner);
+                }
+                NbtPathNode::AllElements => out.push_str("[]"),
+                NbtPathNode::Index(index) => write!(out, "[{index}]").unwrap(),
+                NbtPathNode::MatchElements(compound) => {
+                    out.push('[');
+                    write_compound(&mut out, compound, interner);
+                    out.push(']');
+                }
+            }
+        }
+        out
+    }
+}
+
+fn write_nbt(out: &mut String, nbt: &Nbt, interner: &impl Interner) {
+    fn write_array<T: ToString>(out: &mut String, kind: char, values: &[T], suffix: &str) {
+        write!(out, "[{kind};").unwrap();
+        for (i, value) in values.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            out.push_str(&value.to_string());
+            out.push_str(suffix);
+        }
+        out.push(']');
+    }
+
+    match nbt {
+        Nbt::Byte(value) => write!(out, "{value}b").unwrap(),
+        Nbt::Short(value) => write!(out, "{value}s").unwrap(),
+        Nbt::Int(value) => write!(out, "{value}").unwrap(),
+        Nbt::Long(value) => write!(out, "{value}L").unwrap(),
+        Nbt::Float(value) => write!(out, "{value:?}f").unwrap(),
+        Nbt::Double(value) => write!(out, "{value:?}d").unwrap(),
+        Nbt::String(string) => write_quoted(out, interner.resolve(*string).unwrap_or("?")),
+        Nbt::List(values) => {
+            out.push('[');
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_nbt(out, value, interner);
+            }
+            out.push(']');
+        }
+        Nbt::ByteArray(values) => write_array(out, 'B', values, "b"),
+        Nbt::IntArray(values) => write_array(out, 'I', values, ""),
+        Nbt::LongArray(values) => write_array(out, 'L', values, "L"),
+        Nbt::Compound(compound) => write_compound(out, compound, interner),
+    }
+}
+
+fn write_compound(out: &mut String, compound: &NbtCompound, interner: &impl Interner) {
+    out.push('{');
+    for (i, (key, value)) in compound.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write_key(out, *key, interner);
+        out.push(':');
+        write_nbt(out, value, interner);
+    }
+    out.push('}');
+}
+
+fn write_key(out: &mut String, key: Symbol, interner: &impl Interner) {
+    let key = interner.resolve(key).unwrap_or("?");
+    if !key.is_empty() && key.chars().all(is_unquoted_char) {
+        out.push_str(key);
+    } else {
+        write_quoted(out, key);
+    }
+}
+
+fn write_quoted(out: &mut String, string: &str) {
+    out.push('"');
+    for chr in string.chars() {
+        if matches!(chr, '"' | '\\') {
+            out.push('\\');
+        }
+        out.push(chr);
+    }
+    out.push('"');
+}
+
+/// Returns whether the character may appear in unquoted strings, keys and numbers.
+fn is_unquoted_char(chr: char) -> bool {
+    matches!(chr, '0'..='9' | 'a'..='z' | 'A'..='Z' | '_' | '-' | '.' | '+')
+}
+
+/// Returns whether the character may appear in unquoted keys of NBT paths, which are more lenient
+/// than those of compounds.
+fn is_path_key_char(chr: char) -> bool {
+    !chr.is_whitespace() && !matches!(chr, '"' | '\'' | '[' | ']' | '.' | '{' | '}')
+}
+
+pub fn parse_nbt_tag(ctx: &mut ParseArgContext<'_, '_>) -> Result<Nbt, ParseError> {
+    let nbt = parse_value(ctx)?;
+    expect_end(ctx.reader)?;
+    Ok(nbt)
+}
+
+pub fn parse_nbt_compound(ctx: &mut ParseArgContext<'_, '_>) -> Result<Nbt, ParseError> {
+    if ctx.reader.peek() != Some('{') {
+        return Err(unexpected(ctx.reader, ExpectedNbtToken::Compound));
+    }
+    let compound = parse_compound(ctx)?;
+    expect_end(ctx.reader)?;
+    Ok(Nbt::Compound(compound))
+}
+
+pub fn parse_nbt_path(ctx: &mut ParseArgContext<'_, '_>) -> Result<NbtPath, ParseError> {
+    let mut nodes = Vec::new();
+
+    match ctx.reader.peek() {
+        Some('{') => nodes.push(NbtPathNode::MatchRoot(parse_compound(ctx)?)),
+        Some('[') => (),
+        _ => nodes.push(parse_path_key(ctx)?),
+    }
+
+    loop {
+        match ctx.reader.peek() {
+            Some('[') => nodes.push(parse_path_index(ctx)?),
+            Some('.') => {
+                ctx.reader.advance();
+                nodes.push(parse_path_key(ctx)?);
+            }
+            None => break,
+            Some(chr) if chr.is_whitespace() => break,
+            Some(_) => return Err(unexpected(ctx.reader, ExpectedNbtToken::PathSeparator)),
+        }
+    }
+
+    Ok(NbtPath { nodes })
+}
+
+fn parse_path_key(ctx: &mut ParseArgContext<'_, '_>) -> Result<NbtPathNode, ParseError> {
+    let key = match ctx.reader.peek() {
+        Some('"' | '\'') => {
+            let key = read_quoted_string(ctx.reader).map_err(unterminated_string)?;
+            ctx.interner.intern(&key)
+        }
+        _ => {
+            let key = ctx.reader.read_while(is_path_key_char);
+            if key.is_empty() {
+                return Err(unexpected(ctx.reader, ExpectedNbtToken::Key));
+            }
+            ctx.interner.intern(key)
+        }
+    };
+
+    if ctx.reader.peek() == Some('{') {
+        return Ok(NbtPathNode::MatchKey(key, parse_compound(ctx)?));
+    }
+    Ok(NbtPathNode::Key(key))
+}
+
+fn parse_path_index(ctx: &mut ParseArgContext<'_, '_>) -> Result<NbtPathNode, ParseError> {
+    ctx.reader.advance();
+
+    let node = match ctx.reader.peek() {
+        Some(']') => NbtPathNode::AllElements,
+        Some('{') => NbtPathNode::MatchElements(parse_compound(ctx)?),
+        _ => {
+            let (range, index) = ctx.reader.parse_with_span(|reader| {
+                reader.read_while(|chr| chr == '-' || chr.is_ascii_digit())
+            });
+            match index.parse() {
+                Ok(index) => NbtPathNode::Index(index),
+                Err(_) => {
+                    let span = match range.is_empty() {
+                        true => next_char_span(ctx.reader),
+                        false => range.into(),
+                    };
+                    return Err(ParseError::UnexpectedNbtToken(UnexpectedNbtTokenError {
+                        span,
+                        expected: ExpectedNbtToken::Index,
+                    }));
+                }
+            }
+        }
+    };
+
+    if !ctx.reader.eat(']') {
+        return Err(unexpected(ctx.reader, ExpectedNbtToken::ClosingBracket));
+    }
+    Ok(node)
+}
+
+pub(super) fn parse_value(ctx: &mut ParseArgContext<'_, '_>) -> Result<Nbt, ParseError> {
+    match ctx.reader.peek() {
+        Some('{') => parse_compound(ctx).map(Nbt::Compound),
+        Some('[') => parse_list_or_array(ctx),
+        Some('"' | '\'') => {
+            let string = read_quoted_string(ctx.reader).map_err(unterminated_string)?;
+            Ok(Nbt::String(ctx.interner.intern(&string)))
+        }
+        _ => {
+            let token = ctx.reader.read_while(is_unquoted_char);
+            if token.is_empty() {
+                return Err(unexpected(ctx.reader, ExpectedNbtToken::Value));
+            }
+            Ok(parse_number(token).unwrap_or_else(|| Nbt::String(ctx.interner.intern(token))))
+        }
+    }
+}
+
+fn parse_compound(ctx: &mut ParseArgContext<'_, '_>) -> Result<NbtCompound, ParseError> {
+    let start = ctx.reader.get_pos();
+    ctx.reader.advance();
+
+    let mut compound = Vec::new();
+    ctx.reader.skip_whitespace();
+    while ctx.reader.peek() != Some('}') {
+        if !ctx.reader.has_more() {
+            return Err(unterminated_compound(ctx.reader, start));
+        }
+
+        let key = match ctx.reader.peek() {
+            Some('"' | '\'') => {
+                let key = read_quoted_string(ctx.reader).map_err(unterminated_string)?;
+                ctx.interner.intern(&key)
+            }
+            _ => {
+                let key = ctx.reader.read_while(is_unquoted_char);
+                if key.is_empty() {
+                    return Err(unexpected(ctx.reader, ExpectedNbtToken::Key));
+                }
+                ctx.interner.intern(key)
+            }
+        };
+
+        ctx.reader.skip_whitespace();
+        if !ctx.reader.has_more() {
+            return Err(unterminated_compound(ctx.reader, start));
+        }
+        if !ctx.reader.eat(':') {
+            return Err(unexpected(ctx.reader, ExpectedNbtToken::Colon));
+        }
+        ctx.reader.skip_whitespace();
+        if !ctx.reader.has_more() {
+            return Err(unterminated_compound(ctx.reader, start));
+        }
+
+        let value = parse_value(ctx)?;
+        compound.push((key, value));
+
+        ctx.reader.skip_whitespace();
+        match ctx.reader.peek() {
+            Some(',') => {
+                ctx.reader.advance();
+                ctx.reader.skip_whitespace();
+            }
+            Some('}') => break,
+            None => return Err(unterminated_compound(ctx.reader, start)),
+            Some(_) => return Err(unexpected(ctx.reader, ExpectedNbtToken::CommaOrBrace)),
+        }
+    }
+    ctx.reader.advance();
+
+    Ok(compound)
+}
+
+fn parse_list_or_array(ctx: &mut ParseArgContext<'_, '_>) -> Result<Nbt, ParseError> {
+    ctx.reader.advance();
+
+    if let Some(prefix) = ctx.reader.peek_str(2)
+        && prefix.ends_with(';')
+    {
+        if !matches!(prefix, "B;" | "I;" | "L;") {
+            return Err(unexpected(ctx.reader, ExpectedNbtToken::ArrayType));
+        }
+        ctx.reader.skip(prefix);
+
+        return match prefix {
+            "B;" => parse_array(ctx, ExpectedNbtToken::Byte, |element| match element {
+                Nbt::Byte(value) => Some(value),
+                _ => None,
+            })
+            .map(Nbt::ByteArray),
+            "I;" => parse_array(ctx, ExpectedNbtToken::Int, |element| match element {
+                Nbt::Int(value) => Some(value),
+                _ => None,
+            })
+            .map(Nbt::IntArray),
+            _ => parse_array(ctx, ExpectedNbtToken::Long, |element| match element {
+                Nbt::Long(value) => Some(value),
+                _ => None,
+            })
+            .map(Nbt::LongArray),
+        };
+    }
+
+    parse_elements(ctx, parse_value).map(Nbt::List)
+}
+
+/// Parses the elements of a typed array, which must all be of the type `expected`.
+fn parse_array<T>(
+    ctx: &mut ParseArgContext<'_, '_>,
+    expected: ExpectedNbtToken,
+    extract: fn(Nbt) -> Option<T>,
+) -> Result<Vec<T>, ParseError> {
+    parse_elements(ctx, |ctx| {
+        let start = ctx.reader.get_pos();
+        let element = parse_value(ctx)?;
+        extract(element).ok_or_else(|| {
+            ParseError::UnexpectedNbtToken(UnexpectedNbtTokenError {
+                span: Span::new(start, ctx.reader.get_pos()),
+                expected,
+            })
+        })
+    })
+}
+
+/// Parses the comma separated elements of a list or array up to and including the closing `]`.
+/// The reader must be after the opening `[` and the type of an array.
+pub(super) fn parse_elements<T>(
+    ctx: &mut ParseArgContext<'_, '_>,
+    mut parse_element: impl FnMut(&mut ParseArgContext<'_, '_>) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    let mut elements = Vec::new();
+    ctx.reader.skip_whitespace();
+    while ctx.reader.peek() != Some(']') {
+        if !ctx.reader.has_more() {
+            return Err(unexpected(ctx.reader, ExpectedNbtToken::CommaOrBracket));
+        }
+        elements.push(parse_element(ctx)?);
+
+        ctx.reader.skip_whitespace();
+        match ctx.reader.peek() {
+            Some(',') => {
+                ctx.reader.advance();
+                ctx.reader.skip_whitespace();
+            }
+            Some(']') => break,
+            _ => return Err(unexpected(ctx.reader, ExpectedNbtToken::CommaOrBracket)),
+        }
+    }
+    ctx.reader.advance();
+
+    Ok(elements)
+}
+
+/// Parses an unquoted token as a number or boolean, like `1b`, `-2.5f` or `true`. Returns `None`
+/// for tokens which are strings, including numbers which are out of range for their type.
+fn parse_number(token: &str) -> Option<Nbt> {
+    fn is_integer(string: &str) -> bool {
+        let digits = string.strip_prefix(['-', '+']).unwrap_or(string);
+        !digits.is_empty()
+            && digits.chars().all(|chr| chr.is_ascii_digit())
+            && (digits == "0" || !digits.starts_with('0'))
+    }
+
+    fn is_decimal(string: &str, requires_dot: bool) -> bool {
+        let string = string.strip_prefix(['-', '+']).unwrap_or(string);
+        let (mantissa, exponent) = match string.split_once(['e', 'E']) {
+            Some((mantissa, exponent)) => (mantissa, Some(exponent)),
+            None => (string, None),
+        };
+        let is_mantissa = match mantissa.split_once('.') {
+            Some((int, frac)) => {
+                (!int.is_empty() || !frac.is_empty())
+                    && int
+                        .chars()
+                        .chain(frac.chars())
+                        .all(|chr| chr.is_ascii_digit())
+            }
+            None => {
+                !requires_dot
+                    && !mantissa.is_empty()
+                    && mantissa.chars().all(|chr| chr.is_ascii_digit())
+            }
+        };
+        is_mantissa
+            && exponent.is_none_or(|exponent| {
+                let digits = exponent.strip_prefix(['-', '+']).unwrap_or(exponent);
+                !digits.is_empty() && digits.chars().all(|chr| chr.is_ascii_digit())
+            })
+    }
+
+    match token {
+        "true" => return Some(Nbt::Byte(1)),
+        "false" => return Some(Nbt::Byte(0)),
+        _ => (),
+    }
+
+    let (number, suffix) = token.split_at(token.len() - 1);
+    match suffix {
+        "b" | "B" if is_integer(number) => number.parse().ok().map(Nbt::Byte),
+        "s" | "S" if is_integer(number) => number.parse().ok().map(Nbt::Short),
+        "l" | "L" if is_integer(number) => number.parse().ok().map(Nbt::Long),
+        "f" | "F" if is_decimal(number, false) => number.parse().ok().map(Nbt::Float),
+        "d" | "D" if is_decimal(number, false) => number.parse().ok().map(Nbt::Double),
+        _ if is_integer(token) => token.parse().ok().map(Nbt::Int),
+        _ if is_decimal(token, true) => token.parse().ok().map(Nbt::Double),
+        _ => None,
+    }
+}
+
+/// Checks that the tag is followed by whitespace or the end of the source.
+fn expect_end(reader: &Reader<'_>) -> Result<(), ParseError> {
+    match reader.peek() {
+        Some(chr) if !chr.is_whitespace() => Err(unexpected(reader, ExpectedNbtToken::End)),
+        _ => Ok(()),
+    }
+}
+
+fn next_char_span(reader: &Reader<'_>) -> Span {
+    Span::new(reader.get_pos(), reader.get_next_pos())
+}
+
+pub(super) fn unexpected(reader: &Reader<'_>, expected: ExpectedNbtToken) -> ParseError {
+    ParseError::UnexpectedNbtToken(UnexpectedNbtTokenError {
+        span: next_char_span(reader),
+        expected,
+    })
+}
+
+fn unterminated_compound(reader: &Reader<'_>, start: usize) -> ParseError {
+    ParseError::UnterminatedCompound(UnterminatedCompoundError {
+        span: Span::new(start, reader.get_pos()),
+    })
+}
+
+fn unterminated_string(span: Span) -> ParseError {
+    ParseError::UnterminatedString(UnterminatedStringError { span })
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::{Nbt, NbtPathNode};
+    use crate::{intern::Interner, parse::cst::ArgumentValue, span::Span, test_util};
+
+    #[test]
+    fn compound_with_typed_array() {
+        let parsed = test_util::parse("data merge entity @s {a:1b,b:[I;1,2,3]}");
+        assert!(parsed.diagnostics().is_empty());
+        let arg = test_util::find_arg(&parsed, |value| matches!(value, ArgumentValue::Nbt(_)));
+        let ArgumentValue::Nbt(Nbt::Compound(compound)) = &arg.value else {
+            panic!("expected a compound, got {:?}", arg.value);
+        };
+        let entries: Vec<_> = compound
+            .iter()
+            .map(|(key, value)| (parsed.interner.resolve(*key).unwrap(), value.clone()))
+            .collect();
+        assert_eq!(
+            entries,
+            [("a", Nbt::Byte(1)), ("b", Nbt::IntArray(vec![1, 2, 3])),]
+        );
+    }
+
+    #[test]
+    fn missing_closing_brace() {
+        let parsed = test_util::parse("data merge entity @s {a:1b");
+        let diagnostics = parsed.diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message(), "Unterminated compound");
+        assert_eq!(diagnostics[0].span(), Span::new(21, 26));
+    }
+
+    #[test]
+    fn path_with_index() {
+        let parsed = test_util::parse("data get entity @s foo.bar[0].baz");
+        assert!(parsed.diagnostics().is_empty());
+        let arg = test_util::find_arg(&parsed, |value| matches!(value, ArgumentValue::NbtPath(_)));
+        let ArgumentValue::NbtPath(path) = &arg.value else {
+            unreachable!();
+        };
+        let nodes: Vec<_> = path
+            .nodes
+            .iter()
+            .map(|node| match node {
+                NbtPathNode::Key(key) => parsed.interner.resolve(*key).unwrap().to_owned(),
+                NbtPathNode::Index(index) => format!("[{index}]"),
+                node => panic!("unexpected node {node:?}"),
+            })
+            .collect();
+        assert_eq!(nodes, ["foo", "bar", "[0]", "baz"]);
+        assert_eq!(path.render(&parsed.interner), "foo.bar[0].baz");
+    }
+
+    /// Parses the compound of `data merge storage`, and returns its keys.
+    fn compound_keys(nbt: &str) -> Vec<String> {
+        let parsed = test_util::parse(&format!("data merge storage a:b {nbt}"));
+        assert!(
+            parsed.diagnostics().is_empty(),
+            "{:?}",
+            parsed.diagnostics()
+        );
+        let arg = test_util::find_arg(&parsed, |value| matches!(value, ArgumentValue::Nbt(_)));
+        let ArgumentValue::Nbt(Nbt::Compound(compound)) = &arg.value else {
+            panic!("expected a compound, got {:?}", arg.value);
+        };
+        compound
+            .iter()
+            .map(|(key, _)| parsed.interner.resolve(*key).unwrap().to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn quoted_keys() {
+        assert_eq!(compound_keys(r#"{"a b":1}"#), ["a b"]);
+        assert_eq!(compound_keys("{'k':1}"), ["k"]);
+        assert_eq!(compound_keys(r#"{ "x" : 1 , 'y z' : 2 }"#), ["x", "y z"]);
+        // Keys are stored decoded
+        assert_eq!(compound_keys(r#"{"a\"b":1,'c\'d':2}"#), [r#"a"b"#, "c'd"]);
+    }
+
+    #[test]
+    fn quoted_keys_are_rendered_quoted() {
+        let parsed = test_util::parse(r#"data merge storage a:b {"a b":1b,'k':"v"}"#);
+        let arg = test_util::find_arg(&parsed, |value| matches!(value, ArgumentValue::Nbt(_)));
+        let ArgumentValue::Nbt(nbt) = &arg.value else {
+            unreachable!();
+        };
+        assert_eq!(nbt.render(&parsed.interner), r#"{"a b":1b,k:"v"}"#);
+    }
+
+    #[test]
+    fn unterminated_quoted_key() {
+        let parsed = test_util::parse(r#"data merge storage a:b {"a b:1}"#);
+        let diagnostics = parsed.diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].span(), Span::new(24, 31));
+    }
+}
@@ -0,0 +1,106 @@
+use super::{
+    ParseArgContext, ResourceRef,
+    nbt::{self, Nbt},
+    resource::intern_resource_location,
+};
+use crate::{intern::Interner, parse::errors::ParseError, span::Span};
+
+/// A particle like `minecraft:flame`, optionally followed by its options, e.g.
+/// `dust{color:[1f,0f,0f],scale:1f}`.
+#[derive(Debug, PartialEq)]
+pub struct Particle {
+    pub id: ResourceRef,
+    /// The options of the particle. Their schema depends on the particle and is not checked.
+    pub options: Option<Nbt>,
+    /// The span of the options, which is empty directly after the id if there are none.
+    pub params: Span,
+}
+
+pub fn parse(ctx: &mut ParseArgContext<'_, '_>) -> Result<Particle, ParseError> {
+    let (span, string) = ctx
+        .reader
+        .parse_with_span(|reader| reader.read_until(|chr| chr.is_whitespace() || chr == '{'));
+    let span = Span::from(span);
+    let location = intern_resource_location(ctx, string, span);
+    let id = ResourceRef {
+        registry: ctx.interner.intern("minecraft:particle_type"),
+        location,
+        is_tag: false,
+    };
+
+    let (params, options) = match ctx.reader.peek() {
+        Some('{') => {
+            let start = ctx.reader.get_pos();
+            let options = nbt::parse_nbt_compound(ctx)?;
+            (Span::new(start, ctx.reader.get_pos()), Some(options))
+        }
+        _ => (Span::new(span.end, span.end), None),
+    };
+
+    Ok(Particle {
+        id,
+        options,
+        params,
+    })
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::Particle;
+    use crate::{Parsed, parse::cst::ArgumentValue, span::Span, test_util};
+
+    fn particle(parsed: &Parsed) -> &Particle {
+        let arg = test_util::find_arg(parsed, |value| matches!(value, ArgumentValue::Particle(_)));
+        let ArgumentValue::Particle(particle) = &arg.value else {
+            unreachable!();
+        };
+        particle
+    }
+
+    fn id(parsed: &Parsed) -> String {
+        let location = particle(parsed).id.location.unwrap();
+        location.resolve(&parsed.interner).unwrap()
+    }
+
+    #[test]
+    fn particle_without_options() {
+        let parsed = test_util::parse("particle minecraft:flame");
+        assert!(parsed.diagnostics().is_empty());
+        assert_eq!(id(&parsed), "minecraft:flame");
+        assert_eq!(particle(&parsed).options, None);
+        assert_eq!(particle(&parsed).params, Span::new(24, 24));
+    }
+
+    #[test]
+    fn particle_with_options() {
+        let parsed = test_util::parse("particle dust{color:[1.0,0.5,0.5],scale:1.0} ~ ~ ~");
+        assert!(parsed.diagnostics().is_empty());
+        assert_eq!(id(&parsed), "minecraft:dust");
+        assert!(particle(&parsed).options.is_some());
+        assert_eq!(particle(&parsed).params, Span::new(13, 44));
+
+        // Options given as separate words are not part of the particle, they are read as the
+        // position instead
+        let parsed = test_util::parse("particle dust 1.0 0.5 0.5 1.0");
+        assert_eq!(id(&parsed), "minecraft:dust");
+        assert_eq!(particle(&parsed).options, None);
+        assert_eq!(particle(&parsed).params, Span::new(13, 13));
+    }
+
+    #[test]
+    fn invalid_particle_id() {
+        let parsed = test_util::parse("particle Foo:bar");
+        let diagnostics = parsed.diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message(), "Invalid resource location");
+        assert_eq!(diagnostics[0].span(), Span::new(9, 16));
+    }
+
+    #[test]
+    fn params_follow_edits() {
+        let mut parsed = test_util::parse("particle dust{color:[1,0,0],scale:1}\n");
+        parsed.edit(0..0, "say x\n");
+        assert!(parsed.diagnostics().is_empty());
+        assert_eq!(particle(&parsed).params, Span::new(19, 42));
+    }
+}
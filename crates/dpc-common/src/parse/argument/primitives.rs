@@ -1,21 +1,24 @@
-use std::str::FromStr;
+use std::{borrow::Cow, str::FromStr};
 
 use super::{ParseArgContext, StringKind};
 use crate::{
     intern::{Interner, Symbol},
-    parse::errors::{
-        InvalidStringCharsError, NumberOutOfBoundsError, NumberType, ParseBoolError, ParseError,
-        ParseNumberError, QuotedSingleWordError, UnterminatedStringError,
+    parse::{
+        Reader,
+        errors::{
+            InvalidStringCharsError, NumberOutOfBoundsError, NumberType, ParseBoolError,
+            ParseError, ParseNumberError, QuotedSingleWordError, UnterminatedStringError,
+        },
     },
     span::Span,
 };
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Boolean {
     pub value: Option<bool>,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Integer {
     pub value: Option<i32>,
 }
@@ -28,7 +31,7 @@ impl Integer {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Float {
     pub value: Option<f32>,
 }
@@ -41,7 +44,7 @@ impl Float {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Double {
     pub value: Option<f64>,
 }
@@ -54,7 +57,7 @@ impl Double {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Text {
     pub value: Option<Symbol>,
     pub is_quotable: bool,
@@ -74,25 +77,28 @@ pub fn parse_bool(ctx: &mut ParseArgContext<'_, '_>) -> Boolean {
     Boolean { value }
 }
 
-fn parse_number<T: FromStr + PartialOrd + Into<f64>>(
-    ctx: &mut ParseArgContext<'_, '_>,
-    kind: NumberType,
-    min: T,
-    max: T,
-) -> Option<T> {
+/// Parses a number the way brigadier does, which unlike [`str::parse`] rejects exponents, a
+/// leading `+` and special values like `inf`.
+pub(super) fn parse_number_str<T: FromStr>(string: &str) -> Option<T> {
     fn is_number_char(chr: char) -> bool {
         matches!(chr, '0'..='9' | '.' | '-')
     }
 
-    let range = ctx.reader.read_range_until(char::is_whitespace);
-    let span = range.clone().into();
-    let string = &ctx.reader.get_src()[range.clone()];
     if !string.chars().all(is_number_char) {
-        ctx.error(ParseError::ParseNumber(ParseNumberError { span, kind }));
         return None;
     }
+    string.parse().ok()
+}
 
-    let Ok(number) = string.parse::<T>() else {
+fn parse_number<T: FromStr + PartialOrd + Into<f64>>(
+    ctx: &mut ParseArgContext<'_, '_>,
+    kind: NumberType,
+    min: T,
+    max: T,
+) -> Option<T> {
+    let range = ctx.reader.read_range_until(char::is_whitespace);
+    let span = range.clone().into();
+    let Some(number) = parse_number_str::<T>(&ctx.reader.get_src()[range.clone()]) else {
         ctx.error(ParseError::ParseNumber(ParseNumberError { span, kind }));
         return None;
     };
@@ -131,42 +137,58 @@ pub fn parse_text(ctx: &mut ParseArgContext<'_, '_>, kind: StringKind) -> Result
         return parse_greedy_phrase(ctx);
     }
 
-    let Some(quote @ ('"' | '\'')) = ctx.reader.peek() else {
+    let Some('"' | '\'') = ctx.reader.peek() else {
         let string = parse_unquoted_string(ctx);
 
         return string;
     };
 
     let string_start = ctx.reader.get_pos();
+    let string = read_quoted_string(ctx.reader)
+        .map_err(|span| ParseError::UnterminatedString(UnterminatedStringError { span }))?;
+
+    if kind == StringKind::SingleWord {
+        ctx.error(ParseError::QuotedSingleWord(QuotedSingleWordError {
+            span: Span::new(string_start, ctx.reader.get_pos()),
+        }));
+    }
 
-    ctx.reader.advance();
-    let content_start = ctx.reader.get_pos();
+    Ok(Text {
+        value: Some(ctx.interner.intern(&string)),
+        is_quotable: true,
+    })
+}
 
-    while let Some(chr) = ctx.reader.peek() {
+/// Reads the string in `"` or `'` quotes at the reader and decodes its escape sequences, where a
+/// backslash stands for the character following it. If the closing quote is missing, the span of
+/// the whole string is returned instead.
+pub(super) fn read_quoted_string<'src>(reader: &mut Reader<'src>) -> Result<Cow<'src, str>, Span> {
+    let start = reader.get_pos();
+    let quote = reader.peek().expect("reader must be at a quote");
+    reader.advance();
+    let content_start = reader.get_pos();
+
+    let mut decoded: Option<String> = None;
+    while let Some(chr) = reader.peek() {
         if chr == quote {
-            let string = &ctx.reader.get_src()[content_start..ctx.reader.get_pos()];
-            ctx.reader.advance();
-
-            if kind == StringKind::SingleWord {
-                ctx.error(ParseError::QuotedSingleWord(QuotedSingleWordError {
-                    span: Span::new(string_start, ctx.reader.get_pos()),
-                }));
-            }
-
-            return Ok(Text {
-                value: Some(ctx.interner.intern(string)),
-                is_quotable: true,
+            let content = &reader.get_src()[content_start..reader.get_pos()];
+            reader.advance();
+            return Ok(decoded.map_or(Cow::Borrowed(content), Cow::Owned));
+        }
+
+        if chr == '\\' {
+            let decoded = decoded.get_or_insert_with(|| {
+                reader.get_src()[content_start..reader.get_pos()].to_owned()
             });
-        } else if chr == '\\' {
-            ctx.reader.advance();
+            reader.advance();
+            decoded.extend(reader.peek());
+        } else if let Some(decoded) = &mut decoded {
+            decoded.push(chr);
         }
-        ctx.reader.advance();
+        reader.advance();
     }
 
-    let span = string_start..ctx.reader.get_pos();
-    Err(ParseError::UnterminatedString(UnterminatedStringError {
-        span: span.into(),
-    }))
+    Err(Span::new(start, reader.get_pos()))
 }
 
 fn parse_unquoted_string(ctx: &mut ParseArgContext<'_, '_>) -> Result<Text, ParseError> {
@@ -193,11 +215,100 @@ fn parse_unquoted_string(ctx: &mut ParseArgContext<'_, '_>) -> Result<Text, Pars
     })
 }
 
+/// Reads the rest of the command. Commands may be wrapped onto indented lines, so the phrase
+/// continues on those, joined by a single space. It ends before the last line break.
 fn parse_greedy_phrase(ctx: &mut ParseArgContext<'_, '_>) -> Result<Text, ParseError> {
-    let symbol = ctx.interner.intern(ctx.reader.remaining_src().trim_end());
-    ctx.reader.set_pos(ctx.reader.get_src().len());
+    let mut phrase = Cow::Borrowed("");
+    let mut end = ctx.reader.get_pos();
+    while ctx.reader.has_more() {
+        let start = ctx.reader.get_pos();
+        let line = ctx.reader.rest_of_line().trim_end();
+        end = start + line.len();
+        phrase = match phrase.is_empty() {
+            true => Cow::Borrowed(line),
+            false => Cow::Owned(format!("{phrase} {line}")),
+        };
+        ctx.reader.skip_whitespace();
+    }
+    ctx.reader.set_pos(end);
+
     Ok(Text {
-        value: Some(symbol),
+        value: Some(ctx.interner.intern(&phrase)),
         is_quotable: false,
     })
 }
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use crate::{
+        intern::Interner,
+        parse::{
+            argument::Text,
+            cst::{ArgumentValue, Command},
+        },
+        span::Span,
+        test_util,
+    };
+
+    fn greedy_arg(command: &Command) -> (Span, Text) {
+        let arg = command.args.last().unwrap();
+        match arg.value {
+            ArgumentValue::String(text) => (arg.span, text),
+            ref value => panic!("expected a string, got {value:?}"),
+        }
+    }
+
+    #[test]
+    fn greedy_phrase_ends_at_line_break() {
+        let parsed = test_util::parse("help foo bar\nhelp baz\r\nhelp qux");
+        assert!(parsed.diagnostics().is_empty());
+
+        let commands: Vec<_> = parsed.result.as_ref().unwrap().commands().collect();
+        assert_eq!(commands.len(), 3);
+        let (span, text) = greedy_arg(commands[0]);
+        assert_eq!(span, Span::new(5, 12));
+        assert_eq!(
+            parsed.interner.resolve(text.value.unwrap()),
+            Some("foo bar")
+        );
+        let (span, text) = greedy_arg(commands[1]);
+        assert_eq!(span, Span::new(18, 21));
+        assert_eq!(parsed.interner.resolve(text.value.unwrap()), Some("baz"));
+    }
+
+    #[test]
+    fn greedy_phrase_continues_on_wrapped_lines() {
+        let parsed = test_util::parse("help foo\n    bar  \nhelp baz");
+        assert!(parsed.diagnostics().is_empty());
+
+        let block = parsed.result.as_ref().unwrap();
+        let (span, text) = greedy_arg(block.commands().next().unwrap());
+        assert_eq!(span, Span::new(5, 16));
+        assert_eq!(
+            parsed.interner.resolve(text.value.unwrap()),
+            Some("foo bar")
+        );
+    }
+
+    #[test]
+    fn greedy_phrase_in_block_is_followed_by_more_lines() {
+        let text = "execute run\n    say a b\r\n    say c\rsay d\n";
+        let parsed = test_util::parse(text);
+        assert!(parsed.diagnostics().is_empty());
+
+        let block = parsed.result.as_ref().unwrap();
+        let args: Vec<_> = block
+            .all_commands()
+            .filter(|command| command.literal_path(&parsed.tree) == ["say"])
+            .map(|command| {
+                let (span, value) = greedy_arg(command);
+                let value = parsed.interner.resolve(value.value.unwrap());
+                (&text[span.start..span.end], value)
+            })
+            .collect();
+        assert_eq!(
+            args,
+            [("a b", Some("a b")), ("c", Some("c")), ("d", Some("d"))]
+        );
+    }
+}
@@ -0,0 +1,124 @@
+use super::{ParseArgContext, primitives::parse_number_str};
+use crate::{
+    parse::errors::{
+        EmptyRangeError, NumberType, ParseError, ParseNumberError, ReversedRangeError,
+    },
+    span::Span,
+};
+
+/// A range of integers like `5`, `1..10`, `3..` or `..7`. A missing bound is unbounded, a single
+/// number has equal bounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntRange {
+    pub min: Option<i32>,
+    pub max: Option<i32>,
+}
+
+pub fn parse_int_range(ctx: &mut ParseArgContext<'_, '_>) -> IntRange {
+    let (range, string) = ctx.reader.parse_with_span(|reader| reader.read_literal());
+    parse_int_range_str(ctx, string, range.into())
+}
+
+/// Parses the range from a string at `span`, e.g. the value of a selector option.
+pub(super) fn parse_int_range_str(
+    ctx: &mut ParseArgContext<'_, '_>,
+    string: &str,
+    span: Span,
+) -> IntRange {
+    let Some((min, max)) = string.split_once("..") else {
+        let value = parse_bound(ctx, string, span);
+        return IntRange {
+            min: value,
+            max: value,
+        };
+    };
+
+    if min.is_empty() && max.is_empty() {
+        ctx.error(ParseError::EmptyRange(EmptyRangeError { span }));
+        return IntRange {
+            min: None,
+            max: None,
+        };
+    }
+
+    let min_span = Span::new(span.start, span.start + min.len());
+    let max_span = Span::new(span.end - max.len(), span.end);
+    let range = IntRange {
+        min: (!min.is_empty())
+            .then(|| parse_bound(ctx, min, min_span))
+            .flatten(),
+        max: (!max.is_empty())
+            .then(|| parse_bound(ctx, max, max_span))
+            .flatten(),
+    };
+
+    if let (Some(min), Some(max)) = (range.min, range.max)
+        && min > max
+    {
+        ctx.error(ParseError::ReversedRange(ReversedRangeError { span }));
+    }
+
+    range
+}
+
+fn parse_bound(ctx: &mut ParseArgContext<'_, '_>, string: &str, span: Span) -> Option<i32> {
+    let value = parse_number_str(string);
+    if value.is_none() {
+        ctx.error(ParseError::ParseNumber(ParseNumberError {
+            span,
+            kind: NumberType::Integer,
+        }));
+    }
+    value
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::IntRange;
+    use crate::{parse::cst::ArgumentValue, test_util};
+
+    fn parse_range(range: &str) -> (IntRange, Vec<String>) {
+        let parsed = test_util::parse(&format!("execute if score @s o matches {range}"));
+        let arg = test_util::find_arg(&parsed, |value| matches!(value, ArgumentValue::IntRange(_)));
+        let ArgumentValue::IntRange(range) = arg.value else {
+            unreachable!();
+        };
+        (range, arg.errors.iter().map(ToString::to_string).collect())
+    }
+
+    #[test]
+    fn valid_ranges() {
+        let cases = [
+            ("5", Some(5), Some(5)),
+            ("1..10", Some(1), Some(10)),
+            ("3..", Some(3), None),
+            ("..7", None, Some(7)),
+        ];
+        for (text, min, max) in cases {
+            assert_eq!(parse_range(text), (IntRange { min, max }, vec![]), "{text}");
+        }
+    }
+
+    #[test]
+    fn invalid_ranges() {
+        let (range, errors) = parse_range("..");
+        assert_eq!(
+            range,
+            IntRange {
+                min: None,
+                max: None
+            }
+        );
+        assert_eq!(errors, ["Expected a value or a range of values"]);
+
+        let (range, errors) = parse_range("10..1");
+        assert_eq!(
+            range,
+            IntRange {
+                min: Some(10),
+                max: Some(1)
+            }
+        );
+        assert_eq!(errors, ["Minimum of range is bigger than maximum"]);
+    }
+}
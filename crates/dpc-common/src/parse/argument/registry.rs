@@ -0,0 +1,138 @@
+use std::{collections::HashMap, sync::Arc};
+
+use rustc_hash::FxHashMap;
+use serde_json::Value;
+
+use super::{Argument, CustomArgument};
+
+type ArgumentFactory =
+    Box<dyn Fn(&HashMap<String, Value>) -> Arc<dyn CustomArgument> + Send + Sync>;
+
+/// Maps parser ids from `commands.json` to custom argument parsers.
+#[derive(Default)]
+pub struct ArgumentRegistry {
+    factories: FxHashMap<String, ArgumentFactory>,
+}
+
+impl ArgumentRegistry {
+    /// Registers a parser id. The factory receives the properties of each argument node using the
+    /// parser. Registered parsers take precedence over the built-in ones.
+    pub fn register(
+        &mut self,
+        parser: impl Into<String>,
+        factory: impl Fn(&HashMap<String, Value>) -> Arc<dyn CustomArgument> + Send + Sync + 'static,
+    ) {
+        self.factories.insert(parser.into(), Box::new(factory));
+    }
+
+    pub(crate) fn construct(
+        &self,
+        parser: &str,
+        properties: &HashMap<String, Value>,
+    ) -> Option<Argument> {
+        self.factories
+            .get(parser)
+            .map(|factory| Argument::Custom(factory(properties)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::ArgumentRegistry;
+    use crate::{
+        BuildTree, ImportError, Parsed,
+        parse::{
+            argument::{CustomArgument, ParseArgContext},
+            cst::{ArgumentValue, CustomValue},
+            errors::{NumberType, ParseError, ParseNumberError},
+        },
+        span::Span,
+    };
+
+    /// Parses durations like `5s` or `10t` into ticks.
+    #[derive(Debug)]
+    struct Duration {
+        ticks_per_second: u64,
+    }
+
+    impl CustomArgument for Duration {
+        fn parse(&self, ctx: &mut ParseArgContext<'_, '_>) -> Result<ArgumentValue, ParseError> {
+            let (range, amount) = ctx
+                .reader
+                .parse_with_span(|reader| reader.read_while(|chr| chr.is_ascii_digit()));
+            let amount: u64 = amount.parse().map_err(|_| {
+                ParseError::ParseNumber(ParseNumberError {
+                    span: range.into(),
+                    kind: NumberType::Integer,
+                })
+            })?;
+            let ticks = match ctx.reader.peek() {
+                Some('s') => amount * self.ticks_per_second,
+                _ => amount,
+            };
+            ctx.reader.eat('s');
+            ctx.reader.eat('t');
+            Ok(ArgumentValue::Custom(CustomValue(Box::new(ticks))))
+        }
+    }
+
+    const GRAMMAR: &str = r#"{"type": "root", "children": {"wait": {"type": "literal", "children": {
+        "duration": {"type": "argument", "parser": "mymod:duration",
+                     "properties": {"ticks_per_second": 20}, "executable": true}
+    }}}}"#;
+
+    fn registry() -> ArgumentRegistry {
+        let mut registry = ArgumentRegistry::default();
+        registry.register("mymod:duration", |properties| {
+            let ticks_per_second = properties["ticks_per_second"].as_u64().unwrap();
+            Arc::new(Duration { ticks_per_second })
+        });
+        registry
+    }
+
+    fn parse(text: &str) -> Parsed {
+        let mut tree = BuildTree::default();
+        crate::import::import(GRAMMAR, &mut tree, &registry()).unwrap();
+        crate::parse_source(Arc::new(tree.into_parsing_tree()), None, text.to_owned())
+    }
+
+    fn ticks(parsed: &Parsed) -> Vec<u64> {
+        let block = parsed.result.as_ref().unwrap();
+        block
+            .commands()
+            .filter_map(|command| match &command.args.last()?.value {
+                ArgumentValue::Custom(value) => value.0.downcast_ref::<u64>().copied(),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn custom_parser_is_used_by_grammar() {
+        let parsed = parse("wait 5s\nwait 7t\nwait 3\n");
+        assert!(parsed.diagnostics().is_empty());
+        assert_eq!(ticks(&parsed), [100, 7, 3]);
+    }
+
+    #[test]
+    fn custom_parser_errors() {
+        let parsed = parse("wait soon\n");
+        let diagnostics = parsed.diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message(), "Invalid number");
+        assert_eq!(diagnostics[0].span(), Span::new(5, 5));
+        assert!(ticks(&parsed).is_empty());
+    }
+
+    #[test]
+    fn unregistered_parser_is_rejected() {
+        let mut tree = BuildTree::default();
+        let result = crate::import::import(GRAMMAR, &mut tree, &ArgumentRegistry::default());
+        assert!(matches!(
+            result,
+            Err(ImportError::UnknownParser(parser)) if parser == "mymod:duration"
+        ));
+    }
+}
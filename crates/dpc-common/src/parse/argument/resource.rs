@@ -0,0 +1,212 @@
+use super::{ParseArgContext, nbt};
+use crate::{
+    intern::{Interner, Symbol},
+    parse::{
+        cst::ArgumentValue,
+        errors::{InvalidResourceLocationError, ParseError, TagNotAllowedError},
+    },
+    span::Span,
+};
+
+/// A namespaced id like `minecraft:stone`. A missing namespace defaults to `minecraft`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ResourceLocation {
+    pub namespace: Symbol,
+    pub path: Symbol,
+}
+
+impl ResourceLocation {
+    /// Returns the location as `namespace:path`, or `None` if a symbol is unknown to the interner.
+    pub fn resolve(self, interner: &impl Interner) -> Option<String> {
+        let namespace = interner.resolve(self.namespace)?;
+        let path = interner.resolve(self.path)?;
+        Some(format!("{namespace}:{path}"))
+    }
+}
+
+/// A reference to a function, or to a function tag if it starts with `#`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FunctionRef {
+    pub location: Option<ResourceLocation>,
+    pub is_tag: bool,
+}
+
+/// A reference to an entry of a registry, or to a tag of the registry if it starts with `#`, e.g.
+/// `minecraft:dirt` in the `minecraft:block` registry.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResourceRef {
+    /// The registry the entry belongs to, e.g. `minecraft:block`.
+    pub registry: Symbol,
+    pub location: Option<ResourceLocation>,
+    pub is_tag: bool,
+}
+
+/// Splits a resource location into namespace and path, or returns `None` if it is invalid. The
+/// namespace defaults to `minecraft`.
+pub(crate) fn split_resource_location(string: &str) -> Option<(&str, &str)> {
+    let (namespace, path) = string.split_once(':').unwrap_or(("minecraft", string));
+    let is_valid_char = |chr: char| matches!(chr, 'a'..='z' | '0'..='9' | '_' | '-' | '.');
+    let is_valid = !path.is_empty()
+        && namespace.chars().all(is_valid_char)
+        && path.chars().all(|chr| chr == '/' || is_valid_char(chr));
+    is_valid.then_some((namespace, path))
+}
+
+pub fn parse_resource_location(ctx: &mut ParseArgContext<'_, '_>) -> Option<ResourceLocation> {
+    let (span, string) = ctx
+        .reader
+        .parse_with_span(|reader| reader.read_until(char::is_whitespace));
+    intern_resource_location(ctx, string, span.into())
+}
+
+/// Validates and interns the resource location in a string at `span`, e.g. the value of a
+/// selector option.
+pub(super) fn intern_resource_location(
+    ctx: &mut ParseArgContext<'_, '_>,
+    string: &str,
+    span: Span,
+) -> Option<ResourceLocation> {
+    let Some((namespace, path)) = split_resource_location(string) else {
+        ctx.error(ParseError::InvalidResourceLocation(
+            InvalidResourceLocationError { span },
+        ));
+        return None;
+    };
+
+    Some(ResourceLocation {
+        namespace: ctx.interner.intern(namespace),
+        path: ctx.interner.intern(path),
+    })
+}
+
+pub fn parse_function(ctx: &mut ParseArgContext<'_, '_>) -> FunctionRef {
+    let is_tag = ctx.reader.eat('#');
+    FunctionRef {
+        location: parse_resource_location(ctx),
+        is_tag,
+    }
+}
+
+/// Parses a reference to an entry of `registry`. Tags are parsed either way, but reported unless
+/// `allow_tags` is set.
+pub fn parse_resource_ref(
+    ctx: &mut ParseArgContext<'_, '_>,
+    registry: &str,
+    allow_tags: bool,
+) -> ResourceRef {
+    let start = ctx.reader.get_pos();
+    let is_tag = ctx.reader.eat('#');
+    let location = parse_resource_location(ctx);
+
+    if is_tag && !allow_tags {
+        ctx.error(ParseError::TagNotAllowed(TagNotAllowedError {
+            span: Span::new(start, ctx.reader.get_pos()),
+        }));
+    }
+
+    ResourceRef {
+        registry: ctx.interner.intern(registry),
+        location,
+        is_tag,
+    }
+}
+
+/// Parses a reference to an entry of `registry`, or the entry itself written inline as SNBT, e.g.
+/// the loot table of `loot give @s loot {pools: []}`.
+pub fn parse_resource_or_inline(
+    ctx: &mut ParseArgContext<'_, '_>,
+    registry: &str,
+) -> Result<ArgumentValue, ParseError> {
+    match ctx.reader.peek() {
+        Some('{') => nbt::parse_nbt_compound(ctx).map(ArgumentValue::Nbt),
+        _ => Ok(ArgumentValue::Resource(parse_resource_ref(
+            ctx, registry, false,
+        ))),
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use std::sync::Arc;
+
+    use super::ResourceRef;
+    use crate::{
+        BuildTree, Parsed, intern::Interner, parse::argument::ArgumentRegistry,
+        parse::cst::ArgumentValue,
+    };
+
+    /// Parses `cmd <text>` with a tree whose only argument uses the parser of the block registry.
+    fn parse_block_ref(parser: &str, text: &str) -> (Parsed, Option<ResourceRef>) {
+        let json = format!(
+            r#"{{"type": "root", "children": {{"cmd": {{"type": "literal", "children": {{
+                "block": {{"type": "argument", "parser": "{parser}",
+                           "properties": {{"registry": "minecraft:block"}}, "executable": true}}
+            }}}}}}}}"#
+        );
+        let mut tree = BuildTree::default();
+        crate::import::import(&json, &mut tree, &ArgumentRegistry::default()).unwrap();
+        let tree = Arc::new(tree.into_parsing_tree());
+        let parsed = crate::parse_source(tree, None, format!("cmd {text}"));
+        let block = parsed.result.as_ref().unwrap();
+        let resource = block.commands().next().and_then(|command| {
+            command.args.iter().find_map(|arg| match arg.value {
+                ArgumentValue::Resource(resource) => Some(resource),
+                _ => None,
+            })
+        });
+        (parsed, resource)
+    }
+
+    fn resolve(parsed: &Parsed, resource: ResourceRef) -> (String, String, bool) {
+        (
+            parsed
+                .interner
+                .resolve(resource.registry)
+                .unwrap()
+                .to_owned(),
+            resource
+                .location
+                .unwrap()
+                .resolve(&parsed.interner)
+                .unwrap(),
+            resource.is_tag,
+        )
+    }
+
+    #[test]
+    fn resource_in_registry() {
+        let (parsed, resource) = parse_block_ref("minecraft:resource", "minecraft:dirt");
+        assert!(parsed.diagnostics().is_empty());
+        assert_eq!(
+            resolve(&parsed, resource.unwrap()),
+            (
+                "minecraft:block".to_owned(),
+                "minecraft:dirt".to_owned(),
+                false
+            )
+        );
+    }
+
+    #[test]
+    fn tag_in_resource_or_tag() {
+        let (parsed, resource) = parse_block_ref("minecraft:resource_or_tag", "#minecraft:logs");
+        assert!(parsed.diagnostics().is_empty());
+        assert_eq!(
+            resolve(&parsed, resource.unwrap()),
+            (
+                "minecraft:block".to_owned(),
+                "minecraft:logs".to_owned(),
+                true
+            )
+        );
+    }
+
+    #[test]
+    fn tag_in_plain_resource_is_rejected() {
+        let (parsed, resource) = parse_block_ref("minecraft:resource", "#foo");
+        let diagnostics = parsed.diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].span(), crate::span::Span::new(4, 8));
+        assert!(resource.unwrap().is_tag);
+    }
+}
@@ -0,0 +1,254 @@
+use std::fmt;
+
+use super::ParseArgContext;
+use crate::{
+    intern::{Interner, Symbol},
+    parse::errors::{
+        InvalidOperationError, InvalidStringCharsError, ObjectiveNameTooLongError, ParseError,
+    },
+    span::Span,
+};
+
+/// The maximum number of characters in the name of a scoreboard objective.
+pub const MAX_OBJECTIVE_NAME_LEN: usize = 16;
+
+/// The name of a scoreboard objective.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Objective {
+    pub name: Symbol,
+    pub span: Span,
+}
+
+/// The name of a team.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Team {
+    pub name: Symbol,
+    pub span: Span,
+}
+
+/// The operator of `scoreboard players operation`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Operation {
+    /// The operator, or `None` if it is invalid.
+    pub operator: Option<Operator>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    /// `=`
+    Assign,
+    /// `+=`
+    Add,
+    /// `-=`
+    Subtract,
+    /// `*=`
+    Multiply,
+    /// `/=`
+    Divide,
+    /// `%=`
+    Modulo,
+    /// `<`, keeps the smaller score.
+    Min,
+    /// `>`, keeps the bigger score.
+    Max,
+    /// `><`
+    Swap,
+}
+
+impl Operator {
+    pub const ALL: [Self; 9] = [
+        Self::Assign,
+        Self::Add,
+        Self::Subtract,
+        Self::Multiply,
+        Self::Divide,
+        Self::Modulo,
+        Self::Min,
+        Self::Max,
+        Self::Swap,
+    ];
+
+    pub fn from_string(s: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|operator| operator.as_str() == s)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Assign => "=",
+            Self::Add => "+=",
+            Self::Subtract => "-=",
+            Self::Multiply => "*=",
+            Self::Divide => "/=",
+            Self::Modulo => "%=",
+            Self::Min => "<",
+            Self::Max => ">",
+            Self::Swap => "><",
+        }
+    }
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+pub fn parse_objective(ctx: &mut ParseArgContext<'_, '_>) -> Objective {
+    let (name, span) = parse_name(ctx);
+    let len = ctx.reader.get_src()[span.as_range()].chars().count();
+    if len > MAX_OBJECTIVE_NAME_LEN {
+        ctx.error(ParseError::ObjectiveNameTooLong(
+            ObjectiveNameTooLongError { span },
+        ));
+    }
+    Objective { name, span }
+}
+
+pub fn parse_team(ctx: &mut ParseArgContext<'_, '_>) -> Team {
+    let (name, span) = parse_name(ctx);
+    Team { name, span }
+}
+
+/// Parses an operator. The whole word is matched, so e.g. `><` is never read as `>`.
+pub fn parse_operation(ctx: &mut ParseArgContext<'_, '_>) -> Operation {
+    let (range, token) = ctx
+        .reader
+        .parse_with_span(|reader| reader.read_until(char::is_whitespace));
+    let span = Span::from(range);
+
+    let operator = Operator::from_string(token);
+    if operator.is_none() {
+        ctx.error(ParseError::InvalidOperation(InvalidOperationError { span }));
+    }
+    Operation { operator, span }
+}
+
+/// Reads a single word, which may only contain the characters of unquoted strings. Invalid names
+/// are reported, but interned nonetheless.
+fn parse_name(ctx: &mut ParseArgContext<'_, '_>) -> (Symbol, Span) {
+    fn is_name_char(chr: char) -> bool {
+        matches!(chr, 'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '-' | '.' | '+')
+    }
+
+    let (range, name) = ctx.reader.parse_with_span(|reader| reader.read_literal());
+    let span = Span::from(range);
+    if !name.chars().all(is_name_char) {
+        ctx.error(ParseError::InvalidStringChars(InvalidStringCharsError {
+            span,
+        }));
+    }
+    (ctx.interner.intern(name), span)
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::Operator;
+    use crate::{intern::Interner, parse::cst::ArgumentValue, span::Span, test_util};
+
+    #[test]
+    fn objective_name_length() {
+        let parsed = test_util::parse("scoreboard players set @s abcdefghijklmnopq 1");
+        let diagnostics = parsed.diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].span(), Span::new(26, 43));
+
+        let parsed = test_util::parse("scoreboard players set @s my_obj 1");
+        assert!(parsed.diagnostics().is_empty());
+        let arg = test_util::find_arg(&parsed, |value| {
+            matches!(value, ArgumentValue::Objective(_))
+        });
+        let ArgumentValue::Objective(objective) = arg.value else {
+            unreachable!();
+        };
+        assert_eq!(parsed.interner.resolve(objective.name), Some("my_obj"));
+        assert_eq!(objective.span, Span::new(26, 32));
+    }
+
+    #[test]
+    fn objective_and_team_spans_follow_edits() {
+        let mut parsed = test_util::parse("scoreboard players set @s my_obj 1\nteam join red @s\n");
+        parsed.edit(0..0, "say x\n");
+        assert!(parsed.diagnostics().is_empty());
+
+        let arg = test_util::find_arg(&parsed, |value| {
+            matches!(value, ArgumentValue::Objective(_))
+        });
+        let ArgumentValue::Objective(objective) = arg.value else {
+            unreachable!();
+        };
+        assert_eq!(objective.span, Span::new(32, 38));
+
+        let arg = test_util::find_arg(&parsed, |value| matches!(value, ArgumentValue::Team(_)));
+        let ArgumentValue::Team(team) = arg.value else {
+            unreachable!();
+        };
+        assert_eq!(parsed.interner.resolve(team.name), Some("red"));
+        assert_eq!(team.span, Span::new(51, 54));
+    }
+
+    fn operation(text: &str) -> (Option<Operator>, Span, Vec<String>) {
+        let parsed = test_util::parse(text);
+        let messages = parsed
+            .diagnostics()
+            .iter()
+            .map(|diagnostic| diagnostic.message().to_owned())
+            .collect();
+        let arg = test_util::find_arg(&parsed, |value| {
+            matches!(value, ArgumentValue::Operation(_))
+        });
+        let ArgumentValue::Operation(operation) = arg.value else {
+            unreachable!();
+        };
+        (operation.operator, operation.span, messages)
+    }
+
+    #[test]
+    fn operators() {
+        assert_eq!(
+            operation("scoreboard players operation @s a += @s b"),
+            (Some(Operator::Add), Span::new(34, 36), vec![])
+        );
+        assert_eq!(
+            operation("scoreboard players operation @s a >< @s b"),
+            (Some(Operator::Swap), Span::new(34, 36), vec![])
+        );
+        assert_eq!(
+            operation("scoreboard players operation @s a = @s b"),
+            (Some(Operator::Assign), Span::new(34, 35), vec![])
+        );
+    }
+
+    #[test]
+    fn invalid_operator() {
+        assert_eq!(
+            operation("scoreboard players operation @s a => @s b"),
+            (
+                None,
+                Span::new(34, 36),
+                vec!["Invalid operation".to_owned()]
+            )
+        );
+
+        let parsed = test_util::parse("scoreboard players operation @s a ");
+        let diagnostics = parsed.diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message(), "Incomplete command");
+    }
+
+    #[test]
+    fn operation_span_follows_edits() {
+        let mut parsed = test_util::parse("scoreboard players operation @s a >< @s b\n");
+        parsed.edit(0..0, "say x\n");
+        assert!(parsed.diagnostics().is_empty());
+        let arg = test_util::find_arg(&parsed, |value| {
+            matches!(value, ArgumentValue::Operation(_))
+        });
+        let ArgumentValue::Operation(operation) = arg.value else {
+            unreachable!();
+        };
+        assert_eq!(operation.span, Span::new(40, 42));
+    }
+}
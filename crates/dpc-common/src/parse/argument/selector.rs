@@ -0,0 +1,702 @@
+use std::ops::Range;
+
+use super::{
+    IntRange, ParseArgContext, ResourceLocation, primitives::parse_number_str,
+    range::parse_int_range_str, resource::intern_resource_location,
+};
+use crate::{
+    intern::{Interner, Symbol},
+    parse::{
+        Reader,
+        errors::{
+            InvalidSelectorError, InvalidSelectorErrorKind, NumberOutOfBoundsError, NumberType,
+            ParseError, ParseNumberError, SelectorNotPlayerError, SelectorNotSingleError,
+        },
+        tokenize::{skip_bracketed, skip_quoted},
+    },
+    span::Span,
+};
+
+/// The maximum length of a player name. Longer names are parsed as UUIDs.
+const MAX_PLAYER_NAME_LEN: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectorKind {
+    /// `@p`
+    NearestPlayer,
+    /// `@a`
+    AllPlayers,
+    /// `@r`
+    RandomPlayer,
+    /// `@e`
+    AllEntities,
+    /// `@s`
+    Executor,
+    /// `@n`
+    NearestEntity,
+}
+
+impl SelectorKind {
+    pub fn from_char(chr: char) -> Option<Self> {
+        match chr {
+            'p' => Some(Self::NearestPlayer),
+            'a' => Some(Self::AllPlayers),
+            'r' => Some(Self::RandomPlayer),
+            'e' => Some(Self::AllEntities),
+            's' => Some(Self::Executor),
+            'n' => Some(Self::NearestEntity),
+            _ => None,
+        }
+    }
+
+    pub fn as_char(self) -> char {
+        match self {
+            Self::NearestPlayer => 'p',
+            Self::AllPlayers => 'a',
+            Self::RandomPlayer => 'r',
+            Self::AllEntities => 'e',
+            Self::Executor => 's',
+            Self::NearestEntity => 'n',
+        }
+    }
+}
+
+/// A target selector like `@e[type=zombie,limit=3]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Selector {
+    /// The kind of the selector, or `None` if it is unknown.
+    pub kind: Option<SelectorKind>,
+    /// The span of the options, including the brackets, or `None` if there are none.
+    pub options_span: Option<Span>,
+    pub options: Vec<SelectorOption>,
+}
+
+impl Selector {
+    /// Moves all spans of the selector by `offset` bytes.
+    pub(crate) fn shift(&mut self, offset: isize) {
+        if let Some(span) = &mut self.options_span {
+            *span = span.shift(offset);
+        }
+        for option in &mut self.options {
+            if let SelectorOption::Other { value, .. } = option {
+                *value = value.shift(offset);
+            }
+        }
+    }
+
+    /// Returns the value of the `limit` option, if the selector has one.
+    pub fn limit(&self) -> Option<i32> {
+        self.options.iter().find_map(|option| match option {
+            SelectorOption::Limit(limit) => *limit,
+            _ => None,
+        })
+    }
+
+    /// Returns whether the selector selects at most one entity.
+    pub fn is_single(&self) -> bool {
+        match self.limit() {
+            Some(limit) => limit <= 1,
+            None => matches!(
+                self.kind,
+                Some(
+                    SelectorKind::NearestPlayer
+                        | SelectorKind::RandomPlayer
+                        | SelectorKind::Executor
+                        | SelectorKind::NearestEntity
+                )
+            ),
+        }
+    }
+
+    /// Returns whether the selector may select entities other than players. `@s` does not count,
+    /// since whether it is a player is only known when the command runs.
+    pub fn includes_non_players(&self, interner: &impl Interner) -> bool {
+        let is_player_type = self.options.iter().any(|option| match option {
+            SelectorOption::Type {
+                negated: false,
+                is_tag: false,
+                id: Some(id),
+            } => id.resolve(interner).as_deref() == Some("minecraft:player"),
+            _ => false,
+        });
+        matches!(
+            self.kind,
+            Some(SelectorKind::AllEntities | SelectorKind::NearestEntity)
+        ) && !is_player_type
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SelectorOption {
+    /// `scores={objective=range,...}`
+    Scores(Vec<(Symbol, IntRange)>),
+    /// `predicate=id`, or `predicate=!id` to select entities not matching the predicate.
+    Predicate {
+        negated: bool,
+        id: Option<ResourceLocation>,
+    },
+    /// `limit=count`
+    Limit(Option<i32>),
+    /// `type=id` or `type=#tag`, optionally negated with `!`.
+    Type {
+        negated: bool,
+        is_tag: bool,
+        id: Option<ResourceLocation>,
+    },
+    /// Any other option, whose value is not parsed further.
+    Other { name: Symbol, value: Span },
+}
+
+/// The target of an entity argument.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EntityTarget {
+    Selector(Selector),
+    /// The name of a player.
+    Name(Symbol),
+    Uuid(u128),
+}
+
+/// The target of a score holder argument.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScoreHolder {
+    Selector(Selector),
+    /// The name of a player or of a fake player like `#counter`.
+    Name(Symbol),
+    /// `*`, which stands for every score holder with a score.
+    Wildcard,
+}
+
+pub fn parse_entity(
+    ctx: &mut ParseArgContext<'_, '_>,
+    single: bool,
+    players_only: bool,
+) -> EntityTarget {
+    if ctx.reader.peek() == Some('@') {
+        let start = ctx.reader.get_pos();
+        let selector = parse_selector(ctx);
+        let span = Span::new(start, ctx.reader.get_pos());
+        check_selector(ctx, &selector, span, single, players_only);
+        return EntityTarget::Selector(selector);
+    }
+
+    let (range, string) = ctx.reader.parse_with_span(Reader::read_literal);
+    let span = Span::from(range);
+
+    // Like in vanilla, short strings are always names, even if they would be valid UUIDs
+    if string.chars().count() > MAX_PLAYER_NAME_LEN
+        && let Some(uuid) = parse_uuid(string)
+    {
+        if players_only {
+            ctx.error(ParseError::SelectorNotPlayer(SelectorNotPlayerError {
+                span,
+            }));
+        }
+        return EntityTarget::Uuid(uuid);
+    }
+
+    if string.is_empty()
+        || string.chars().count() > MAX_PLAYER_NAME_LEN
+        || !string.chars().all(is_name_char)
+    {
+        ctx.error(ParseError::InvalidSelector(InvalidSelectorError {
+            span,
+            kind: InvalidSelectorErrorKind::InvalidNameOrUuid,
+        }));
+    }
+    EntityTarget::Name(ctx.interner.intern(string))
+}
+
+pub fn parse_score_holder(ctx: &mut ParseArgContext<'_, '_>, single: bool) -> ScoreHolder {
+    if ctx.reader.peek() == Some('@') {
+        let start = ctx.reader.get_pos();
+        let selector = parse_selector(ctx);
+        let span = Span::new(start, ctx.reader.get_pos());
+        check_selector(ctx, &selector, span, single, false);
+        return ScoreHolder::Selector(selector);
+    }
+
+    let (range, string) = ctx.reader.parse_with_span(Reader::read_literal);
+    if string == "*" {
+        if single {
+            ctx.error(ParseError::SelectorNotSingle(SelectorNotSingleError {
+                span: range.into(),
+            }));
+        }
+        return ScoreHolder::Wildcard;
+    }
+    ScoreHolder::Name(ctx.interner.intern(string))
+}
+
+fn check_selector(
+    ctx: &mut ParseArgContext<'_, '_>,
+    selector: &Selector,
+    span: Span,
+    single: bool,
+    players_only: bool,
+) {
+    if single && !selector.is_single() {
+        ctx.error(ParseError::SelectorNotSingle(SelectorNotSingleError {
+            span,
+        }));
+    }
+    if players_only && selector.includes_non_players(ctx.interner) {
+        ctx.error(ParseError::SelectorNotPlayer(SelectorNotPlayerError {
+            span,
+        }));
+    }
+}
+
+fn parse_selector(ctx: &mut ParseArgContext<'_, '_>) -> Selector {
+    let start = ctx.reader.get_pos();
+    ctx.reader.advance();
+
+    let kind = ctx.reader.peek().and_then(SelectorKind::from_char);
+    match kind {
+        Some(_) => ctx.reader.advance(),
+        None => {
+            ctx.reader
+                .read_until(|chr| chr == '[' || chr.is_whitespace());
+            invalid_selector(
+                ctx,
+                Span::new(start, ctx.reader.get_pos()),
+                InvalidSelectorErrorKind::UnknownType,
+            );
+        }
+    }
+
+    let mut selector = Selector {
+        kind,
+        options_span: None,
+        options: Vec::new(),
+    };
+    if ctx.reader.peek() == Some('[') {
+        let options_start = ctx.reader.get_pos();
+        ctx.reader.advance();
+        parse_options(ctx, &mut selector.options, options_start);
+        selector.options_span = Some(Span::new(options_start, ctx.reader.get_pos()));
+    }
+
+    // The argument has to end here, anything else is not part of the selector
+    if ctx.reader.peek().is_some_and(|chr| !chr.is_whitespace()) {
+        let range = ctx.reader.read_range_until(char::is_whitespace);
+        invalid_selector(ctx, range.into(), InvalidSelectorErrorKind::TrailingChars);
+    }
+
+    selector
+}
+
+/// Parses the options after the opening `[` up to and including the closing `]`.
+fn parse_options(
+    ctx: &mut ParseArgContext<'_, '_>,
+    options: &mut Vec<SelectorOption>,
+    options_start: usize,
+) {
+    loop {
+        ctx.reader.skip_whitespace();
+        if ctx.reader.eat(']') {
+            return;
+        }
+        if !ctx.reader.has_more() {
+            let span = Span::new(options_start, ctx.reader.get_pos());
+            invalid_selector(ctx, span, InvalidSelectorErrorKind::Unterminated);
+            return;
+        }
+
+        let (name_range, name) = ctx.reader.parse_with_span(|reader| {
+            reader.read_until(|chr| matches!(chr, '=' | ',' | ']') || chr.is_whitespace())
+        });
+        ctx.reader.skip_whitespace();
+        if name.is_empty() || !ctx.reader.eat('=') {
+            let span = match name.is_empty() {
+                true => Span::new(ctx.reader.get_pos(), ctx.reader.get_next_pos()),
+                false => name_range.into(),
+            };
+            invalid_selector(ctx, span, InvalidSelectorErrorKind::ExpectedOption);
+            skip_options(ctx);
+            return;
+        }
+        ctx.reader.skip_whitespace();
+
+        let option = parse_option_value(ctx, name);
+        options.push(option);
+
+        ctx.reader.skip_whitespace();
+        if ctx.reader.eat(',') {
+            continue;
+        }
+        if !ctx.reader.eat(']') {
+            let span = Span::new(options_start, ctx.reader.get_pos());
+            invalid_selector(ctx, span, InvalidSelectorErrorKind::Unterminated);
+            skip_options(ctx);
+        }
+        return;
+    }
+}
+
+fn parse_option_value(ctx: &mut ParseArgContext<'_, '_>, name: &str) -> SelectorOption {
+    match name {
+        "scores" => SelectorOption::Scores(parse_scores(ctx)),
+        "predicate" => {
+            let negated = ctx.reader.eat('!');
+            SelectorOption::Predicate {
+                negated,
+                id: parse_option_location(ctx),
+            }
+        }
+        "type" => {
+            let negated = ctx.reader.eat('!');
+            let is_tag = ctx.reader.eat('#');
+            SelectorOption::Type {
+                negated,
+                is_tag,
+                id: parse_option_location(ctx),
+            }
+        }
+        "limit" => {
+            let range = read_option_value(ctx.reader);
+            let span = Span::from(range.clone());
+            let limit = parse_number_str::<i32>(&ctx.reader.get_src()[range]);
+            match limit {
+                None => ctx.error(ParseError::ParseNumber(ParseNumberError {
+                    span,
+                    kind: NumberType::Integer,
+                })),
+                Some(limit) if limit < 1 => {
+                    ctx.error(ParseError::NumberOutOfBounds(NumberOutOfBoundsError {
+                        span,
+                        min: 1.0,
+                        max: i32::MAX.into(),
+                    }))
+                }
+                Some(_) => (),
+            }
+            SelectorOption::Limit(limit)
+        }
+        _ => SelectorOption::Other {
+            name: ctx.interner.intern(name),
+            value: read_option_value(ctx.reader).into(),
+        },
+    }
+}
+
+fn parse_option_location(ctx: &mut ParseArgContext<'_, '_>) -> Option<ResourceLocation> {
+    let (range, string) = ctx.reader.parse_with_span(|reader| {
+        reader.read_until(|chr| matches!(chr, ',' | ']') || chr.is_whitespace())
+    });
+    intern_resource_location(ctx, string, range.into())
+}
+
+/// Parses the value of the `scores` option, e.g. `{kills=3..,deaths=0}`.
+fn parse_scores(ctx: &mut ParseArgContext<'_, '_>) -> Vec<(Symbol, IntRange)> {
+    let mut scores = Vec::new();
+    let start = ctx.reader.get_pos();
+    if !ctx.reader.eat('{') {
+        let span = read_option_value(ctx.reader).into();
+        invalid_selector(ctx, span, InvalidSelectorErrorKind::ExpectedScores);
+        return scores;
+    }
+
+    loop {
+        ctx.reader.skip_whitespace();
+        if ctx.reader.eat('}') {
+            return scores;
+        }
+
+        let (objective_range, objective) = ctx.reader.parse_with_span(|reader| {
+            reader.read_until(|chr| matches!(chr, '=' | ',' | '}' | ']') || chr.is_whitespace())
+        });
+        ctx.reader.skip_whitespace();
+        if objective.is_empty() || !ctx.reader.eat('=') {
+            let kind = match ctx.reader.has_more() && ctx.reader.peek() != Some(']') {
+                true => InvalidSelectorErrorKind::ExpectedScores,
+                false => InvalidSelectorErrorKind::UnterminatedScores,
+            };
+            let span = match objective.is_empty() {
+                true => Span::new(start, ctx.reader.get_pos()),
+                false => objective_range.into(),
+            };
+            invalid_selector(ctx, span, kind);
+            ctx.reader.read_until(|chr| matches!(chr, '}' | ']'));
+            ctx.reader.eat('}');
+            return scores;
+        }
+        ctx.reader.skip_whitespace();
+
+        let (range, value) = ctx.reader.parse_with_span(|reader| {
+            reader.read_until(|chr| matches!(chr, ',' | '}' | ']') || chr.is_whitespace())
+        });
+        let range = parse_int_range_str(ctx, value, range.into());
+        scores.push((ctx.interner.intern(objective), range));
+
+        ctx.reader.skip_whitespace();
+        if ctx.reader.eat(',') {
+            continue;
+        }
+        if !ctx.reader.eat('}') {
+            let span = Span::new(start, ctx.reader.get_pos());
+            invalid_selector(ctx, span, InvalidSelectorErrorKind::UnterminatedScores);
+        }
+        return scores;
+    }
+}
+
+/// Reads the value of an option up to the next `,` or `]`, skipping over nested brackets and
+/// quoted strings.
+fn read_option_value(reader: &mut Reader<'_>) -> Range<usize> {
+    let start = reader.get_pos();
+    while let Some(chr) = reader.peek() {
+        match chr {
+            '"' | '\'' => skip_quoted(reader, chr),
+            '[' | '{' | '(' => skip_bracketed(reader),
+            ',' | ']' => break,
+            _ if chr.is_whitespace() => break,
+            _ => reader.advance(),
+        }
+    }
+    start..reader.get_pos()
+}
+
+/// Skips the rest of malformed options, up to and including the closing `]` if there is one.
+fn skip_options(ctx: &mut ParseArgContext<'_, '_>) {
+    while ctx.reader.peek().is_some_and(|chr| chr != ']') {
+        read_option_value(ctx.reader);
+        if ctx.reader.peek().is_some_and(|chr| chr != ']') {
+            ctx.reader.advance();
+        }
+    }
+    ctx.reader.eat(']');
+}
+
+fn invalid_selector(ctx: &mut ParseArgContext<'_, '_>, span: Span, kind: InvalidSelectorErrorKind) {
+    ctx.error(ParseError::InvalidSelector(InvalidSelectorError {
+        span,
+        kind,
+    }));
+}
+
+fn is_name_char(chr: char) -> bool {
+    matches!(chr, 'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '-' | '.' | '+')
+}
+
+/// Parses a UUID made of five groups of hex digits separated by `-`, like Java's
+/// `UUID.fromString`, which also accepts groups with fewer digits.
+fn parse_uuid(string: &str) -> Option<u128> {
+    const GROUP_BITS: [u32; 5] = [32, 16, 16, 16, 48];
+
+    let mut groups = string.split('-');
+    let mut uuid = 0u128;
+    for bits in GROUP_BITS {
+        let group = groups.next()?;
+        if group.is_empty() || group.len() > bits as usize / 4 {
+            return None;
+        }
+        let value = u64::from_str_radix(group, 16).ok()?;
+        uuid = uuid << bits | u128::from(value);
+    }
+    groups.next().is_none().then_some(uuid)
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::{EntityTarget, ScoreHolder, Selector, SelectorKind, SelectorOption};
+    use crate::parse::argument::IntRange;
+    use crate::{Parsed, intern::Interner, parse::cst::ArgumentValue, span::Span, test_util};
+
+    fn selector(parsed: &Parsed) -> &Selector {
+        let arg = test_util::find_arg(parsed, |value| {
+            matches!(
+                value,
+                ArgumentValue::Entity(EntityTarget::Selector(_))
+                    | ArgumentValue::ScoreHolder(ScoreHolder::Selector(_))
+            )
+        });
+        match &arg.value {
+            ArgumentValue::Entity(EntityTarget::Selector(selector))
+            | ArgumentValue::ScoreHolder(ScoreHolder::Selector(selector)) => selector,
+            _ => unreachable!(),
+        }
+    }
+
+    fn messages(parsed: &Parsed) -> Vec<String> {
+        let diagnostics = parsed.diagnostics();
+        diagnostics.iter().map(|d| d.message().to_owned()).collect()
+    }
+
+    #[test]
+    fn executor_selector() {
+        let parsed = test_util::parse("kill @s");
+        assert!(parsed.diagnostics().is_empty());
+        let selector = selector(&parsed);
+        assert_eq!(selector.kind, Some(SelectorKind::Executor));
+        assert_eq!(selector.options_span, None);
+        assert!(selector.is_single());
+    }
+
+    #[test]
+    fn selector_with_options() {
+        let parsed = test_util::parse("kill @e[distance=..5]");
+        assert!(parsed.diagnostics().is_empty());
+        let selector = selector(&parsed);
+        assert_eq!(selector.kind, Some(SelectorKind::AllEntities));
+        assert_eq!(selector.options_span, Some(Span::new(7, 21)));
+        let [SelectorOption::Other { name, value }] = selector.options[..] else {
+            panic!("unexpected options {:?}", selector.options);
+        };
+        assert_eq!(parsed.interner.resolve(name), Some("distance"));
+        assert_eq!(value, Span::new(17, 20));
+    }
+
+    #[test]
+    fn wildcard_score_holder() {
+        let parsed = test_util::parse("scoreboard players reset * o");
+        assert!(parsed.diagnostics().is_empty());
+        let arg = test_util::find_arg(&parsed, |value| {
+            matches!(value, ArgumentValue::ScoreHolder(_))
+        });
+        assert_eq!(arg.value, ArgumentValue::ScoreHolder(ScoreHolder::Wildcard));
+    }
+
+    #[test]
+    fn single_and_player_targets() {
+        let parsed = test_util::parse("ride @s mount @e");
+        assert_eq!(
+            messages(&parsed),
+            ["Selector may select more than one entity"]
+        );
+        let parsed = test_util::parse("ride @s mount @e[limit=1]");
+        assert!(parsed.diagnostics().is_empty());
+
+        let parsed = test_util::parse("msg @e hi");
+        assert_eq!(
+            messages(&parsed),
+            ["Selector may select non-player entities"]
+        );
+        assert!(test_util::parse("msg @a hi").diagnostics().is_empty());
+    }
+
+    #[test]
+    fn selector_spans_follow_edits() {
+        let mut parsed = test_util::parse("kill @e[distance=..5]\n");
+        parsed.edit(0..0, "say x\n");
+        assert!(parsed.diagnostics().is_empty());
+        let selector = selector(&parsed);
+        assert_eq!(selector.options_span, Some(Span::new(13, 27)));
+        let [SelectorOption::Other { value, .. }] = selector.options[..] else {
+            panic!("unexpected options {:?}", selector.options);
+        };
+        assert_eq!(value, Span::new(23, 26));
+    }
+
+    /// Returns the objectives and ranges of the `scores` option of the selector.
+    fn scores(parsed: &Parsed) -> Vec<(&str, IntRange)> {
+        let selector = selector(parsed);
+        let [SelectorOption::Scores(scores)] = &selector.options[..] else {
+            panic!("unexpected options {:?}", selector.options);
+        };
+        scores
+            .iter()
+            .map(|(objective, range)| (parsed.interner.resolve(*objective).unwrap(), *range))
+            .collect()
+    }
+
+    #[test]
+    fn scores_option() {
+        let parsed = test_util::parse("kill @e[scores={o=1..}]");
+        assert!(parsed.diagnostics().is_empty());
+        let at_least_one = IntRange {
+            min: Some(1),
+            max: None,
+        };
+        assert_eq!(scores(&parsed), [("o", at_least_one)]);
+
+        let parsed = test_util::parse("kill @e[scores={kills=3.., deaths = 0 ,x=..-2}]");
+        assert!(parsed.diagnostics().is_empty());
+        assert_eq!(
+            scores(&parsed),
+            [
+                (
+                    "kills",
+                    IntRange {
+                        min: Some(3),
+                        max: None
+                    }
+                ),
+                (
+                    "deaths",
+                    IntRange {
+                        min: Some(0),
+                        max: Some(0)
+                    }
+                ),
+                (
+                    "x",
+                    IntRange {
+                        min: None,
+                        max: Some(-2)
+                    }
+                ),
+            ]
+        );
+        assert!(
+            test_util::parse("kill @e[scores={}]")
+                .diagnostics()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn invalid_scores_option() {
+        let labels = |text| {
+            let diagnostics = test_util::parse(text).diagnostics();
+            assert!(
+                diagnostics
+                    .iter()
+                    .all(|d| d.message() == "Invalid selector")
+            );
+            let labels = diagnostics.iter().flat_map(|d| d.labels());
+            labels
+                .map(|label| label.message().to_owned())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(labels("kill @e[scores={o=1..]"), ["Missing closing `}`"]);
+        assert_eq!(
+            labels("kill @e[scores=o]"),
+            ["Expected scores like `{objective=1..}`"]
+        );
+
+        // Values which are not ranges are reported by the range parser
+        let parsed = test_util::parse("kill @e[scores={o=abc}]");
+        assert_eq!(messages(&parsed).len(), 1);
+        assert_ne!(messages(&parsed)[0], "Invalid selector");
+        assert_eq!(scores(&parsed)[0].0, "o");
+    }
+
+    #[test]
+    fn predicate_option() {
+        let predicate = |text: &str| {
+            let parsed = test_util::parse(text);
+            assert!(
+                parsed.diagnostics().is_empty(),
+                "{:?}",
+                parsed.diagnostics()
+            );
+            let selector = selector(&parsed);
+            let [SelectorOption::Predicate { negated, id }] = selector.options[..] else {
+                panic!("unexpected options {:?}", selector.options);
+            };
+            (negated, id.and_then(|id| id.resolve(&parsed.interner)))
+        };
+        assert_eq!(
+            predicate("kill @e[predicate=ns:p]"),
+            (false, Some("ns:p".to_owned()))
+        );
+        assert_eq!(
+            predicate("kill @e[predicate=!foo:bar]"),
+            (true, Some("foo:bar".to_owned()))
+        );
+        assert_eq!(
+            predicate("kill @e[predicate=p]"),
+            (false, Some("minecraft:p".to_owned()))
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use super::{ParseArgContext, primitives::parse_number_str};
+use crate::{
+    parse::errors::{NumberType, ParseError, ParseNumberError, TimeBelowMinimumError},
+    span::Span,
+};
+
+/// A duration like `20t`, `0.5s` or `1d`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Time {
+    /// The number as written, before converting it to ticks.
+    pub value: Option<f32>,
+    /// The unit suffix, or `None` if the number has none, in which case it counts ticks.
+    pub unit: Option<TimeUnit>,
+    /// The duration in ticks, rounded to the nearest tick.
+    pub ticks: Option<i32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeUnit {
+    Ticks,
+    Seconds,
+    Days,
+}
+
+impl TimeUnit {
+    pub fn from_suffix(suffix: char) -> Option<Self> {
+        match suffix {
+            't' => Some(Self::Ticks),
+            's' => Some(Self::Seconds),
+            'd' => Some(Self::Days),
+            _ => None,
+        }
+    }
+
+    pub fn suffix(self) -> char {
+        match self {
+            Self::Ticks => 't',
+            Self::Seconds => 's',
+            Self::Days => 'd',
+        }
+    }
+
+    pub fn ticks(self) -> i32 {
+        match self {
+            Self::Ticks => 1,
+            Self::Seconds => 20,
+            Self::Days => 24000,
+        }
+    }
+}
+
+pub fn parse(ctx: &mut ParseArgContext<'_, '_>, min: i32) -> Time {
+    let (range, string) = ctx.reader.parse_with_span(|reader| reader.read_literal());
+    let span = Span::from(range);
+
+    let unit = string.chars().last().and_then(TimeUnit::from_suffix);
+    let number = match unit {
+        Some(_) => &string[..string.len() - 1],
+        None => string,
+    };
+
+    let Some(value) = parse_number_str::<f32>(number) else {
+        ctx.error(ParseError::ParseNumber(ParseNumberError {
+            span,
+            kind: NumberType::Float,
+        }));
+        return Time {
+            value: None,
+            unit,
+            ticks: None,
+        };
+    };
+
+    let ticks = (value * unit.map_or(1, TimeUnit::ticks) as f32).round() as i32;
+    if ticks < min {
+        ctx.error(ParseError::TimeBelowMinimum(TimeBelowMinimumError {
+            span,
+            min,
+            ticks,
+        }));
+    }
+
+    Time {
+        value: Some(value),
+        unit,
+        ticks: Some(ticks),
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::{Time, TimeUnit};
+    use crate::{parse::cst::ArgumentValue, test_util};
+
+    fn parse_time(text: &str) -> (Time, usize) {
+        let parsed = test_util::parse(text);
+        let arg = test_util::find_arg(&parsed, |value| matches!(value, ArgumentValue::Time(_)));
+        let ArgumentValue::Time(time) = arg.value else {
+            unreachable!();
+        };
+        (time, parsed.diagnostics().len())
+    }
+
+    #[test]
+    fn units_are_converted_to_ticks() {
+        let cases = [
+            ("time add 20t", 20.0, Some(TimeUnit::Ticks), 20),
+            ("time add 1d", 1.0, Some(TimeUnit::Days), 24000),
+            ("time add 0.5s", 0.5, Some(TimeUnit::Seconds), 10),
+            ("time add 100", 100.0, None, 100),
+        ];
+        for (text, value, unit, ticks) in cases {
+            let expected = Time {
+                value: Some(value),
+                unit,
+                ticks: Some(ticks),
+            };
+            assert_eq!(parse_time(text), (expected, 0), "{text}");
+        }
+    }
+
+    #[test]
+    fn time_below_minimum() {
+        let (time, errors) = parse_time("weather clear 0t");
+        assert_eq!(time.ticks, Some(0));
+        assert_eq!(errors, 1);
+        assert_eq!(parse_time("weather clear 1t").1, 0);
+        assert_eq!(parse_time("time add 0").1, 0);
+    }
+}
@@ -1,28 +1,86 @@
-use std::sync::Arc;
+use std::{borrow::Cow, ops::Range, sync::Arc};
 
 use crate::{
     ParsingTree,
     intern::StaticInterner,
-    parse::{cst::Block, errors::ParseError},
+    parse::{
+        cst::{Block, Command},
+        errors::ParseError,
+    },
     source::SourceFile,
 };
 
+#[derive(Debug, Default, Clone)]
+pub struct ParseOptions {
+    /// Literal paths of commands that must not be used. A command is forbidden if its literal path
+    /// starts with one of these paths, e.g. `["gamerule"]` forbids every `gamerule` command.
+    pub denied_commands: Vec<Vec<String>>,
+    /// If set, tabs in indentation count as this many spaces. Otherwise indenting with tabs is an
+    /// error.
+    pub expand_tabs: Option<usize>,
+    /// If set, a misspelled literal which closely resembles a valid one is parsed as if it was
+    /// that literal, so the rest of the command is still available. The invalid literal is
+    /// reported nonetheless.
+    pub recover: bool,
+    /// The maximum number of argument candidates tried for a single command. Once it is reached,
+    /// the best candidate found so far is used and a warning is reported. This keeps parsing fast
+    /// on inputs which are ambiguous in many places.
+    pub max_candidates: Option<usize>,
+    /// If set, the source is treated as still being typed, e.g. in an editor. Errors which reach
+    /// the end of a command, like an unterminated string or a missing command after `run`, are
+    /// not reported, since they will likely be resolved by the following input.
+    pub partial: bool,
+}
+
 pub struct ParseContext<'src> {
-    pub source: &'src SourceFile,
+    /// The source to parse. It is only copied once it is edited by
+    /// [`reparse_range`](Self::reparse_range).
+    pub source: Cow<'src, SourceFile>,
     pub tree: Arc<ParsingTree>,
     pub interner: StaticInterner,
+    pub options: ParseOptions,
+    /// The result of the previous parse of the source, whose commands are reused by
+    /// [`reparse_range`](Self::reparse_range).
+    pub previous: Option<Block>,
+    /// The number of argument candidates which may still be tried for the current command.
+    pub(crate) remaining_candidates: Option<usize>,
+    /// Where the current command first ran out of argument candidates.
+    pub(crate) exceeded_candidates_at: Option<usize>,
 }
 
 impl<'src> ParseContext<'src> {
     pub fn new(source: &'src SourceFile, parse_tree: Arc<ParsingTree>) -> Self {
         Self {
-            source,
+            source: Cow::Borrowed(source),
             tree: parse_tree,
             interner: StaticInterner::new(),
+            options: ParseOptions::default(),
+            previous: None,
+            remaining_candidates: None,
+            exceeded_candidates_at: None,
         }
     }
 
     pub fn parse(&mut self) -> Result<Block, ParseError> {
         Arc::clone(&self.tree).parse(self)
     }
+
+    /// Replaces the bytes in `edit` by `new_text` and parses the source again. The commands of
+    /// [`previous`](Self::previous) which are not affected by the edit are reused, see
+    /// [`ParsingTree::reparse`]. Without a previous result, the whole source is parsed.
+    pub fn reparse_range(
+        &mut self,
+        edit: Range<usize>,
+        new_text: &str,
+    ) -> Result<Block, ParseError> {
+        self.source.to_mut().replace_range(edit.clone(), new_text);
+        match self.previous.take() {
+            Some(previous) => Arc::clone(&self.tree).reparse(self, previous, edit, new_text.len()),
+            None => self.parse(),
+        }
+    }
+
+    pub fn parse_single_command(&mut self) -> Option<Command> {
+        Arc::clone(&self.tree).parse_single_command(self)
+    }
 }
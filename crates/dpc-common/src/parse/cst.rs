@@ -1,21 +1,93 @@
+use std::{any::Any, iter};
+
 use smallvec::SmallVec;
 
-use super::argument::{Angle, Boolean, Color, Coordinates, Double, Float, Integer, Text};
-use crate::{parse::errors::ParseError, span::Span};
+use super::argument::{
+    Angle, Boolean, Color, Coordinates, Double, EntityAnchor, EntityTarget, Float, FunctionRef,
+    Heightmap, IntRange, Integer, ItemSlot, ItemStack, Nbt, NbtPath, Objective, Operation,
+    Particle, ResourceLocation, ResourceRef, ScoreHolder, Selector, Team, Text, Time,
+};
+#[cfg(feature = "serde")]
+pub use super::json::to_json;
+use crate::{Node, ParsingTree, parse::errors::ParseError, span::Span};
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub enum Item {
     Command(Command),
     Comment(Span),
 }
 
-#[derive(Debug)]
+impl Item {
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Command(command) => command.span(),
+            Self::Comment(span) => *span,
+        }
+    }
+
+    /// Moves all spans of the item by `offset` bytes. Spans within custom values are not moved.
+    pub(crate) fn shift(&mut self, offset: isize) {
+        match self {
+            Self::Command(command) => {
+                for arg in &mut command.args {
+                    arg.shift(offset);
+                }
+                if let Some(error) = &mut command.error {
+                    *error.span_mut() = error.span().shift(offset);
+                }
+            }
+            Self::Comment(span) => *span = span.shift(offset),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
 pub struct Command {
     pub args: Vec<Argument>,
     pub error: Option<ParseError>,
 }
 
-#[derive(Debug)]
+impl Command {
+    /// Returns the span covering all arguments and the error of this command.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the command has neither arguments nor an error, which is never the case for
+    /// parsed commands.
+    pub fn span(&self) -> Span {
+        self.args
+            .iter()
+            .map(|arg| arg.span)
+            .chain(self.error.as_ref().map(ParseError::span))
+            .reduce(|a, b| Span::new(a.start.min(b.start), a.end.max(b.end)))
+            .expect("command must not be empty")
+    }
+
+    /// Returns the names of all literal arguments of this command, in order.
+    pub fn literal_path<'t>(&self, tree: &'t ParsingTree) -> Vec<&'t str> {
+        self.args
+            .iter()
+            .filter(|arg| matches!(arg.value, ArgumentValue::Literal))
+            .filter_map(|arg| tree.get_node(arg.lin_node_id))
+            .map(Node::name)
+            .collect()
+    }
+
+    /// Returns the spans of the whitespace between consecutive arguments, so that the original
+    /// spacing can be reproduced. There is one gap less than there are arguments.
+    pub fn gaps(&self) -> impl Iterator<Item = Span> + '_ {
+        self.args
+            .windows(2)
+            .map(|args| Span::new(args[0].span.end, args[1].span.start))
+    }
+
+    /// Returns the block of this command, if it has one. A block is always the last argument.
+    pub fn block_arg(&self) -> Option<&Block> {
+        self.args.last().and_then(Argument::block)
+    }
+}
+
+#[derive(Debug, PartialEq)]
 pub struct Argument {
     pub span: Span,
     pub lin_node_id: usize,
@@ -27,9 +99,24 @@ impl Argument {
     pub fn has_errors(&self) -> bool {
         !self.errors.is_empty()
     }
+
+    pub fn block(&self) -> Option<&Block> {
+        match &self.value {
+            ArgumentValue::Block(block) => Some(block),
+            _ => None,
+        }
+    }
+
+    fn shift(&mut self, offset: isize) {
+        self.span = self.span.shift(offset);
+        for error in &mut self.errors {
+            *error.span_mut() = error.span().shift(offset);
+        }
+        self.value.shift(offset);
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub enum ArgumentValue {
     Literal,
     Block(Block),
@@ -42,24 +129,227 @@ pub enum ArgumentValue {
     Coordinates2(Coordinates<2>),
     Coordinates3(Coordinates<3>),
     Color(Color),
+    Function(FunctionRef),
+    Objective(Objective),
+    Operation(Operation),
+    Team(Team),
+    Time(Time),
+    IntRange(IntRange),
+    Entity(EntityTarget),
+    ScoreHolder(ScoreHolder),
+    EntityAnchor(EntityAnchor),
+    Heightmap(Heightmap),
+    ItemSlot(ItemSlot),
+    ItemStack(ItemStack),
+    Nbt(Nbt),
+    NbtPath(NbtPath),
+    Resource(ResourceRef),
+    ResourceLocation(Option<ResourceLocation>),
+    Particle(Particle),
+    Custom(CustomValue),
+}
+
+impl ArgumentValue {
+    /// Moves all spans within the value by `offset` bytes. Custom values are opaque, so their
+    /// spans are not moved.
+    fn shift(&mut self, offset: isize) {
+        match self {
+            Self::Block(block) => {
+                block.span = block.span.shift(offset);
+                for item in &mut block.items {
+                    item.shift(offset);
+                }
+            }
+            Self::Objective(Objective { span, .. })
+            | Self::Team(Team { span, .. })
+            | Self::Operation(Operation { span, .. })
+            | Self::EntityAnchor(EntityAnchor { span, .. })
+            | Self::Heightmap(Heightmap { span, .. })
+            | Self::ItemSlot(ItemSlot { span, .. })
+            | Self::Particle(Particle { params: span, .. }) => *span = span.shift(offset),
+            Self::Entity(EntityTarget::Selector(selector))
+            | Self::ScoreHolder(ScoreHolder::Selector(selector)) => selector.shift(offset),
+            Self::Literal
+            | Self::Boolean(_)
+            | Self::Integer(_)
+            | Self::Float(_)
+            | Self::Double(_)
+            | Self::String(_)
+            | Self::Angle(_)
+            | Self::Coordinates2(_)
+            | Self::Coordinates3(_)
+            | Self::Color(_)
+            | Self::Function(_)
+            | Self::Time(_)
+            | Self::IntRange(_)
+            | Self::Entity(_)
+            | Self::ScoreHolder(_)
+            | Self::ItemStack(_)
+            | Self::Nbt(_)
+            | Self::NbtPath(_)
+            | Self::Resource(_)
+            | Self::ResourceLocation(_)
+            | Self::Custom(_) => (),
+        }
+    }
+
+    pub fn kind(&self) -> ArgumentValueKind {
+        match self {
+            Self::Literal => ArgumentValueKind::Literal,
+            Self::Block(_) => ArgumentValueKind::Block,
+            Self::Boolean(_) => ArgumentValueKind::Boolean,
+            Self::Integer(_) => ArgumentValueKind::Integer,
+            Self::Float(_) => ArgumentValueKind::Float,
+            Self::Double(_) => ArgumentValueKind::Double,
+            Self::String(_) => ArgumentValueKind::String,
+            Self::Angle(_) => ArgumentValueKind::Angle,
+            Self::Coordinates2(_) => ArgumentValueKind::Coordinates2,
+            Self::Coordinates3(_) => ArgumentValueKind::Coordinates3,
+            Self::Color(_) => ArgumentValueKind::Color,
+            Self::Function(_) => ArgumentValueKind::Function,
+            Self::Objective(_) => ArgumentValueKind::Objective,
+            Self::Operation(_) => ArgumentValueKind::Operation,
+            Self::Team(_) => ArgumentValueKind::Team,
+            Self::Time(_) => ArgumentValueKind::Time,
+            Self::IntRange(_) => ArgumentValueKind::IntRange,
+            Self::Entity(_) => ArgumentValueKind::Entity,
+            Self::ScoreHolder(_) => ArgumentValueKind::ScoreHolder,
+            Self::EntityAnchor(_) => ArgumentValueKind::EntityAnchor,
+            Self::Heightmap(_) => ArgumentValueKind::Heightmap,
+            Self::ItemSlot(_) => ArgumentValueKind::ItemSlot,
+            Self::ItemStack(_) => ArgumentValueKind::ItemStack,
+            Self::Nbt(_) => ArgumentValueKind::Nbt,
+            Self::NbtPath(_) => ArgumentValueKind::NbtPath,
+            Self::Resource(_) => ArgumentValueKind::Resource,
+            Self::ResourceLocation(_) => ArgumentValueKind::ResourceLocation,
+            Self::Particle(_) => ArgumentValueKind::Particle,
+            Self::Custom(_) => ArgumentValueKind::Custom,
+        }
+    }
 }
 
+/// The variant of an [`ArgumentValue`], without its data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ArgumentValueKind {
+    Literal,
+    Block,
+    Boolean,
+    Integer,
+    Float,
+    Double,
+    String,
+    Angle,
+    Coordinates2,
+    Coordinates3,
+    Color,
+    Function,
+    Objective,
+    Operation,
+    Team,
+    Time,
+    IntRange,
+    Entity,
+    ScoreHolder,
+    EntityAnchor,
+    Heightmap,
+    ItemSlot,
+    ItemStack,
+    Nbt,
+    NbtPath,
+    Resource,
+    ResourceLocation,
+    Particle,
+    Custom,
+}
+
+/// The value produced by a [`CustomArgument`](super::argument::CustomArgument).
+///
+/// Custom values are opaque, therefore they never compare equal.
 #[derive(Debug)]
+pub struct CustomValue(pub Box<dyn Any + Send + Sync>);
+
+impl PartialEq for CustomValue {
+    fn eq(&self, _: &Self) -> bool {
+        false
+    }
+}
+
+#[derive(Debug, PartialEq)]
 pub struct Block {
+    /// The range of lines the block consists of, including the indentation of its first line. The
+    /// top-level block spans the whole file.
+    pub span: Span,
     pub items: Vec<Item>,
 }
 
+impl Block {
+    /// Returns the commands directly in this block, without those in nested blocks.
+    pub fn commands(&self) -> impl Iterator<Item = &Command> {
+        self.items.iter().filter_map(|item| match item {
+            Item::Command(command) => Some(command),
+            Item::Comment(_) => None,
+        })
+    }
+
+    /// Returns the spans of the comments directly in this block.
+    pub fn comments(&self) -> impl Iterator<Item = &Span> {
+        self.items.iter().filter_map(|item| match item {
+            Item::Comment(span) => Some(span),
+            Item::Command(_) => None,
+        })
+    }
+
+    /// Returns all commands of this block and its nested blocks, e.g. those of `execute run`, in
+    /// source order. Like [`walk_block`], a command comes before the commands nested in it.
+    pub fn all_commands(&self) -> impl Iterator<Item = &Command> {
+        let mut stack = vec![self.items.iter()];
+        iter::from_fn(move || {
+            while let Some(items) = stack.last_mut() {
+                match items.next() {
+                    Some(Item::Command(command)) => {
+                        let blocks = command.args.iter().filter_map(Argument::block);
+                        stack.extend(blocks.rev().map(|block| block.items.iter()));
+                        return Some(command);
+                    }
+                    Some(Item::Comment(_)) => (),
+                    None => {
+                        stack.pop();
+                    }
+                }
+            }
+            None
+        })
+    }
+}
+
 pub trait Visitor: Sized {
     fn visit_comment(&mut self, _comment: &Span) {}
+    fn visit_command(&mut self, command: &Command) {
+        walk_command(self, command);
+    }
     fn visit_argument(&mut self, argument: &Argument) {
         walk_argument(self, argument);
     }
+    fn visit_objective(&mut self, _objective: &Objective) {}
+    fn visit_operation(&mut self, _operation: &Operation) {}
+    fn visit_team(&mut self, _team: &Team) {}
+    fn visit_time(&mut self, _time: &Time) {}
+    fn visit_int_range(&mut self, _range: &IntRange) {}
+    fn visit_selector(&mut self, _selector: &Selector) {}
+    fn visit_entity_anchor(&mut self, _anchor: &EntityAnchor) {}
+    fn visit_heightmap(&mut self, _heightmap: &Heightmap) {}
+    fn visit_item_slot(&mut self, _slot: &ItemSlot) {}
+    fn visit_item_stack(&mut self, _stack: &ItemStack) {}
+    fn visit_nbt(&mut self, _nbt: &Nbt) {}
+    fn visit_nbt_path(&mut self, _path: &NbtPath) {}
+    fn visit_resource(&mut self, _resource: &ResourceRef) {}
+    fn visit_particle(&mut self, _particle: &Particle) {}
     fn visit_parse_error(&mut self, _error: &ParseError) {}
 }
 
 pub fn walk_item(visitor: &mut impl Visitor, item: &Item) {
     match item {
-        Item::Command(command) => walk_command(visitor, command),
+        Item::Command(command) => visitor.visit_command(command),
         Item::Comment(comment) => visitor.visit_comment(comment),
     }
 }
@@ -78,8 +368,26 @@ pub fn walk_argument(visitor: &mut impl Visitor, argument: &Argument) {
         visitor.visit_parse_error(error);
     }
 
-    if let ArgumentValue::Block(block) = &argument.value {
-        walk_block(visitor, block);
+    match &argument.value {
+        ArgumentValue::Block(block) => walk_block(visitor, block),
+        ArgumentValue::Objective(objective) => visitor.visit_objective(objective),
+        ArgumentValue::Operation(operation) => visitor.visit_operation(operation),
+        ArgumentValue::Team(team) => visitor.visit_team(team),
+        ArgumentValue::Time(time) => visitor.visit_time(time),
+        ArgumentValue::IntRange(range) => visitor.visit_int_range(range),
+        ArgumentValue::EntityAnchor(anchor) => visitor.visit_entity_anchor(anchor),
+        ArgumentValue::Heightmap(heightmap) => visitor.visit_heightmap(heightmap),
+        ArgumentValue::ItemSlot(slot) => visitor.visit_item_slot(slot),
+        ArgumentValue::ItemStack(stack) => visitor.visit_item_stack(stack),
+        ArgumentValue::Nbt(nbt) => visitor.visit_nbt(nbt),
+        ArgumentValue::NbtPath(path) => visitor.visit_nbt_path(path),
+        ArgumentValue::Resource(resource) => visitor.visit_resource(resource),
+        ArgumentValue::Particle(particle) => visitor.visit_particle(particle),
+        ArgumentValue::Entity(EntityTarget::Selector(selector))
+        | ArgumentValue::ScoreHolder(ScoreHolder::Selector(selector)) => {
+            visitor.visit_selector(selector)
+        }
+        _ => (),
     }
 }
 
@@ -88,3 +396,126 @@ pub fn walk_block(visitor: &mut impl Visitor, block: &Block) {
         walk_item(visitor, item);
     }
 }
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use smallvec::smallvec;
+
+    use super::{Argument, ArgumentValue, Block, Command, Item};
+    use crate::{intern::Interner, parse::argument::Text, span::Span, test_util};
+
+    #[test]
+    fn parsed_tree_equals_expected_tree() {
+        let mut parsed = test_util::parse("help foo\n# done\n");
+        let tree = &parsed.tree;
+        let foo = parsed.interner.intern("foo");
+
+        let expected = Block {
+            span: Span::new(0, 16),
+            items: vec![
+                Item::Command(Command {
+                    args: vec![
+                        Argument {
+                            span: Span::new(0, 4),
+                            lin_node_id: tree.find_node(["help"]).unwrap(),
+                            value: ArgumentValue::Literal,
+                            errors: smallvec![],
+                        },
+                        Argument {
+                            span: Span::new(5, 8),
+                            lin_node_id: tree.find_node(["help", "command"]).unwrap(),
+                            value: ArgumentValue::String(Text {
+                                value: Some(foo),
+                                is_quotable: false,
+                            }),
+                            errors: smallvec![],
+                        },
+                    ],
+                    error: None,
+                }),
+                Item::Comment(Span::new(9, 15)),
+            ],
+        };
+        assert_eq!(parsed.result, Ok(expected));
+    }
+
+    #[test]
+    fn block_iterators() {
+        let text = "# first\nsay a\nexecute as @a run\n    # nested\n    say b\n    execute run say c\nsay d\n";
+        let parsed = test_util::parse(text);
+        assert!(parsed.diagnostics().is_empty());
+        let block = parsed.result.as_ref().unwrap();
+        let tree = &parsed.tree;
+
+        let comments: Vec<_> = block.comments().copied().collect();
+        assert_eq!(comments, [Span::new(0, 7)]);
+
+        let paths = |commands: Vec<&Command>| -> Vec<String> {
+            let paths = commands
+                .iter()
+                .map(|command| command.literal_path(tree).join(" "));
+            paths.collect()
+        };
+        assert_eq!(
+            paths(block.commands().collect()),
+            ["say", "execute as run", "say"]
+        );
+        assert_eq!(
+            paths(block.all_commands().collect()),
+            ["say", "execute as run", "say", "execute run", "say", "say"]
+        );
+        assert_eq!(block.all_commands().count(), 6);
+    }
+
+    #[test]
+    fn nested_block_span_covers_its_lines() {
+        let text = "say a\nexecute run\n    say b\n    say c\nsay d\n";
+        let parsed = test_util::parse(text);
+        assert!(parsed.diagnostics().is_empty());
+        let block = parsed.result.as_ref().unwrap();
+        assert_eq!(block.span, Span::new(0, text.len()));
+
+        let Some(Item::Command(execute)) = block.items.get(1) else {
+            panic!("expected a command");
+        };
+        let ArgumentValue::Block(nested) = &execute.args.last().unwrap().value else {
+            panic!("expected a block");
+        };
+        assert_eq!(&text[nested.span.as_range()], "    say b\n    say c");
+    }
+
+    #[test]
+    fn block_accessors() {
+        let parsed = test_util::parse("execute run\n    say a\n    say b\nsay c\n");
+        assert!(parsed.diagnostics().is_empty());
+        let block = parsed.result.as_ref().unwrap();
+        let commands: Vec<_> = block.commands().collect();
+
+        let nested = commands[0].block_arg().unwrap();
+        assert_eq!(nested.commands().count(), 2);
+        assert_eq!(commands[0].args.last().unwrap().block(), Some(nested));
+        assert!(commands[0].args[0].block().is_none());
+
+        assert!(commands[1].block_arg().is_none());
+        assert!(commands[1].args.iter().all(|arg| arg.block().is_none()));
+    }
+
+    #[test]
+    fn gap_widths() {
+        let text = "say  hi\nexecute   as @s\trun say x\ntime set day\n";
+        let parsed = test_util::parse(text);
+        assert!(parsed.diagnostics().is_empty());
+        let widths: Vec<Vec<usize>> = parsed
+            .result
+            .as_ref()
+            .unwrap()
+            .commands()
+            .map(|command| command.gaps().map(Span::len).collect())
+            .collect();
+        assert_eq!(widths, [vec![2], vec![3, 1, 1, 1], vec![1, 1]]);
+
+        let execute = parsed.result.as_ref().unwrap().commands().nth(1).unwrap();
+        let gaps: Vec<_> = execute.gaps().map(|gap| &text[gap.as_range()]).collect();
+        assert_eq!(gaps, ["   ", " ", "\t", " "]);
+    }
+}
@@ -3,8 +3,15 @@ use std::{fmt, ops::Range};
 use ariadne::{Color, Fmt};
 
 use crate::{
-    diagnostics::{Diagnostic, Label},
-    parse::ParseContext,
+    NodeKind,
+    diagnostics::{Diagnostic, Label, Level},
+    parse::{
+        ParseContext,
+        argument::{
+            EntityAnchorKind, HeightmapKind, ItemSlotCategory, MAX_OBJECTIVE_NAME_LEN, Operator,
+        },
+        cst,
+    },
     span::Span,
 };
 
@@ -18,11 +25,33 @@ impl<T: EmitDiagnostic> EmitDiagnostic for &T {
     }
 }
 
-#[derive(Debug)]
+/// Collects the diagnostics of all parse errors in a syntax tree, in the order they are visited.
+pub struct ParseErrorVisitor<'a> {
+    ctx: &'a ParseContext<'a>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> ParseErrorVisitor<'a> {
+    pub fn new(ctx: &'a ParseContext<'a>) -> Self {
+        Self {
+            ctx,
+            diagnostics: Vec::new(),
+        }
+    }
+}
+
+impl cst::Visitor for ParseErrorVisitor<'_> {
+    fn visit_parse_error(&mut self, error: &ParseError) {
+        self.diagnostics.push(error.emit(self.ctx));
+    }
+}
+
+#[derive(Debug, PartialEq)]
 pub enum ParseError {
     Indentation(IndentationError),
     InvalidLiteral(InvalidLiteralError),
     TooManyArguments(TooManyArgumentsError),
+    TrailingArgumentChars(TrailingArgumentCharsError),
     ParseBool(ParseBoolError),
     ParseNumber(ParseNumberError),
     NumberOutOfBounds(NumberOutOfBoundsError),
@@ -33,6 +62,26 @@ pub enum ParseError {
     ExpectedLocalCoordinate(ExpectedLocalCoordinateError),
     MixedCoordinates(MixedCoordiantesError),
     InvalidColor(InvalidColorError),
+    InvalidHexColor(InvalidHexColorError),
+    UnknownItemSlot(UnknownItemSlotError),
+    UnterminatedCompound(UnterminatedCompoundError),
+    UnexpectedNbtToken(UnexpectedNbtTokenError),
+    TagNotAllowed(TagNotAllowedError),
+    InvalidResourceLocation(InvalidResourceLocationError),
+    ObjectiveNameTooLong(ObjectiveNameTooLongError),
+    InvalidOperation(InvalidOperationError),
+    InvalidHeightmap(InvalidHeightmapError),
+    InvalidEntityAnchor(InvalidEntityAnchorError),
+    TimeBelowMinimum(TimeBelowMinimumError),
+    EmptyRange(EmptyRangeError),
+    ReversedRange(ReversedRangeError),
+    InvalidSelector(InvalidSelectorError),
+    SelectorNotSingle(SelectorNotSingleError),
+    SelectorNotPlayer(SelectorNotPlayerError),
+    ForbiddenCommand(ForbiddenCommandError),
+    MissingRunBody(MissingRunBodyError),
+    IncompleteCommand(IncompleteCommandError),
+    ParseComplexity(ParseComplexityWarning),
 }
 
 impl EmitDiagnostic for ParseError {
@@ -41,6 +90,7 @@ impl EmitDiagnostic for ParseError {
             Self::Indentation(error) => error.emit(ctx),
             Self::InvalidLiteral(error) => error.emit(ctx),
             Self::TooManyArguments(error) => error.emit(ctx),
+            Self::TrailingArgumentChars(error) => error.emit(ctx),
             Self::ParseBool(error) => error.emit(ctx),
             Self::ParseNumber(error) => error.emit(ctx),
             Self::NumberOutOfBounds(error) => error.emit(ctx),
@@ -51,35 +101,195 @@ impl EmitDiagnostic for ParseError {
             Self::ExpectedLocalCoordinate(error) => error.emit(ctx),
             Self::MixedCoordinates(error) => error.emit(ctx),
             Self::InvalidColor(error) => error.emit(ctx),
+            Self::InvalidHexColor(error) => error.emit(ctx),
+            Self::UnknownItemSlot(error) => error.emit(ctx),
+            Self::UnterminatedCompound(error) => error.emit(ctx),
+            Self::UnexpectedNbtToken(error) => error.emit(ctx),
+            Self::TagNotAllowed(error) => error.emit(ctx),
+            Self::InvalidResourceLocation(error) => error.emit(ctx),
+            Self::ObjectiveNameTooLong(error) => error.emit(ctx),
+            Self::InvalidOperation(error) => error.emit(ctx),
+            Self::InvalidHeightmap(error) => error.emit(ctx),
+            Self::InvalidEntityAnchor(error) => error.emit(ctx),
+            Self::TimeBelowMinimum(error) => error.emit(ctx),
+            Self::EmptyRange(error) => error.emit(ctx),
+            Self::ReversedRange(error) => error.emit(ctx),
+            Self::InvalidSelector(error) => error.emit(ctx),
+            Self::SelectorNotSingle(error) => error.emit(ctx),
+            Self::SelectorNotPlayer(error) => error.emit(ctx),
+            Self::ForbiddenCommand(error) => error.emit(ctx),
+            Self::MissingRunBody(error) => error.emit(ctx),
+            Self::IncompleteCommand(error) => error.emit(ctx),
+            Self::ParseComplexity(error) => error.emit(ctx),
+        }
+    }
+}
+
+impl ParseError {
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Indentation(error) => error.span,
+            Self::InvalidLiteral(error) => error.span,
+            Self::TooManyArguments(error) => error.span,
+            Self::TrailingArgumentChars(error) => error.span,
+            Self::ParseBool(error) => error.span,
+            Self::ParseNumber(error) => error.span,
+            Self::NumberOutOfBounds(error) => error.span,
+            Self::UnterminatedString(error) => error.span,
+            Self::InvalidStringChars(error) => error.span,
+            Self::QuotedSingleWord(error) => error.span,
+            Self::IncompleteLocalCoordinates(error) => error.span,
+            Self::ExpectedLocalCoordinate(error) => error.span,
+            Self::MixedCoordinates(error) => error.span,
+            Self::InvalidColor(error) => error.span,
+            Self::InvalidHexColor(error) => error.span,
+            Self::UnknownItemSlot(error) => error.span,
+            Self::UnterminatedCompound(error) => error.span,
+            Self::UnexpectedNbtToken(error) => error.span,
+            Self::TagNotAllowed(error) => error.span,
+            Self::InvalidResourceLocation(error) => error.span,
+            Self::ObjectiveNameTooLong(error) => error.span,
+            Self::InvalidOperation(error) => error.span,
+            Self::InvalidHeightmap(error) => error.span,
+            Self::InvalidEntityAnchor(error) => error.span,
+            Self::TimeBelowMinimum(error) => error.span,
+            Self::EmptyRange(error) => error.span,
+            Self::ReversedRange(error) => error.span,
+            Self::InvalidSelector(error) => error.span,
+            Self::SelectorNotSingle(error) => error.span,
+            Self::SelectorNotPlayer(error) => error.span,
+            Self::ForbiddenCommand(error) => error.span,
+            Self::MissingRunBody(error) => error.span,
+            Self::IncompleteCommand(error) => error.span,
+            Self::ParseComplexity(error) => error.span,
+        }
+    }
+
+    pub(crate) fn span_mut(&mut self) -> &mut Span {
+        match self {
+            Self::Indentation(error) => &mut error.span,
+            Self::InvalidLiteral(error) => &mut error.span,
+            Self::TooManyArguments(error) => &mut error.span,
+            Self::TrailingArgumentChars(error) => &mut error.span,
+            Self::ParseBool(error) => &mut error.span,
+            Self::ParseNumber(error) => &mut error.span,
+            Self::NumberOutOfBounds(error) => &mut error.span,
+            Self::UnterminatedString(error) => &mut error.span,
+            Self::InvalidStringChars(error) => &mut error.span,
+            Self::QuotedSingleWord(error) => &mut error.span,
+            Self::IncompleteLocalCoordinates(error) => &mut error.span,
+            Self::ExpectedLocalCoordinate(error) => &mut error.span,
+            Self::MixedCoordinates(error) => &mut error.span,
+            Self::InvalidColor(error) => &mut error.span,
+            Self::InvalidHexColor(error) => &mut error.span,
+            Self::UnknownItemSlot(error) => &mut error.span,
+            Self::UnterminatedCompound(error) => &mut error.span,
+            Self::UnexpectedNbtToken(error) => &mut error.span,
+            Self::TagNotAllowed(error) => &mut error.span,
+            Self::InvalidResourceLocation(error) => &mut error.span,
+            Self::ObjectiveNameTooLong(error) => &mut error.span,
+            Self::InvalidOperation(error) => &mut error.span,
+            Self::InvalidHeightmap(error) => &mut error.span,
+            Self::InvalidEntityAnchor(error) => &mut error.span,
+            Self::TimeBelowMinimum(error) => &mut error.span,
+            Self::EmptyRange(error) => &mut error.span,
+            Self::ReversedRange(error) => &mut error.span,
+            Self::InvalidSelector(error) => &mut error.span,
+            Self::SelectorNotSingle(error) => &mut error.span,
+            Self::SelectorNotPlayer(error) => &mut error.span,
+            Self::ForbiddenCommand(error) => &mut error.span,
+            Self::MissingRunBody(error) => &mut error.span,
+            Self::IncompleteCommand(error) => &mut error.span,
+            Self::ParseComplexity(error) => &mut error.span,
         }
     }
 }
 
-#[derive(Debug)]
+/// Writes the primary message of the error, without any source snippets. Use
+/// [`EmitDiagnostic::emit`] for a detailed report.
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Indentation(error) => error.fmt(f),
+            Self::InvalidLiteral(error) => error.fmt(f),
+            Self::TooManyArguments(error) => error.fmt(f),
+            Self::TrailingArgumentChars(error) => error.fmt(f),
+            Self::ParseBool(error) => error.fmt(f),
+            Self::ParseNumber(error) => error.fmt(f),
+            Self::NumberOutOfBounds(error) => error.fmt(f),
+            Self::UnterminatedString(error) => error.fmt(f),
+            Self::InvalidStringChars(error) => error.fmt(f),
+            Self::QuotedSingleWord(error) => error.fmt(f),
+            Self::IncompleteLocalCoordinates(error) => error.fmt(f),
+            Self::ExpectedLocalCoordinate(error) => error.fmt(f),
+            Self::MixedCoordinates(error) => error.fmt(f),
+            Self::InvalidColor(error) => error.fmt(f),
+            Self::InvalidHexColor(error) => error.fmt(f),
+            Self::UnknownItemSlot(error) => error.fmt(f),
+            Self::UnterminatedCompound(error) => error.fmt(f),
+            Self::UnexpectedNbtToken(error) => error.fmt(f),
+            Self::TagNotAllowed(error) => error.fmt(f),
+            Self::InvalidResourceLocation(error) => error.fmt(f),
+            Self::ObjectiveNameTooLong(error) => error.fmt(f),
+            Self::InvalidOperation(error) => error.fmt(f),
+            Self::InvalidHeightmap(error) => error.fmt(f),
+            Self::InvalidEntityAnchor(error) => error.fmt(f),
+            Self::TimeBelowMinimum(error) => error.fmt(f),
+            Self::EmptyRange(error) => error.fmt(f),
+            Self::ReversedRange(error) => error.fmt(f),
+            Self::InvalidSelector(error) => error.fmt(f),
+            Self::SelectorNotSingle(error) => error.fmt(f),
+            Self::SelectorNotPlayer(error) => error.fmt(f),
+            Self::ForbiddenCommand(error) => error.fmt(f),
+            Self::MissingRunBody(error) => error.fmt(f),
+            Self::IncompleteCommand(error) => error.fmt(f),
+            Self::ParseComplexity(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug, PartialEq)]
 pub struct IndentationError {
     pub span: Span,
     pub kind: IndentationErrorKind,
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub enum IndentationErrorKind {
     MixedWhitespace,
+    /// The line is indented with tabs, which are only allowed if a tab width is configured.
+    TabIndentation,
     InvalidIndentation,
 }
 
 impl EmitDiagnostic for IndentationError {
     fn emit(&self, _: &ParseContext<'_>) -> Diagnostic {
-        Diagnostic::error(self.span, "Indentation error").with_label(Label::new(
+        let diagnostic = Diagnostic::error(self.span, self.to_string()).with_label(Label::new(
             self.span,
             match self.kind {
                 IndentationErrorKind::MixedWhitespace => "Must only use spaces for indentation",
+                IndentationErrorKind::TabIndentation => "Tabs are not allowed in indentation",
                 IndentationErrorKind::InvalidIndentation => "Invalid indentation",
             },
-        ))
+        ));
+        match self.kind {
+            IndentationErrorKind::TabIndentation => {
+                diagnostic.with_help("Indent with spaces instead, e.g. four spaces per tab")
+            }
+            _ => diagnostic,
+        }
     }
 }
 
-#[derive(Debug)]
+impl fmt::Display for IndentationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Indentation error")
+    }
+}
+
+#[derive(Debug, PartialEq)]
 pub struct InvalidLiteralError {
     pub span: Span,
     pub valid_literals: Range<usize>,
@@ -87,24 +297,49 @@ pub struct InvalidLiteralError {
 
 impl EmitDiagnostic for InvalidLiteralError {
     fn emit(&self, ctx: &ParseContext<'_>) -> Diagnostic {
-        let mut valid_literals: Vec<_> = self
-            .valid_literals
-            .clone()
-            .map(|node_id| ctx.tree.get_node(node_id).unwrap().name())
-            .collect();
+        // Argument nodes are named after their parameter, which is meaningless as a suggestion, so
+        // they are described by their type instead
+        let mut valid_literals = Vec::new();
+        let mut argument_types = Vec::new();
+        for node_id in self.valid_literals.clone() {
+            match &ctx.tree.get_node(node_id).unwrap().kind {
+                NodeKind::Literal(literal) => valid_literals.push(&**literal),
+                NodeKind::Argument { arg, .. } => argument_types.push(format!("{arg:?}")),
+                NodeKind::Block => (),
+            }
+        }
         valid_literals.sort();
+        argument_types.sort();
+        argument_types.dedup();
 
-        let mut diagnostic =
-            Diagnostic::error(self.span, "Invalid literal").with_label(Label::new(
+        let expected_types = argument_types
+            .iter()
+            .map(|ty| ty.fg(Color::BrightBlue).surrounded('`', '`'))
+            .delimited(", ", " or ");
+        let mut diagnostic = Diagnostic::error(self.span, self.to_string());
+        if valid_literals.is_empty() {
+            return diagnostic.with_label(Label::new(
                 self.span,
-                format!(
-                    "Expected one of {}",
-                    valid_literals
-                        .iter()
-                        .map(|lit| lit.fg(Color::BrightGreen).surrounded('`', '`'))
-                        .delimited(", ", " or ")
-                ),
+                format!("Expected an argument of type {expected_types}"),
             ));
+        }
+
+        diagnostic = diagnostic.with_label(Label::new(
+            self.span,
+            format!(
+                "Expected one of {}",
+                valid_literals
+                    .iter()
+                    .map(|lit| lit.fg(Color::BrightGreen).surrounded('`', '`'))
+                    .delimited(", ", " or ")
+            ),
+        ));
+        if !argument_types.is_empty() {
+            diagnostic = diagnostic.with_sub(
+                Level::Info,
+                format!("An argument of type {expected_types} is accepted as well"),
+            );
+        }
 
         let input = &ctx.source.text()[self.span.as_range()];
         let likely_literal = valid_literals
@@ -124,14 +359,20 @@ impl EmitDiagnostic for InvalidLiteralError {
     }
 }
 
-#[derive(Debug)]
+impl fmt::Display for InvalidLiteralError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Invalid literal")
+    }
+}
+
+#[derive(Debug, PartialEq)]
 pub struct TooManyArgumentsError {
     pub span: Span,
 }
 
 impl EmitDiagnostic for TooManyArgumentsError {
     fn emit(&self, ctx: &ParseContext<'_>) -> Diagnostic {
-        Diagnostic::error(self.span, "Too many arguments").with_label(Label::new(
+        Diagnostic::error(self.span, self.to_string()).with_label(Label::new(
             self.span,
             match ctx.source.text()[self.span.as_range()].contains(char::is_whitespace) {
                 true => "These arguments were not expected",
@@ -141,14 +382,41 @@ impl EmitDiagnostic for TooManyArgumentsError {
     }
 }
 
-#[derive(Debug)]
+impl fmt::Display for TooManyArgumentsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Too many arguments")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct TrailingArgumentCharsError {
+    /// The span from where the argument parser stopped to the next whitespace.
+    pub span: Span,
+}
+
+impl EmitDiagnostic for TrailingArgumentCharsError {
+    fn emit(&self, _: &ParseContext<'_>) -> Diagnostic {
+        Diagnostic::error(self.span, self.to_string()).with_label(Label::new(
+            self.span,
+            "Arguments must be separated by whitespace",
+        ))
+    }
+}
+
+impl fmt::Display for TrailingArgumentCharsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Unexpected characters after argument")
+    }
+}
+
+#[derive(Debug, PartialEq)]
 pub struct ParseBoolError {
     pub span: Span,
 }
 
 impl EmitDiagnostic for ParseBoolError {
     fn emit(&self, _: &ParseContext<'_>) -> Diagnostic {
-        Diagnostic::error(self.span, "Invalid boolean").with_label(Label::new(
+        Diagnostic::error(self.span, self.to_string()).with_label(Label::new(
             self.span,
             format!(
                 "Expected either `{}` or `{}`",
@@ -159,14 +427,20 @@ impl EmitDiagnostic for ParseBoolError {
     }
 }
 
-#[derive(Debug)]
+impl fmt::Display for ParseBoolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Invalid boolean")
+    }
+}
+
+#[derive(Debug, PartialEq)]
 pub enum NumberType {
     Integer,
     Float,
     Double,
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct ParseNumberError {
     pub span: Span,
     pub kind: NumberType,
@@ -174,7 +448,7 @@ pub struct ParseNumberError {
 
 impl EmitDiagnostic for ParseNumberError {
     fn emit(&self, _: &ParseContext<'_>) -> Diagnostic {
-        Diagnostic::error(self.span, "Invalid number").with_label(Label::new(
+        Diagnostic::error(self.span, self.to_string()).with_label(Label::new(
             self.span,
             match self.kind {
                 NumberType::Integer => format!("Expected an {}", "integer".fg(Color::Magenta)),
@@ -185,7 +459,13 @@ impl EmitDiagnostic for ParseNumberError {
     }
 }
 
-#[derive(Debug)]
+impl fmt::Display for ParseNumberError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Invalid number")
+    }
+}
+
+#[derive(Debug, PartialEq)]
 pub struct NumberOutOfBoundsError {
     pub span: Span,
     pub min: f64,
@@ -194,92 +474,669 @@ pub struct NumberOutOfBoundsError {
 
 impl EmitDiagnostic for NumberOutOfBoundsError {
     fn emit(&self, _: &ParseContext<'_>) -> Diagnostic {
-        Diagnostic::error(
-            self.span,
-            format!(
-                "Number out of bounds: must be at least {} and at most {}",
-                self.min, self.max
-            ),
+        Diagnostic::error(self.span, self.to_string())
+    }
+}
+
+impl fmt::Display for NumberOutOfBoundsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Number out of bounds: must be at least {} and at most {}",
+            self.min, self.max
         )
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct UnterminatedStringError {
     pub span: Span,
 }
 
 impl EmitDiagnostic for UnterminatedStringError {
     fn emit(&self, _: &ParseContext<'_>) -> Diagnostic {
-        Diagnostic::error(self.span, "Unterminated string")
+        Diagnostic::error(self.span, self.to_string())
             .with_label(Label::new(self.span, "Missing closing quotation mark"))
     }
 }
 
-#[derive(Debug)]
+impl fmt::Display for UnterminatedStringError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Unterminated string")
+    }
+}
+
+#[derive(Debug, PartialEq)]
 pub struct InvalidStringCharsError {
     pub span: Span,
 }
 
 impl EmitDiagnostic for InvalidStringCharsError {
     fn emit(&self, _: &ParseContext<'_>) -> Diagnostic {
-        Diagnostic::error(self.span, "Invalid characters in string")
+        Diagnostic::error(self.span, self.to_string())
     }
 }
 
-#[derive(Debug)]
+impl fmt::Display for InvalidStringCharsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Invalid characters in string")
+    }
+}
+
+#[derive(Debug, PartialEq)]
 pub struct QuotedSingleWordError {
     pub span: Span,
 }
 
 impl EmitDiagnostic for QuotedSingleWordError {
     fn emit(&self, _: &ParseContext<'_>) -> Diagnostic {
-        Diagnostic::error(self.span, "Cannot quote single-word strings")
+        Diagnostic::error(self.span, self.to_string())
             .with_label(Label::new(self.span, "This string must not be quoted"))
     }
 }
 
-#[derive(Debug)]
+impl fmt::Display for QuotedSingleWordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Cannot quote single-word strings")
+    }
+}
+
+#[derive(Debug, PartialEq)]
 pub struct IncompleteLocalCoordinatesError {
     pub span: Span,
 }
 
 impl EmitDiagnostic for IncompleteLocalCoordinatesError {
     fn emit(&self, _: &ParseContext<'_>) -> Diagnostic {
-        Diagnostic::error(self.span, "Incomplete local coordinates")
+        Diagnostic::error(self.span, self.to_string())
     }
 }
 
-#[derive(Debug)]
+impl fmt::Display for IncompleteLocalCoordinatesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Incomplete local coordinates")
+    }
+}
+
+#[derive(Debug, PartialEq)]
 pub struct ExpectedLocalCoordinateError {
     pub span: Span,
 }
 
 impl EmitDiagnostic for ExpectedLocalCoordinateError {
     fn emit(&self, _: &ParseContext<'_>) -> Diagnostic {
-        Diagnostic::error(self.span, "Expected local coordinate")
+        Diagnostic::error(self.span, self.to_string())
+    }
+}
+
+impl fmt::Display for ExpectedLocalCoordinateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Expected local coordinate")
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct MixedCoordiantesError {
     pub span: Span,
 }
 
 impl EmitDiagnostic for MixedCoordiantesError {
     fn emit(&self, _: &ParseContext<'_>) -> Diagnostic {
-        Diagnostic::error(self.span, "Cannot mix world and local coordinates")
+        Diagnostic::error(self.span, self.to_string())
+    }
+}
+
+impl fmt::Display for MixedCoordiantesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Cannot mix world and local coordinates")
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct InvalidColorError {
     pub span: Span,
 }
 
 impl EmitDiagnostic for InvalidColorError {
     fn emit(&self, _: &ParseContext<'_>) -> Diagnostic {
-        Diagnostic::error(self.span, "Invalid color")
+        Diagnostic::error(self.span, self.to_string())
+    }
+}
+
+impl fmt::Display for InvalidColorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Invalid color")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InvalidHexColorError {
+    pub span: Span,
+    pub kind: InvalidHexColorErrorKind,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum InvalidHexColorErrorKind {
+    WrongLength,
+    InvalidDigits,
+}
+
+impl EmitDiagnostic for InvalidHexColorError {
+    fn emit(&self, _: &ParseContext<'_>) -> Diagnostic {
+        Diagnostic::error(self.span, self.to_string()).with_label(Label::new(
+            self.span,
+            match self.kind {
+                InvalidHexColorErrorKind::WrongLength => {
+                    "Expected exactly six hex digits like `#ff8800`"
+                }
+                InvalidHexColorErrorKind::InvalidDigits => {
+                    "Only the digits `0`-`9` and `a`-`f` are allowed"
+                }
+            },
+        ))
+    }
+}
+
+impl fmt::Display for InvalidHexColorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Invalid hex color")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct UnknownItemSlotError {
+    pub span: Span,
+    /// Whether groups of slots like `container.*` are allowed.
+    pub allow_groups: bool,
+}
+
+impl EmitDiagnostic for UnknownItemSlotError {
+    fn emit(&self, ctx: &ParseContext<'_>) -> Diagnostic {
+        let diagnostic = Diagnostic::error(self.span, self.to_string()).with_label(Label::new(
+            self.span,
+            format!(
+                "Expected a slot starting with one of {}",
+                ItemSlotCategory::ALL
+                    .iter()
+                    .map(|category| category
+                        .prefix()
+                        .fg(Color::BrightGreen)
+                        .surrounded('`', '`'))
+                    .delimited(", ", " or ")
+            ),
+        ));
+
+        let input = ctx
+            .source
+            .text()
+            .get(self.span.as_range())
+            .unwrap_or_default();
+        if !self.allow_groups && input.ends_with(".*") {
+            return diagnostic.with_help("Groups of slots are not allowed here");
+        }
+        diagnostic
+    }
+}
+
+impl fmt::Display for UnknownItemSlotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Unknown item slot")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct TagNotAllowedError {
+    pub span: Span,
+}
+
+impl EmitDiagnostic for TagNotAllowedError {
+    fn emit(&self, _: &ParseContext<'_>) -> Diagnostic {
+        Diagnostic::error(self.span, self.to_string()).with_label(Label::new(
+            self.span,
+            "Only a single entry is allowed here, remove the `#`",
+        ))
+    }
+}
+
+impl fmt::Display for TagNotAllowedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Tag not allowed")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct UnterminatedCompoundError {
+    /// The span from the opening `{` to the end of the input.
+    pub span: Span,
+}
+
+impl EmitDiagnostic for UnterminatedCompoundError {
+    fn emit(&self, _: &ParseContext<'_>) -> Diagnostic {
+        Diagnostic::error(self.span, self.to_string()).with_label(Label::new(
+            Span::new(self.span.start, self.span.start + 1),
+            "This `{` is never closed",
+        ))
+    }
+}
+
+impl fmt::Display for UnterminatedCompoundError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Unterminated compound")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct UnexpectedNbtTokenError {
+    /// The span of the unexpected character, which is empty at the end of the input.
+    pub span: Span,
+    pub expected: ExpectedNbtToken,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExpectedNbtToken {
+    Value,
+    Compound,
+    Key,
+    Colon,
+    Equals,
+    CommaOrBrace,
+    CommaOrBracket,
+    ArrayType,
+    Byte,
+    Int,
+    Long,
+    /// Whitespace or the end of the input after a tag.
+    End,
+    PathSeparator,
+    Index,
+    ClosingBracket,
+}
+
+impl EmitDiagnostic for UnexpectedNbtTokenError {
+    fn emit(&self, _: &ParseContext<'_>) -> Diagnostic {
+        Diagnostic::error(self.span, self.to_string()).with_label(Label::new(
+            self.span,
+            match self.expected {
+                ExpectedNbtToken::Value => "Expected a value",
+                ExpectedNbtToken::Compound => "Expected a compound starting with `{`",
+                ExpectedNbtToken::Key => "Expected a key",
+                ExpectedNbtToken::Colon => "Expected `:`",
+                ExpectedNbtToken::Equals => "Expected `=`",
+                ExpectedNbtToken::CommaOrBrace => "Expected `,` or `}`",
+                ExpectedNbtToken::CommaOrBracket => "Expected `,` or `]`",
+                ExpectedNbtToken::ArrayType => "Expected one of the array types `B`, `I` or `L`",
+                ExpectedNbtToken::Byte => "Expected a byte like `1b`",
+                ExpectedNbtToken::Int => "Expected an int like `1`",
+                ExpectedNbtToken::Long => "Expected a long like `1L`",
+                ExpectedNbtToken::End => "Expected whitespace after the tag",
+                ExpectedNbtToken::PathSeparator => "Expected `.`, `[` or whitespace",
+                ExpectedNbtToken::Index => "Expected an index, `{` or `]`",
+                ExpectedNbtToken::ClosingBracket => "Expected `]`",
+            },
+        ))
+    }
+}
+
+impl fmt::Display for UnexpectedNbtTokenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Invalid NBT")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InvalidResourceLocationError {
+    pub span: Span,
+}
+
+impl EmitDiagnostic for InvalidResourceLocationError {
+    fn emit(&self, _: &ParseContext<'_>) -> Diagnostic {
+        Diagnostic::error(self.span, self.to_string()).with_label(Label::new(
+            self.span,
+            "Only lowercase letters, digits, `_`, `-` and `.` are allowed, and `/` in the path",
+        ))
+    }
+}
+
+impl fmt::Display for InvalidResourceLocationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Invalid resource location")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ObjectiveNameTooLongError {
+    /// The span of the whole name.
+    pub span: Span,
+}
+
+impl EmitDiagnostic for ObjectiveNameTooLongError {
+    fn emit(&self, ctx: &ParseContext<'_>) -> Diagnostic {
+        let name = ctx
+            .source
+            .text()
+            .get(self.span.as_range())
+            .unwrap_or_default();
+        let excess_start = name
+            .char_indices()
+            .nth(MAX_OBJECTIVE_NAME_LEN)
+            .map_or(self.span.end, |(i, _)| self.span.start + i);
+        let excess = Span::new(excess_start, self.span.end);
+        Diagnostic::error(self.span, self.to_string()).with_label(Label::new(
+            excess,
+            format!("Objective names may be at most {MAX_OBJECTIVE_NAME_LEN} characters long"),
+        ))
+    }
+}
+
+impl fmt::Display for ObjectiveNameTooLongError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Objective name is too long")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InvalidOperationError {
+    pub span: Span,
+}
+
+impl EmitDiagnostic for InvalidOperationError {
+    fn emit(&self, _: &ParseContext<'_>) -> Diagnostic {
+        Diagnostic::error(self.span, self.to_string()).with_label(Label::new(
+            self.span,
+            format!(
+                "Expected one of {}",
+                Operator::ALL
+                    .iter()
+                    .map(|operator| operator
+                        .as_str()
+                        .fg(Color::BrightGreen)
+                        .surrounded('`', '`'))
+                    .delimited(", ", " or ")
+            ),
+        ))
+    }
+}
+
+impl fmt::Display for InvalidOperationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Invalid operation")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InvalidHeightmapError {
+    pub span: Span,
+}
+
+impl EmitDiagnostic for InvalidHeightmapError {
+    fn emit(&self, _: &ParseContext<'_>) -> Diagnostic {
+        Diagnostic::error(self.span, self.to_string()).with_label(Label::new(
+            self.span,
+            format!(
+                "Expected one of {}",
+                HeightmapKind::ALL
+                    .iter()
+                    .map(|kind| kind.as_str().fg(Color::BrightGreen).surrounded('`', '`'))
+                    .delimited(", ", " or ")
+            ),
+        ))
+    }
+}
+
+impl fmt::Display for InvalidHeightmapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Invalid heightmap")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InvalidEntityAnchorError {
+    pub span: Span,
+}
+
+impl EmitDiagnostic for InvalidEntityAnchorError {
+    fn emit(&self, _: &ParseContext<'_>) -> Diagnostic {
+        Diagnostic::error(self.span, self.to_string()).with_label(Label::new(
+            self.span,
+            format!(
+                "Expected one of {}",
+                EntityAnchorKind::ALL
+                    .iter()
+                    .map(|kind| kind.as_str().fg(Color::BrightGreen).surrounded('`', '`'))
+                    .delimited(", ", " or ")
+            ),
+        ))
+    }
+}
+
+impl fmt::Display for InvalidEntityAnchorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Invalid entity anchor")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct TimeBelowMinimumError {
+    pub span: Span,
+    pub min: i32,
+    /// The duration in ticks.
+    pub ticks: i32,
+}
+
+impl EmitDiagnostic for TimeBelowMinimumError {
+    fn emit(&self, _: &ParseContext<'_>) -> Diagnostic {
+        Diagnostic::error(self.span, self.to_string())
+    }
+}
+
+impl fmt::Display for TimeBelowMinimumError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Tick count must not be less than {}, found {}",
+            self.min, self.ticks
+        )
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct EmptyRangeError {
+    pub span: Span,
+}
+
+impl EmitDiagnostic for EmptyRangeError {
+    fn emit(&self, _: &ParseContext<'_>) -> Diagnostic {
+        Diagnostic::error(self.span, self.to_string()).with_label(Label::new(
+            self.span,
+            "At least one bound is required, e.g. `1..` or `..5`",
+        ))
+    }
+}
+
+impl fmt::Display for EmptyRangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Expected a value or a range of values")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ReversedRangeError {
+    pub span: Span,
+}
+
+impl EmitDiagnostic for ReversedRangeError {
+    fn emit(&self, _: &ParseContext<'_>) -> Diagnostic {
+        Diagnostic::error(self.span, self.to_string())
+            .with_label(Label::new(self.span, "This range contains no values"))
+    }
+}
+
+impl fmt::Display for ReversedRangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Minimum of range is bigger than maximum")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InvalidSelectorError {
+    pub span: Span,
+    pub kind: InvalidSelectorErrorKind,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum InvalidSelectorErrorKind {
+    UnknownType,
+    InvalidNameOrUuid,
+    ExpectedOption,
+    Unterminated,
+    ExpectedScores,
+    UnterminatedScores,
+    TrailingChars,
+}
+
+impl EmitDiagnostic for InvalidSelectorError {
+    fn emit(&self, _: &ParseContext<'_>) -> Diagnostic {
+        Diagnostic::error(self.span, self.to_string()).with_label(Label::new(
+            self.span,
+            match self.kind {
+                InvalidSelectorErrorKind::UnknownType => {
+                    "Expected one of `@p`, `@a`, `@r`, `@e`, `@s` or `@n`"
+                }
+                InvalidSelectorErrorKind::InvalidNameOrUuid => {
+                    "Expected a player name, a UUID or a selector"
+                }
+                InvalidSelectorErrorKind::ExpectedOption => "Expected an option like `type=pig`",
+                InvalidSelectorErrorKind::Unterminated => "Missing closing `]`",
+                InvalidSelectorErrorKind::ExpectedScores => {
+                    "Expected scores like `{objective=1..}`"
+                }
+                InvalidSelectorErrorKind::UnterminatedScores => "Missing closing `}`",
+                InvalidSelectorErrorKind::TrailingChars => "Expected whitespace after the selector",
+            },
+        ))
+    }
+}
+
+impl fmt::Display for InvalidSelectorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Invalid selector")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SelectorNotSingleError {
+    pub span: Span,
+}
+
+impl EmitDiagnostic for SelectorNotSingleError {
+    fn emit(&self, _: &ParseContext<'_>) -> Diagnostic {
+        Diagnostic::error(self.span, self.to_string()).with_label(Label::new(
+            self.span,
+            "Only one entity is allowed here, add `limit=1` or use a single selector",
+        ))
+    }
+}
+
+impl fmt::Display for SelectorNotSingleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Selector may select more than one entity")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct SelectorNotPlayerError {
+    pub span: Span,
+}
+
+impl EmitDiagnostic for SelectorNotPlayerError {
+    fn emit(&self, _: &ParseContext<'_>) -> Diagnostic {
+        Diagnostic::error(self.span, self.to_string())
+            .with_label(Label::new(self.span, "Only players are allowed here"))
+    }
+}
+
+impl fmt::Display for SelectorNotPlayerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Selector may select non-player entities")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ForbiddenCommandError {
+    pub span: Span,
+}
+
+impl EmitDiagnostic for ForbiddenCommandError {
+    fn emit(&self, _: &ParseContext<'_>) -> Diagnostic {
+        Diagnostic::error(self.span, self.to_string())
+            .with_label(Label::new(self.span, "This command is not allowed"))
+    }
+}
+
+impl fmt::Display for ForbiddenCommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Forbidden command")
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct MissingRunBodyError {
+    pub span: Span,
+}
+
+impl EmitDiagnostic for MissingRunBodyError {
+    fn emit(&self, _: &ParseContext<'_>) -> Diagnostic {
+        Diagnostic::error(self.span, self.to_string()).with_label(Label::new(
+            self.span,
+            "Expected a command or an indented block of commands",
+        ))
+    }
+}
+
+impl fmt::Display for MissingRunBodyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Missing command")
+    }
+}
+
+/// The command ends at a node which is not executable, e.g. `execute as @s`.
+#[derive(Debug, PartialEq)]
+pub struct IncompleteCommandError {
+    /// The empty span after the last argument.
+    pub span: Span,
+}
+
+impl EmitDiagnostic for IncompleteCommandError {
+    fn emit(&self, _: &ParseContext<'_>) -> Diagnostic {
+        Diagnostic::error(self.span, self.to_string())
+            .with_label(Label::new(self.span, "Expected more arguments"))
+    }
+}
+
+impl fmt::Display for IncompleteCommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Incomplete command")
+    }
+}
+
+/// Not all possible interpretations of the command were tried, because there were too many.
+#[derive(Debug, PartialEq)]
+pub struct ParseComplexityWarning {
+    pub span: Span,
+}
+
+impl EmitDiagnostic for ParseComplexityWarning {
+    fn emit(&self, _: &ParseContext<'_>) -> Diagnostic {
+        Diagnostic::warn(self.span, self.to_string()).with_label(Label::new(
+            self.span,
+            "Not all interpretations of these arguments were checked",
+        ))
+    }
+}
+
+impl fmt::Display for ParseComplexityWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Command is too ambiguous")
     }
 }
 
@@ -355,3 +1212,116 @@ impl<T> FmtExt for T {
         }
     }
 }
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::{NumberOutOfBoundsError, ParseError, UnterminatedStringError};
+    use crate::{parse::cst, span::Span, test_util};
+
+    #[test]
+    fn display_is_the_short_message() {
+        let error = ParseError::UnterminatedString(UnterminatedStringError {
+            span: Span::new(0, 4),
+        });
+        assert_eq!(format!("{error}"), "Unterminated string");
+
+        let error = ParseError::NumberOutOfBounds(NumberOutOfBoundsError {
+            span: Span::new(0, 2),
+            min: 0.0,
+            max: 10.0,
+        });
+        assert_eq!(
+            format!("{error}"),
+            "Number out of bounds: must be at least 0 and at most 10"
+        );
+    }
+
+    #[test]
+    fn display_matches_emitted_message() {
+        struct DisplayVisitor(Vec<String>);
+
+        impl cst::Visitor for DisplayVisitor {
+            fn visit_parse_error(&mut self, error: &ParseError) {
+                self.0.push(error.to_string());
+            }
+        }
+
+        let parsed = test_util::parse("help\nfoo\ntime add -5\ndatapack enable \"x\n");
+        let mut visitor = DisplayVisitor(Vec::new());
+        cst::walk_block(&mut visitor, parsed.result.as_ref().unwrap());
+        let mut displayed = visitor.0;
+        displayed.sort();
+
+        let mut emitted: Vec<_> = parsed
+            .diagnostics()
+            .iter()
+            .map(|diagnostic| diagnostic.message().to_owned())
+            .collect();
+        emitted.sort();
+        assert_eq!(displayed.len(), 3);
+        assert_eq!(displayed, emitted);
+
+        let parsed = test_util::parse("  help\n");
+        let error = parsed.result.as_ref().unwrap_err();
+        assert_eq!(error.to_string(), parsed.diagnostics()[0].message());
+    }
+
+    /// Returns the label and sub diagnostic messages of the only diagnostic, with a tree whose `cmd`
+    /// has the literals `day` and `night` and an integer argument as children.
+    fn mixed_children_messages(text: &str) -> (String, Vec<String>, Vec<String>) {
+        let json = r#"{"type": "root", "children": {"cmd": {"type": "literal", "children": {
+            "day": {"type": "literal", "executable": true},
+            "night": {"type": "literal", "executable": true},
+            "ticks": {"type": "argument", "parser": "brigadier:integer", "executable": true}
+        }}}}"#;
+        let mut tree = crate::BuildTree::default();
+        let registry = crate::parse::argument::ArgumentRegistry::default();
+        crate::import::import(json, &mut tree, &registry).unwrap();
+        let parsed = crate::parse_source(
+            std::sync::Arc::new(tree.into_parsing_tree()),
+            None,
+            text.to_owned(),
+        );
+
+        let diagnostics = parsed.diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        let labels = diagnostics[0].labels().iter();
+        let subs = diagnostics[0].sub().iter();
+        (
+            diagnostics[0].message().to_owned(),
+            labels.map(|label| strip_ansi(label.message())).collect(),
+            subs.map(|sub| strip_ansi(sub.message())).collect(),
+        )
+    }
+
+    fn strip_ansi(text: &str) -> String {
+        let mut stripped = String::new();
+        let mut chars = text.chars();
+        while let Some(chr) = chars.next() {
+            if chr == '\x1b' {
+                chars.by_ref().find(|&chr| chr == 'm');
+            } else {
+                stripped.push(chr);
+            }
+        }
+        stripped
+    }
+
+    #[test]
+    fn invalid_literal_with_argument_children() {
+        assert_eq!(
+            mixed_children_messages("cmd nigth"),
+            (
+                "Invalid literal".to_owned(),
+                vec!["Expected one of `day` or `night`".to_owned()],
+                vec![
+                    "An argument of type `integer` is accepted as well".to_owned(),
+                    "Did you mean `night`?".to_owned(),
+                ]
+            )
+        );
+
+        // Input which resembles no literal is reported by the argument
+        assert_eq!(mixed_children_messages("cmd abc").0, "Invalid number");
+    }
+}
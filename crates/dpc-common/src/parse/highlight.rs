@@ -0,0 +1,164 @@
+use super::cst::{self, ArgumentValue, Block, Command, Visitor};
+use crate::{parse::errors::ParseError, span::Span};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SemanticTokenKind {
+    Command,
+    Literal,
+    StringArg,
+    NumberArg,
+    Selector,
+    Comment,
+    Error,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SemanticToken {
+    pub span: Span,
+    pub kind: SemanticTokenKind,
+}
+
+/// Classifies the parsed source for syntax highlighting.
+///
+/// The returned tokens are sorted and never overlap. Errors take precedence over the arguments
+/// they are reported on.
+pub fn semantic_tokens(block: &Block) -> Vec<SemanticToken> {
+    struct SemanticTokenVisitor {
+        tokens: Vec<SemanticToken>,
+        errors: Vec<Span>,
+    }
+
+    impl SemanticTokenVisitor {
+        fn push(&mut self, span: Span, kind: SemanticTokenKind) {
+            if span.len() > 0 {
+                self.tokens.push(SemanticToken { span, kind });
+            }
+        }
+    }
+
+    impl Visitor for SemanticTokenVisitor {
+        fn visit_comment(&mut self, comment: &Span) {
+            self.push(*comment, SemanticTokenKind::Comment);
+        }
+
+        fn visit_command(&mut self, command: &Command) {
+            for (i, argument) in command.args.iter().enumerate() {
+                let kind = match &argument.value {
+                    ArgumentValue::Literal if i == 0 => SemanticTokenKind::Command,
+                    ArgumentValue::Literal
+                    | ArgumentValue::Boolean(_)
+                    | ArgumentValue::Color(_)
+                    | ArgumentValue::Operation(_)
+                    | ArgumentValue::EntityAnchor(_)
+                    | ArgumentValue::Heightmap(_)
+                    | ArgumentValue::ItemSlot(_) => SemanticTokenKind::Literal,
+                    ArgumentValue::String(_)
+                    | ArgumentValue::Function(_)
+                    | ArgumentValue::Resource(_)
+                    | ArgumentValue::ResourceLocation(_)
+                    | ArgumentValue::Particle(_)
+                    | ArgumentValue::ItemStack(_)
+                    | ArgumentValue::Objective(_)
+                    | ArgumentValue::Team(_)
+                    | ArgumentValue::Nbt(_)
+                    | ArgumentValue::NbtPath(_) => SemanticTokenKind::StringArg,
+                    ArgumentValue::Integer(_)
+                    | ArgumentValue::Float(_)
+                    | ArgumentValue::Double(_)
+                    | ArgumentValue::Angle(_)
+                    | ArgumentValue::Coordinates2(_)
+                    | ArgumentValue::Coordinates3(_)
+                    | ArgumentValue::Time(_)
+                    | ArgumentValue::IntRange(_) => SemanticTokenKind::NumberArg,
+                    ArgumentValue::Entity(_) | ArgumentValue::ScoreHolder(_) => {
+                        SemanticTokenKind::Selector
+                    }
+                    ArgumentValue::Block(_) | ArgumentValue::Custom(_) => continue,
+                };
+                self.push(argument.span, kind);
+            }
+            cst::walk_command(self, command);
+        }
+
+        fn visit_parse_error(&mut self, error: &ParseError) {
+            let span = error.span();
+            if span.len() > 0 && !self.errors.iter().any(|other| other.overlaps(span)) {
+                self.errors.push(span);
+            }
+        }
+    }
+
+    let mut visitor = SemanticTokenVisitor {
+        tokens: Vec::new(),
+        errors: Vec::new(),
+    };
+    cst::walk_block(&mut visitor, block);
+
+    let SemanticTokenVisitor { mut tokens, errors } = visitor;
+    tokens.retain(|token| !errors.iter().any(|error| error.overlaps(token.span)));
+    tokens.extend(errors.into_iter().map(|span| SemanticToken {
+        span,
+        kind: SemanticTokenKind::Error,
+    }));
+    tokens.sort_by_key(|token| token.span);
+    tokens
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::{SemanticTokenKind, semantic_tokens};
+    use crate::test_util;
+
+    /// Returns the text and kind of every token.
+    fn tokens(text: &str) -> Vec<(&str, SemanticTokenKind)> {
+        let parsed = test_util::parse(text);
+        semantic_tokens(parsed.result.as_ref().unwrap())
+            .into_iter()
+            .map(|token| (&text[token.span.as_range()], token.kind))
+            .collect()
+    }
+
+    #[test]
+    fn command_with_nested_block() {
+        use SemanticTokenKind::*;
+
+        let text = "# setup\nexecute as @a run\n    say hi\n    scoreboard players set @s obj 5\n";
+        assert_eq!(
+            tokens(text),
+            [
+                ("# setup", Comment),
+                ("execute", Command),
+                ("as", Literal),
+                ("@a", Selector),
+                ("run", Literal),
+                ("say", Command),
+                ("hi", StringArg),
+                ("scoreboard", Command),
+                ("players", Literal),
+                ("set", Literal),
+                ("@s", Selector),
+                ("obj", StringArg),
+                ("5", NumberArg),
+            ]
+        );
+    }
+
+    #[test]
+    fn errors_replace_overlapping_tokens() {
+        use SemanticTokenKind::*;
+
+        let text = "execute as @a run\n    time set xyz\n";
+        assert_eq!(
+            tokens(text),
+            [
+                ("execute", Command),
+                ("as", Literal),
+                ("@a", Selector),
+                ("run", Literal),
+                ("time", Command),
+                ("set", Literal),
+                ("xyz", Error),
+            ]
+        );
+    }
+}
@@ -0,0 +1,273 @@
+use serde_json::{Map, Value, json};
+
+use crate::{
+    intern::{Interner, Symbol},
+    parse::{
+        argument::{
+            Coordinates, EntityTarget, IntRange, ItemComponent, ResourceLocation, ScoreHolder,
+            Selector, SelectorOption, WorldCoordinate,
+        },
+        cst::{Argument, ArgumentValue, Block, Command, Item},
+        errors::ParseError,
+    },
+};
+
+/// Converts the block into JSON, e.g. for tools which do not link against this crate. Symbols are
+/// resolved to their strings, since they are meaningless outside of the interner.
+///
+/// Every argument is an object with its `span`, the id of its `node` in the parsing tree, its
+/// `errors` and a `value` whose `kind` tells how the remaining fields are to be read. Values which
+/// failed to parse are `null`. Spans are objects with a `start` and an `end`.
+pub fn to_json(block: &Block, interner: &impl Interner) -> Value {
+    JsonWriter { interner }.block(block)
+}
+
+struct JsonWriter<'a, I> {
+    interner: &'a I,
+}
+
+impl<I: Interner> JsonWriter<'_, I> {
+    fn block(&self, block: &Block) -> Value {
+        json!({
+            "span": block.span,
+            "items": block.items.iter().map(|item| self.item(item)).collect::<Vec<_>>(),
+        })
+    }
+
+    fn item(&self, item: &Item) -> Value {
+        match item {
+            Item::Command(command) => self.command(command),
+            Item::Comment(comment) => json!({ "type": "comment", "span": comment }),
+        }
+    }
+
+    fn command(&self, command: &Command) -> Value {
+        json!({
+            "type": "command",
+            "span": command.span(),
+            "args": command.args.iter().map(|arg| self.argument(arg)).collect::<Vec<_>>(),
+            "error": command.error.as_ref().map(error),
+        })
+    }
+
+    fn argument(&self, arg: &Argument) -> Value {
+        json!({
+            "span": arg.span,
+            "node": arg.lin_node_id,
+            "value": self.value(&arg.value),
+            "errors": arg.errors.iter().map(error).collect::<Vec<_>>(),
+        })
+    }
+
+    fn value(&self, value: &ArgumentValue) -> Value {
+        let (kind, fields) = match value {
+            ArgumentValue::Literal => ("literal", json!({})),
+            ArgumentValue::Block(block) => ("block", json!({ "block": self.block(block) })),
+            ArgumentValue::Boolean(boolean) => ("boolean", json!({ "value": boolean.value })),
+            ArgumentValue::Integer(integer) => ("integer", json!({ "value": integer.value })),
+            ArgumentValue::Float(float) => ("float", json!({ "value": float.value })),
+            ArgumentValue::Double(double) => ("double", json!({ "value": double.value })),
+            ArgumentValue::String(text) => (
+                "string",
+                json!({ "value": text.value.map(|value| self.symbol(value)) }),
+            ),
+            ArgumentValue::Angle(angle) => (
+                "angle",
+                json!({ "value": angle.value.value, "relative": angle.relative }),
+            ),
+            ArgumentValue::Coordinates2(coords) => ("coordinates", coordinates(coords)),
+            ArgumentValue::Coordinates3(coords) => ("coordinates", coordinates(coords)),
+            ArgumentValue::Color(color) => (
+                "color",
+                json!({ "value": color.color.map(|color| color.to_string()) }),
+            ),
+            ArgumentValue::Function(function) => (
+                "function",
+                json!({
+                    "location": self.location(function.location),
+                    "is_tag": function.is_tag,
+                }),
+            ),
+            ArgumentValue::Objective(objective) => {
+                ("objective", json!({ "name": self.symbol(objective.name) }))
+            }
+            ArgumentValue::EntityAnchor(anchor) => (
+                "entity_anchor",
+                json!({ "anchor": anchor.kind.map(|kind| kind.as_str()) }),
+            ),
+            ArgumentValue::Heightmap(heightmap) => (
+                "heightmap",
+                json!({ "heightmap": heightmap.kind.map(|kind| kind.as_str()) }),
+            ),
+            ArgumentValue::Operation(operation) => (
+                "operation",
+                json!({ "operator": operation.operator.map(|operator| operator.as_str()) }),
+            ),
+            ArgumentValue::Team(team) => ("team", json!({ "name": self.symbol(team.name) })),
+            ArgumentValue::Time(time) => (
+                "time",
+                json!({
+                    "value": time.value,
+                    "unit": time.unit.map(|unit| unit.suffix().to_string()),
+                    "ticks": time.ticks,
+                }),
+            ),
+            ArgumentValue::IntRange(range) => ("int_range", int_range(range)),
+            ArgumentValue::Entity(EntityTarget::Selector(selector))
+            | ArgumentValue::ScoreHolder(ScoreHolder::Selector(selector)) => {
+                ("selector", self.selector(selector))
+            }
+            ArgumentValue::Entity(EntityTarget::Name(name))
+            | ArgumentValue::ScoreHolder(ScoreHolder::Name(name)) => {
+                ("name", json!({ "name": self.symbol(*name) }))
+            }
+            ArgumentValue::Entity(EntityTarget::Uuid(uuid)) => {
+                ("uuid", json!({ "uuid": format!("{uuid:032x}") }))
+            }
+            ArgumentValue::ScoreHolder(ScoreHolder::Wildcard) => ("wildcard", json!({})),
+            ArgumentValue::ItemSlot(slot) => (
+                "item_slot",
+                json!({
+                    "name": self.symbol(slot.name),
+                    "category": slot.category.map(|category| category.prefix()),
+                }),
+            ),
+            ArgumentValue::ItemStack(stack) => {
+                let components = stack
+                    .components
+                    .iter()
+                    .map(|component| match component {
+                        ItemComponent::Set { key, value } => json!({
+                            "key": self.location(*key),
+                            "snbt": value.render(self.interner),
+                        }),
+                        ItemComponent::Removed { key } => json!({
+                            "key": self.location(*key),
+                            "removed": true,
+                        }),
+                    })
+                    .collect::<Vec<_>>();
+                (
+                    "item_stack",
+                    json!({
+                        "id": self.location(stack.id.location),
+                        "components": components,
+                    }),
+                )
+            }
+            ArgumentValue::Nbt(nbt) => ("nbt", json!({ "snbt": nbt.render(self.interner) })),
+            ArgumentValue::NbtPath(path) => {
+                ("nbt_path", json!({ "path": path.render(self.interner) }))
+            }
+            ArgumentValue::Resource(resource) => (
+                "resource",
+                json!({
+                    "registry": self.symbol(resource.registry),
+                    "location": self.location(resource.location),
+                    "is_tag": resource.is_tag,
+                }),
+            ),
+            ArgumentValue::ResourceLocation(location) => (
+                "resource_location",
+                json!({ "location": self.location(*location) }),
+            ),
+            ArgumentValue::Particle(particle) => (
+                "particle",
+                json!({
+                    "id": self.location(particle.id.location),
+                    "options": particle.options.as_ref().map(|options| options.render(self.interner)),
+                    "params_span": particle.params,
+                }),
+            ),
+            ArgumentValue::Custom(_) => ("custom", json!({})),
+        };
+
+        let mut object = Map::new();
+        object.insert("kind".to_owned(), kind.into());
+        if let Value::Object(fields) = fields {
+            object.extend(fields);
+        }
+        Value::Object(object)
+    }
+
+    fn selector(&self, selector: &Selector) -> Value {
+        let options = selector
+            .options
+            .iter()
+            .map(|option| match option {
+                SelectorOption::Scores(scores) => json!({
+                    "name": "scores",
+                    "scores": scores
+                        .iter()
+                        .map(|(objective, range)| {
+                            let objective = self.symbol(*objective).unwrap_or_default();
+                            (objective.to_owned(), int_range(range))
+                        })
+                        .collect::<Map<_, _>>(),
+                }),
+                SelectorOption::Predicate { negated, id } => json!({
+                    "name": "predicate",
+                    "negated": negated,
+                    "id": self.location(*id),
+                }),
+                SelectorOption::Limit(limit) => json!({ "name": "limit", "value": limit }),
+                SelectorOption::Type {
+                    negated,
+                    is_tag,
+                    id,
+                } => json!({
+                    "name": "type",
+                    "negated": negated,
+                    "is_tag": is_tag,
+                    "id": self.location(*id),
+                }),
+                SelectorOption::Other { name, value } => json!({
+                    "name": self.symbol(*name),
+                    "value_span": value,
+                }),
+            })
+            .collect::<Vec<_>>();
+
+        json!({
+            "selector": selector.kind.map(|kind| kind.as_char().to_string()),
+            "options": options,
+        })
+    }
+
+    fn symbol(&self, symbol: Symbol) -> Option<&str> {
+        self.interner.resolve(symbol)
+    }
+
+    fn location(&self, location: Option<ResourceLocation>) -> Option<String> {
+        location.and_then(|location| location.resolve(self.interner))
+    }
+}
+
+fn error(error: &ParseError) -> Value {
+    json!({ "message": error.to_string(), "span": error.span() })
+}
+
+fn int_range(range: &IntRange) -> Value {
+    json!({ "min": range.min, "max": range.max })
+}
+
+fn coordinates<const N: usize>(coords: &Coordinates<N>) -> Value {
+    match coords {
+        Coordinates::World(coords) => json!({
+            "local": false,
+            "values": coords
+                .iter()
+                .map(|WorldCoordinate { value, relative }| {
+                    json!({ "value": value.value, "relative": relative })
+                })
+                .collect::<Vec<_>>(),
+        }),
+        Coordinates::Local(coords) => json!({
+            "local": true,
+            "values": coords
+                .iter()
+                .map(|value| json!({ "value": value.value }))
+                .collect::<Vec<_>>(),
+        }),
+    }
+}
@@ -1,8 +1,14 @@
-pub use context::ParseContext;
+pub use context::{ParseContext, ParseOptions};
 pub use reader::Reader;
 
 pub mod argument;
 mod context;
 pub mod cst;
 pub mod errors;
+pub mod highlight;
+#[cfg(feature = "serde")]
+mod json;
+pub mod outline;
 mod reader;
+pub mod sexpr;
+pub mod tokenize;
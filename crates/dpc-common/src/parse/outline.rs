@@ -0,0 +1,288 @@
+use std::ops::Range;
+
+use super::cst::{ArgumentValue, Block, Item};
+use crate::span::Span;
+
+/// Describes which comments start a new section of a function, e.g. `# --- setup ---`.
+#[derive(Debug, Clone)]
+pub struct SectionMarker {
+    /// The string surrounding the section name. The closing delimiter is optional.
+    pub delimiter: String,
+}
+
+impl Default for SectionMarker {
+    fn default() -> Self {
+        Self {
+            delimiter: "---".to_owned(),
+        }
+    }
+}
+
+impl SectionMarker {
+    /// Returns the section name if the comment is a section marker.
+    pub fn section_name<'a>(&self, comment: &'a str) -> Option<&'a str> {
+        let rest = comment.trim_start().strip_prefix('#')?.trim();
+        let rest = rest.strip_prefix(self.delimiter.as_str())?;
+        let name = rest.strip_suffix(self.delimiter.as_str()).unwrap_or(rest);
+        Some(name.trim()).filter(|name| !name.is_empty())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentSymbol {
+    pub name: String,
+    /// The span from the marker comment to the last item before the next section.
+    pub span: Span,
+    /// The span of the marker comment.
+    pub selection_span: Span,
+    pub children: Vec<DocumentSymbol>,
+}
+
+/// Returns the byte ranges of the block itself, of all nested blocks and of all runs of
+/// consecutive comments, which editors can offer to fold.
+///
+/// The ranges are ordered by their start. Ranges spanning a single line should be ignored by the
+/// caller.
+pub fn folding_ranges(block: &Block) -> Vec<Range<usize>> {
+    fn collect(block: &Block, ranges: &mut Vec<Range<usize>>) {
+        let mut comments: Option<(Range<usize>, usize)> = None;
+
+        for item in &block.items {
+            let Item::Command(command) = item else {
+                let span = item.span();
+                match &mut comments {
+                    Some((range, count)) => {
+                        range.end = span.end;
+                        *count += 1;
+                    }
+                    None => comments = Some((span.as_range(), 1)),
+                }
+                continue;
+            };
+
+            if let Some((range, 2..)) = comments.take() {
+                ranges.push(range);
+            }
+
+            for argument in &command.args {
+                if let ArgumentValue::Block(nested) = &argument.value
+                    && let Some(range) = block_range(nested)
+                {
+                    ranges.push(range);
+                    collect(nested, ranges);
+                }
+            }
+        }
+
+        if let Some((range, 2..)) = comments {
+            ranges.push(range);
+        }
+    }
+
+    let mut ranges = Vec::from_iter(block_range(block));
+    collect(block, &mut ranges);
+    ranges
+}
+
+fn block_range(block: &Block) -> Option<Range<usize>> {
+    let first = block.items.first()?.span();
+    let last = block.items.last()?.span();
+    Some(first.start..last.end)
+}
+
+/// Groups the block into sections delimited by marker comments.
+///
+/// A section extends up to the next marker in the same block. Sections within nested blocks
+/// become children of the section containing the block.
+pub fn document_symbols(
+    block: &Block,
+    source: &str,
+    marker: &SectionMarker,
+) -> Vec<DocumentSymbol> {
+    let mut symbols = Vec::new();
+    let mut sections: Vec<DocumentSymbol> = Vec::new();
+
+    for item in &block.items {
+        match item {
+            Item::Comment(span) => {
+                if let Some(name) = marker.section_name(&source[span.as_range()]) {
+                    sections.push(DocumentSymbol {
+                        name: name.to_owned(),
+                        span: *span,
+                        selection_span: *span,
+                        children: Vec::new(),
+                    });
+                    continue;
+                }
+            }
+            Item::Command(command) => {
+                for argument in &command.args {
+                    if let ArgumentValue::Block(nested) = &argument.value {
+                        let nested = document_symbols(nested, source, marker);
+                        match sections.last_mut() {
+                            Some(section) => section.children.extend(nested),
+                            None => symbols.extend(nested),
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(section) = sections.last_mut() {
+            section.span.end = item.span().end;
+        }
+    }
+
+    symbols.extend(sections);
+    symbols
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::{DocumentSymbol, SectionMarker, document_symbols, folding_ranges};
+    use crate::test_util;
+
+    fn folded_texts(text: &str) -> Vec<&str> {
+        let parsed = test_util::parse(text);
+        assert!(parsed.diagnostics().is_empty());
+        folding_ranges(parsed.result.as_ref().unwrap())
+            .into_iter()
+            .map(|range| &text[range])
+            .collect()
+    }
+
+    #[test]
+    fn nested_blocks_and_outer_block() {
+        let text = "\
+execute as @a run
+    say a
+    execute at @s run
+        say b
+        say c
+say d
+";
+        assert_eq!(
+            folded_texts(text),
+            [
+                text.trim_end(),
+                "say a\n    execute at @s run\n        say b\n        say c",
+                "say b\n        say c",
+            ]
+        );
+    }
+
+    #[test]
+    fn comment_runs() {
+        let text = "# a\n# b\nsay hi\n# c\nexecute run\n    # d\n    # e\n    say nested\n";
+        assert_eq!(
+            folded_texts(text),
+            [
+                text.trim_end(),
+                "# a\n# b",
+                // Comment spans include their indentation
+                "    # d\n    # e\n    say nested",
+                "    # d\n    # e",
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_source_has_no_ranges() {
+        assert!(folded_texts("").is_empty());
+    }
+
+    /// Returns the names of the symbols with the text of their spans, and their children.
+    fn outline<'a>(symbols: &[DocumentSymbol], text: &'a str) -> Vec<(String, &'a str, usize)> {
+        symbols
+            .iter()
+            .map(|symbol| {
+                let name = symbol.name.clone();
+                (name, &text[symbol.span.as_range()], symbol.children.len())
+            })
+            .collect()
+    }
+
+    fn symbols(text: &str, marker: &SectionMarker) -> Vec<DocumentSymbol> {
+        let parsed = test_util::parse(text);
+        assert!(parsed.diagnostics().is_empty());
+        document_symbols(parsed.result.as_ref().unwrap(), text, marker)
+    }
+
+    #[test]
+    fn sections_extend_to_next_marker() {
+        let text = "\
+say before
+# --- setup ---
+scoreboard players set @s obj 0
+# not a marker
+say setup
+# --- tick
+say tick
+";
+        let symbols = symbols(text, &SectionMarker::default());
+        assert_eq!(
+            outline(&symbols, text),
+            [
+                (
+                    "setup".to_owned(),
+                    "# --- setup ---\nscoreboard players set @s obj 0\n# not a marker\nsay setup",
+                    0
+                ),
+                ("tick".to_owned(), "# --- tick\nsay tick", 0),
+            ]
+        );
+        assert_eq!(
+            &text[symbols[0].selection_span.as_range()],
+            "# --- setup ---"
+        );
+    }
+
+    #[test]
+    fn nested_sections() {
+        let text = "\
+# --- outer ---
+execute as @a run
+    # --- first ---
+    say a
+    # --- second ---
+    say b
+say c
+";
+        let symbols = symbols(text, &SectionMarker::default());
+        assert_eq!(
+            outline(&symbols, text),
+            [("outer".to_owned(), text.trim_end(), 2)]
+        );
+        assert_eq!(
+            outline(&symbols[0].children, text),
+            [
+                ("first".to_owned(), "    # --- first ---\n    say a", 0),
+                ("second".to_owned(), "    # --- second ---\n    say b", 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_sections_without_outer_section() {
+        let text = "execute run\n    # === inner\n    say a\n";
+        let marker = SectionMarker {
+            delimiter: "===".to_owned(),
+        };
+        let symbols = symbols(text, &marker);
+        assert_eq!(
+            outline(&symbols, text),
+            [("inner".to_owned(), "    # === inner\n    say a", 0)]
+        );
+        assert!(self::symbols(text, &SectionMarker::default()).is_empty());
+    }
+
+    #[test]
+    fn section_names() {
+        let marker = SectionMarker::default();
+        assert_eq!(marker.section_name("# --- setup ---"), Some("setup"));
+        assert_eq!(marker.section_name("  #---setup"), Some("setup"));
+        assert_eq!(marker.section_name("# ------"), None);
+        assert_eq!(marker.section_name("# setup"), None);
+        assert_eq!(marker.section_name("say --- setup"), None);
+    }
+}
@@ -1,5 +1,7 @@
 use std::ops::Range;
 
+use crate::{source, span::Span};
+
 #[derive(Clone)]
 pub struct Reader<'a> {
     src: &'a str,
@@ -73,6 +75,16 @@ impl<'a> Reader<'a> {
         clone.peek()
     }
 
+    /// Returns the next `len` bytes without advancing, or `None` if fewer remain. If `len` ends
+    /// within a character, the string ends before that character.
+    pub fn peek_str(&self, len: usize) -> Option<&'a str> {
+        let end = self.pos.checked_add(len)?;
+        if end > self.src.len() {
+            return None;
+        }
+        Some(&self.src[self.pos..self.src.floor_char_boundary(end)])
+    }
+
     pub fn advance(&mut self) {
         if let Some(chr) = self.cur {
             self.pos += chr.len_utf8();
@@ -80,6 +92,24 @@ impl<'a> Reader<'a> {
         }
     }
 
+    /// Advances past the next character if it is `chr`. Returns whether it was.
+    pub fn eat(&mut self, chr: char) -> bool {
+        let matches = self.cur == Some(chr);
+        if matches {
+            self.advance();
+        }
+        matches
+    }
+
+    /// Advances past the next character if it is `chr`. Otherwise returns the span of the
+    /// unexpected character, which is empty at the end of the source.
+    pub fn expect(&mut self, chr: char) -> Result<(), Span> {
+        match self.eat(chr) {
+            true => Ok(()),
+            false => Err(Span::new(self.pos, self.get_next_pos())),
+        }
+    }
+
     pub fn skip(&mut self, string: &str) -> bool {
         if self.remaining_src().starts_with(string) {
             self.set_pos(self.pos + string.len());
@@ -116,4 +146,121 @@ impl<'a> Reader<'a> {
     pub fn read_literal(&mut self) -> &'a str {
         self.read_until(|chr| chr.is_whitespace())
     }
+
+    /// Returns the 1-based line and the 0-based column of the position, which is counted in
+    /// characters.
+    pub fn line_column(&self) -> (usize, usize) {
+        source::line_col(self.src, self.pos).expect("reader is at a character boundary")
+    }
+
+    /// Reads everything up to the next line break or the end of the source and advances past the
+    /// line break. The returned line does not include the line break.
+    pub fn rest_of_line(&mut self) -> &'a str {
+        let line = self.read_until(|chr| matches!(chr, '\n' | '\r'));
+        // A line break is either `\n`, `\r\n` or a lone `\r`
+        self.eat('\r');
+        self.eat('\n');
+        line
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Reader;
+    use crate::span::Span;
+
+    #[test]
+    fn rest_of_line_advances_past_line_break() {
+        let mut reader = Reader::new("say hi\nsay there");
+        reader.set_pos(4);
+        assert_eq!(reader.rest_of_line(), "hi");
+        assert_eq!(reader.get_pos(), 7);
+        assert_eq!(reader.rest_of_line(), "say there");
+        assert!(!reader.has_more());
+
+        let mut reader = Reader::new("say hi\r\nsay there\r\r\n");
+        reader.set_pos(4);
+        assert_eq!(reader.rest_of_line(), "hi");
+        assert_eq!(reader.get_pos(), 8);
+        assert_eq!(reader.rest_of_line(), "say there");
+        assert_eq!(reader.get_pos(), 18);
+        assert_eq!(reader.rest_of_line(), "");
+        assert!(!reader.has_more());
+    }
+
+    #[test]
+    fn rest_of_line_stops_at_end_of_range() {
+        let mut reader = Reader::with_range("say hi there\nsay bye", 4..6);
+        assert_eq!(reader.rest_of_line(), "hi");
+        assert!(!reader.has_more());
+        assert_eq!(reader.rest_of_line(), "");
+    }
+
+    #[test]
+    fn eat_only_advances_on_match() {
+        let mut reader = Reader::new("[ä]");
+        assert!(!reader.eat(']'));
+        assert_eq!(reader.get_pos(), 0);
+        assert!(reader.eat('['));
+        assert!(reader.eat('ä'));
+        assert_eq!(reader.get_pos(), 3);
+        assert!(reader.eat(']'));
+        assert!(!reader.eat(']'));
+        assert_eq!(reader.get_pos(), 4);
+    }
+
+    #[test]
+    fn expect_reports_unexpected_char() {
+        let mut reader = Reader::new("{äb");
+        assert_eq!(reader.expect('{'), Ok(()));
+        // The span covers the whole unexpected character
+        assert_eq!(reader.expect('}'), Err(Span::new(1, 3)));
+        assert_eq!(reader.get_pos(), 1);
+
+        let mut reader = Reader::with_range("{} more", 0..1);
+        assert_eq!(reader.expect('{'), Ok(()));
+        // At the end of the range, the span is empty
+        assert_eq!(reader.expect('}'), Err(Span::new(1, 1)));
+    }
+
+    #[test]
+    fn peek_str_at_end_of_input() {
+        let mut reader = Reader::new("1..5");
+        reader.set_pos(1);
+        assert_eq!(reader.peek_str(2), Some(".."));
+        assert_eq!(reader.get_pos(), 1);
+        assert_eq!(reader.peek_str(3), Some("..5"));
+        assert_eq!(reader.peek_str(4), None);
+        assert_eq!(reader.peek_str(usize::MAX), None);
+
+        reader.set_pos(4);
+        assert_eq!(reader.peek_str(0), Some(""));
+        assert_eq!(reader.peek_str(1), None);
+
+        let reader = Reader::with_range("1..5 more", 0..4);
+        assert_eq!(reader.peek_str(5), None);
+    }
+
+    #[test]
+    fn peek_str_at_multibyte_boundary() {
+        // `ä` takes two bytes
+        let reader = Reader::new("aäb");
+        assert_eq!(reader.peek_str(1), Some("a"));
+        assert_eq!(reader.peek_str(2), Some("a"));
+        assert_eq!(reader.peek_str(3), Some("aä"));
+        assert_eq!(reader.peek_str(4), Some("aäb"));
+        assert_eq!(reader.peek_str(5), None);
+    }
+
+    #[test]
+    fn line_column() {
+        let mut reader = Reader::new("say ä\nsay b");
+        assert_eq!(reader.line_column(), (1, 0));
+        reader.read_until(|chr| chr == '\n');
+        assert_eq!(reader.line_column(), (1, 5));
+        reader.rest_of_line();
+        assert_eq!(reader.line_column(), (2, 0));
+        reader.read_literal();
+        assert_eq!(reader.line_column(), (2, 3));
+    }
 }
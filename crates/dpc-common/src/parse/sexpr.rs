@@ -0,0 +1,341 @@
+use std::fmt::{self, Write};
+
+use crate::{
+    ParsingTree,
+    intern::Interner,
+    parse::{
+        argument::{
+            Coordinates, EntityTarget, IntRange, ItemComponent, ResourceLocation, ScoreHolder,
+            Selector, SelectorOption, TimeUnit, WorldCoordinate,
+        },
+        cst::{ArgumentValue, Block, Command, Item},
+    },
+};
+
+/// Renders the items of the block as compact S-expressions, one item per line, e.g.
+/// `(command (lit execute) (lit run) (block (command (lit say) (str "hi"))))`.
+///
+/// Values which failed to parse are rendered as `?`, and errors as `(error <span>)`. This is meant
+/// for debugging and comparing parse results, the format is not stable.
+pub fn to_sexpr(block: &Block, tree: &ParsingTree, interner: &impl Interner) -> String {
+    let mut out = String::new();
+    let mut writer = SexprWriter {
+        out: &mut out,
+        tree,
+        interner,
+    };
+    for item in &block.items {
+        writer.write_item(item).unwrap();
+        writer.out.push('\n');
+    }
+    out
+}
+
+struct SexprWriter<'a, I> {
+    out: &'a mut String,
+    tree: &'a ParsingTree,
+    interner: &'a I,
+}
+
+impl<I: Interner> SexprWriter<'_, I> {
+    fn write_block(&mut self, block: &Block) -> fmt::Result {
+        self.out.push_str("(block");
+        for item in &block.items {
+            self.out.push(' ');
+            self.write_item(item)?;
+        }
+        self.out.push(')');
+        Ok(())
+    }
+
+    fn write_item(&mut self, item: &Item) -> fmt::Result {
+        match item {
+            Item::Command(command) => self.write_command(command),
+            Item::Comment(span) => write!(self.out, "(comment {span:?})"),
+        }
+    }
+
+    fn write_command(&mut self, command: &Command) -> fmt::Result {
+        self.out.push_str("(command");
+        for arg in &command.args {
+            self.out.push(' ');
+            self.write_value(&arg.value, arg.lin_node_id)?;
+            for error in &arg.errors {
+                write!(self.out, " (error {:?})", error.span())?;
+            }
+        }
+        if let Some(error) = &command.error {
+            write!(self.out, " (error {:?})", error.span())?;
+        }
+        self.out.push(')');
+        Ok(())
+    }
+
+    fn write_value(&mut self, value: &ArgumentValue, node_id: usize) -> fmt::Result {
+        match value {
+            ArgumentValue::Literal => {
+                let name = self.tree.get_node(node_id).map_or("?", |node| node.name());
+                write!(self.out, "(lit {name})")
+            }
+            ArgumentValue::Block(block) => self.write_block(block),
+            ArgumentValue::Boolean(boolean) => write!(self.out, "(bool {})", opt(boolean.value)),
+            ArgumentValue::Integer(integer) => write!(self.out, "(int {})", opt(integer.value)),
+            ArgumentValue::Float(float) => write!(self.out, "(float {})", opt(float.value)),
+            ArgumentValue::Double(double) => write!(self.out, "(double {})", opt(double.value)),
+            ArgumentValue::String(text) => {
+                match text.value.and_then(|sym| self.interner.resolve(sym)) {
+                    Some(string) => write!(self.out, "(str {string:?})"),
+                    None => write!(self.out, "(str ?)"),
+                }
+            }
+            ArgumentValue::Angle(angle) => write!(
+                self.out,
+                "(angle {}{})",
+                if angle.relative { "~" } else { "" },
+                opt(angle.value.value),
+            ),
+            ArgumentValue::Coordinates2(coords) => self.write_coordinates(coords),
+            ArgumentValue::Coordinates3(coords) => self.write_coordinates(coords),
+            ArgumentValue::Color(color) => write!(self.out, "(color {})", opt(color.color)),
+            ArgumentValue::Function(function) => {
+                let prefix = if function.is_tag { "#" } else { "" };
+                match function
+                    .location
+                    .and_then(|location| location.resolve(self.interner))
+                {
+                    Some(location) => write!(self.out, "(function {prefix}{location})"),
+                    None => write!(self.out, "(function {prefix}?)"),
+                }
+            }
+            ArgumentValue::Objective(objective) => {
+                let name = self.interner.resolve(objective.name).unwrap_or("?");
+                write!(self.out, "(objective {name})")
+            }
+            ArgumentValue::EntityAnchor(anchor) => {
+                write!(self.out, "(anchor {})", opt(anchor.kind))
+            }
+            ArgumentValue::Heightmap(heightmap) => {
+                write!(self.out, "(heightmap {})", opt(heightmap.kind))
+            }
+            ArgumentValue::Operation(operation) => {
+                write!(self.out, "(operation {})", opt(operation.operator))
+            }
+            ArgumentValue::Team(team) => {
+                let name = self.interner.resolve(team.name).unwrap_or("?");
+                write!(self.out, "(team {name})")
+            }
+            ArgumentValue::Time(time) => write!(
+                self.out,
+                "(time {}{} {})",
+                opt(time.value),
+                time.unit
+                    .map(TimeUnit::suffix)
+                    .map(String::from)
+                    .unwrap_or_default(),
+                opt(time.ticks),
+            ),
+            ArgumentValue::IntRange(range) => write!(self.out, "(range {})", fmt_range(range)),
+            ArgumentValue::Entity(EntityTarget::Selector(selector))
+            | ArgumentValue::ScoreHolder(ScoreHolder::Selector(selector)) => {
+                self.write_selector(selector)
+            }
+            ArgumentValue::Entity(EntityTarget::Name(name))
+            | ArgumentValue::ScoreHolder(ScoreHolder::Name(name)) => {
+                write!(
+                    self.out,
+                    "(name {})",
+                    self.interner.resolve(*name).unwrap_or("?")
+                )
+            }
+            ArgumentValue::Entity(EntityTarget::Uuid(uuid)) => {
+                write!(self.out, "(uuid {uuid:032x})")
+            }
+            ArgumentValue::ScoreHolder(ScoreHolder::Wildcard) => write!(self.out, "(wildcard)"),
+            ArgumentValue::ItemSlot(slot) => {
+                let name = self.interner.resolve(slot.name).unwrap_or("?");
+                write!(self.out, "(slot {name})")
+            }
+            ArgumentValue::ItemStack(stack) => {
+                write!(
+                    self.out,
+                    "(item {}",
+                    self.resolve_location(stack.id.location)
+                )?;
+                for component in &stack.components {
+                    match component {
+                        ItemComponent::Set { key, value } => write!(
+                            self.out,
+                            " (set {} {})",
+                            self.resolve_location(*key),
+                            value.render(self.interner)
+                        )?,
+                        ItemComponent::Removed { key } => {
+                            write!(self.out, " (remove {})", self.resolve_location(*key))?
+                        }
+                    }
+                }
+                write!(self.out, ")")
+            }
+            ArgumentValue::Nbt(nbt) => write!(self.out, "(nbt {})", nbt.render(self.interner)),
+            ArgumentValue::NbtPath(path) => {
+                write!(self.out, "(nbt_path {})", path.render(self.interner))
+            }
+            ArgumentValue::Resource(resource) => write!(
+                self.out,
+                "(resource {} {}{})",
+                self.interner.resolve(resource.registry).unwrap_or("?"),
+                if resource.is_tag { "#" } else { "" },
+                self.resolve_location(resource.location),
+            ),
+            ArgumentValue::ResourceLocation(location) => {
+                write!(self.out, "(location {})", self.resolve_location(*location))
+            }
+            ArgumentValue::Particle(particle) => {
+                write!(
+                    self.out,
+                    "(particle {}",
+                    self.resolve_location(particle.id.location)
+                )?;
+                if let Some(options) = &particle.options {
+                    write!(self.out, " {}", options.render(self.interner))?;
+                }
+                write!(self.out, ")")
+            }
+            ArgumentValue::Custom(_) => write!(self.out, "(custom)"),
+        }
+    }
+
+    fn write_selector(&mut self, selector: &Selector) -> fmt::Result {
+        write!(
+            self.out,
+            "(selector @{}",
+            opt(selector.kind.map(|kind| kind.as_char()))
+        )?;
+        for option in &selector.options {
+            match option {
+                SelectorOption::Scores(scores) => {
+                    self.out.push_str(" (scores");
+                    for (objective, range) in scores {
+                        let objective = self.interner.resolve(*objective).unwrap_or("?");
+                        write!(self.out, " {objective}={}", fmt_range(range))?;
+                    }
+                    self.out.push(')');
+                }
+                SelectorOption::Predicate { negated, id } => write!(
+                    self.out,
+                    " (predicate {}{})",
+                    if *negated { "!" } else { "" },
+                    self.resolve_location(*id),
+                )?,
+                SelectorOption::Limit(limit) => write!(self.out, " (limit {})", opt(*limit))?,
+                SelectorOption::Type {
+                    negated,
+                    is_tag,
+                    id,
+                } => write!(
+                    self.out,
+                    " (type {}{}{})",
+                    if *negated { "!" } else { "" },
+                    if *is_tag { "#" } else { "" },
+                    self.resolve_location(*id),
+                )?,
+                SelectorOption::Other { name, .. } => {
+                    let name = self.interner.resolve(*name).unwrap_or("?");
+                    write!(self.out, " (option {name})")?;
+                }
+            }
+        }
+        self.out.push(')');
+        Ok(())
+    }
+
+    fn resolve_location(&self, location: Option<ResourceLocation>) -> String {
+        location
+            .and_then(|location| location.resolve(self.interner))
+            .unwrap_or_else(|| "?".to_owned())
+    }
+
+    fn write_coordinates<const N: usize>(&mut self, coords: &Coordinates<N>) -> fmt::Result {
+        self.out.push_str("(coords");
+        match coords {
+            Coordinates::World(coords) => {
+                for WorldCoordinate { value, relative } in coords {
+                    let prefix = if *relative { "~" } else { "" };
+                    write!(self.out, " {prefix}{}", opt(value.value))?;
+                }
+            }
+            Coordinates::Local(coords) => {
+                for value in coords {
+                    write!(self.out, " ^{}", opt(value.value))?;
+                }
+            }
+        }
+        self.out.push(')');
+        Ok(())
+    }
+}
+
+fn fmt_range(range: &IntRange) -> String {
+    match (range.min, range.max) {
+        (Some(min), Some(max)) if min == max => min.to_string(),
+        (min, max) => format!(
+            "{}..{}",
+            min.map(|min| min.to_string()).unwrap_or_default(),
+            max.map(|max| max.to_string()).unwrap_or_default(),
+        ),
+    }
+}
+
+fn opt<T: fmt::Display>(value: Option<T>) -> String {
+    value.map_or_else(|| "?".to_owned(), |value| value.to_string())
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::to_sexpr;
+    use crate::test_util;
+
+    fn sexpr(text: &str) -> String {
+        let parsed = test_util::parse(text);
+        assert!(
+            parsed.diagnostics().is_empty(),
+            "{:?}",
+            parsed.diagnostics()
+        );
+        to_sexpr(
+            parsed.result.as_ref().unwrap(),
+            &parsed.tree,
+            &parsed.interner,
+        )
+    }
+
+    #[test]
+    fn execute_run_block() {
+        assert_eq!(
+            sexpr("execute run\n    say hi\n"),
+            "(command (lit execute) (lit run) (block (command (lit say) (str \"hi\"))))\n"
+        );
+    }
+
+    #[test]
+    fn arguments_and_comments() {
+        assert_eq!(
+            sexpr("# x\nscoreboard players set @a[scores={o=1..}] o 5\ntp @s ~ ~1 5\n"),
+            "(comment 0..3)\n\
+             (command (lit scoreboard) (lit players) (lit set) (selector @a (scores o=1..)) \
+             (objective o) (int 5))\n\
+             (command (lit tp) (selector @s) (coords ~0 ~1 5))\n"
+        );
+    }
+
+    #[test]
+    fn errors_are_rendered() {
+        let parsed = test_util::parse("time set nigth\n");
+        let sexpr = to_sexpr(
+            parsed.result.as_ref().unwrap(),
+            &parsed.tree,
+            &parsed.interner,
+        );
+        assert_eq!(sexpr, "(command (lit time) (lit set) (error 9..14))\n");
+    }
+}
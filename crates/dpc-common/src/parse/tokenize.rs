@@ -0,0 +1,164 @@
+use super::Reader;
+use crate::span::Span;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Word,
+    /// A number, range (`1..3`) or coordinate (`~1`, `^`).
+    Number,
+    /// A quoted string, including its quotation marks.
+    String,
+    /// An entity selector, e.g. `@s` or `@e[type=pig]`.
+    Selector,
+    /// A word with brackets, e.g. `{Count:1b}` or `stone[facing=up]`.
+    Bracketed,
+    Comment,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    pub span: Span,
+    pub kind: TokenKind,
+}
+
+/// Splits a command line into whitespace-separated tokens without using the parsing tree.
+///
+/// This is much less precise than [`semantic_tokens`](super::highlight::semantic_tokens), which
+/// needs the parsed command, but works on any input. Whitespace within quotes and brackets does
+/// not end a token. Unterminated strings and brackets extend to the end of the line.
+pub fn tokenize(line: &str) -> Vec<Token> {
+    let mut reader = Reader::new(line);
+    let mut tokens = Vec::new();
+
+    loop {
+        reader.skip_whitespace();
+        let start = reader.get_pos();
+        let Some(first) = reader.peek() else {
+            break;
+        };
+
+        if first == '#' && tokens.is_empty() {
+            reader.read_until(|chr| chr == '\n');
+            tokens.push(Token {
+                span: Span::new(start, reader.get_pos()),
+                kind: TokenKind::Comment,
+            });
+            continue;
+        }
+
+        let mut bracketed = false;
+        while let Some(chr) = reader.peek() {
+            match chr {
+                '"' | '\'' => skip_quoted(&mut reader, chr),
+                '[' | '{' | '(' => {
+                    skip_bracketed(&mut reader);
+                    bracketed = true;
+                }
+                _ if chr.is_whitespace() => break,
+                _ => reader.advance(),
+            }
+        }
+
+        let span = Span::new(start, reader.get_pos());
+        let text = &line[span.as_range()];
+        let kind = match first {
+            '"' | '\'' if !bracketed => TokenKind::String,
+            '@' => TokenKind::Selector,
+            _ if bracketed => TokenKind::Bracketed,
+            _ if is_number(text) => TokenKind::Number,
+            _ => TokenKind::Word,
+        };
+        tokens.push(Token { span, kind });
+    }
+
+    tokens
+}
+
+/// Advances past the quoted string starting at the reader, honoring backslash escapes.
+pub(crate) fn skip_quoted(reader: &mut Reader<'_>, quote: char) {
+    reader.advance();
+    while let Some(chr) = reader.peek() {
+        reader.advance();
+        match chr {
+            '\\' => reader.advance(),
+            _ if chr == quote => return,
+            _ => (),
+        }
+    }
+}
+
+/// Advances past the brackets starting at the reader, including any nested brackets and strings.
+pub(crate) fn skip_bracketed(reader: &mut Reader<'_>) {
+    let mut depth = 0usize;
+    while let Some(chr) = reader.peek() {
+        match chr {
+            '"' | '\'' => {
+                skip_quoted(reader, chr);
+                continue;
+            }
+            '[' | '{' | '(' => depth += 1,
+            ']' | '}' | ')' => depth = depth.saturating_sub(1),
+            _ => (),
+        }
+        reader.advance();
+        if depth == 0 {
+            return;
+        }
+    }
+}
+
+fn is_number(text: &str) -> bool {
+    text.chars()
+        .all(|chr| chr.is_ascii_digit() || matches!(chr, '.' | '-' | '+' | '~' | '^'))
+        && (text.contains(|chr: char| chr.is_ascii_digit()) || text.starts_with(['~', '^']))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TokenKind, tokenize};
+
+    fn tokens(line: &str) -> Vec<(&str, TokenKind)> {
+        tokenize(line)
+            .into_iter()
+            .map(|token| (&line[token.span.as_range()], token.kind))
+            .collect()
+    }
+
+    #[test]
+    fn execute_if_score() {
+        use TokenKind::*;
+        assert_eq!(
+            tokens("execute if score @s o matches 1..3 run say hi"),
+            [
+                ("execute", Word),
+                ("if", Word),
+                ("score", Word),
+                ("@s", Selector),
+                ("o", Word),
+                ("matches", Word),
+                ("1..3", Number),
+                ("run", Word),
+                ("say", Word),
+                ("hi", Word),
+            ]
+        );
+    }
+
+    #[test]
+    fn whitespace_in_strings_and_brackets() {
+        use TokenKind::*;
+        assert_eq!(
+            tokens("give @a[tag=a, limit=1] stone{display:{Name:'\"a b\"'}} \"x y\" ~ ^1"),
+            [
+                ("give", Word),
+                ("@a[tag=a, limit=1]", Selector),
+                ("stone{display:{Name:'\"a b\"'}}", Bracketed),
+                ("\"x y\"", String),
+                ("~", Number),
+                ("^1", Number),
+            ]
+        );
+        assert_eq!(tokens("# a comment"), [("# a comment", Comment)]);
+        assert_eq!(tokens("say \"open"), [("say", Word), ("\"open", String)]);
+    }
+}
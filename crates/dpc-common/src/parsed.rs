@@ -0,0 +1,196 @@
+use std::{mem, ops::Range, path::PathBuf, sync::Arc};
+
+use crate::{
+    ParsingTree,
+    diagnostics::Diagnostic,
+    intern::StaticInterner,
+    parse::{
+        ParseContext, cst,
+        errors::{EmitDiagnostic, ParseError, ParseErrorVisitor},
+    },
+    source::SourceFile,
+};
+
+/// A parsed source file, along with everything needed to report its diagnostics and to resolve
+/// the symbols in its syntax tree.
+pub struct Parsed {
+    pub source: SourceFile,
+    pub tree: Arc<ParsingTree>,
+    pub result: Result<cst::Block, ParseError>,
+    pub interner: StaticInterner,
+}
+
+impl Parsed {
+    /// Returns the diagnostics of all parse errors, sorted by their position.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        collect_diagnostics(&self.source, &self.tree, &self.result)
+    }
+
+    /// Replaces the text in `range` by `new_text` and parses the source again. Only the commands
+    /// affected by the edit are parsed again, which keeps this fast for small edits, e.g. in an
+    /// editor.
+    pub fn edit(&mut self, range: Range<usize>, new_text: &str) {
+        let mut ctx = ParseContext::new(&self.source, Arc::clone(&self.tree));
+        ctx.interner = mem::take(&mut self.interner);
+        if let Ok(previous) = &mut self.result {
+            ctx.previous = Some(cst::Block {
+                span: previous.span,
+                items: mem::take(&mut previous.items),
+            });
+        }
+        let result = ctx.reparse_range(range, new_text);
+        self.interner = mem::take(&mut ctx.interner);
+        self.source = ctx.source.into_owned();
+        self.result = result;
+    }
+}
+
+/// Returns the diagnostics of all parse errors in the result, sorted by their position.
+pub(crate) fn collect_diagnostics(
+    source: &SourceFile,
+    tree: &Arc<ParsingTree>,
+    result: &Result<cst::Block, ParseError>,
+) -> Vec<Diagnostic> {
+    let ctx = ParseContext::new(source, Arc::clone(tree));
+    let mut diagnostics = match result {
+        Ok(block) => {
+            let mut visitor = ParseErrorVisitor::new(&ctx);
+            cst::walk_block(&mut visitor, block);
+            visitor.diagnostics
+        }
+        Err(err) => vec![err.emit(&ctx)],
+    };
+    diagnostics.sort_by_key(Diagnostic::span);
+    diagnostics
+}
+
+/// Parses the text with the default options. This bundles creating the [`SourceFile`] and the
+/// [`ParseContext`], use those directly for more control.
+///
+/// ```
+/// # #[cfg(feature = "serde")] {
+/// # use std::sync::Arc;
+/// # let commands_json = std::fs::read_to_string("../../commands.json").unwrap();
+/// let tree = Arc::new(dpc_common::load_tree(&commands_json).unwrap());
+/// let parsed = dpc_common::parse_source(tree, None, "say hi\nkill @e[type=cow]\n".to_owned());
+/// assert!(parsed.diagnostics().is_empty());
+/// # }
+/// ```
+pub fn parse_source(tree: Arc<ParsingTree>, path: Option<PathBuf>, text: String) -> Parsed {
+    let source = SourceFile::new(path, text);
+    let mut ctx = ParseContext::new(&source, Arc::clone(&tree));
+    let result = ctx.parse();
+    let interner = mem::take(&mut ctx.interner);
+    Parsed {
+        source,
+        tree,
+        result,
+        interner,
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use std::{mem, sync::Arc};
+
+    use crate::{Parsed, parse::ParseContext, source::SourceFile, test_util};
+
+    const COMMANDS: [&str; 10] = [
+        "scoreboard players operation @s obj += #x obj",
+        "team join red @a[tag=x,distance=..5]",
+        "execute anchored eyes positioned over world_surface run say hi",
+        "item replace entity @s weapon.mainhand from entity @p container.0",
+        "particle dust{color:[1f,0f,0f],scale:1f} ~ ~ ~",
+        "scoreboard players set @e[scores={obj=1..},name=foo] obj 5",
+        "# a comment",
+        "execute if entity @s run\n    scoreboard players reset @s obj",
+        "kill @e[type=!player,limit=1]",
+        "tp @s ~ ~1 ~",
+    ];
+
+    /// Asserts that the result equals parsing the current source from scratch. The interner is
+    /// reused, so the symbols of both parses are the same.
+    fn assert_fresh_parse(parsed: &mut Parsed) {
+        let mut ctx = ParseContext::new(&parsed.source, Arc::clone(&parsed.tree));
+        ctx.interner = mem::take(&mut parsed.interner);
+        let fresh = ctx.parse();
+        parsed.interner = mem::take(&mut ctx.interner);
+        assert_eq!(parsed.result, fresh);
+    }
+
+    #[test]
+    fn incremental_parse_equals_fresh_parse() {
+        let text: String = COMMANDS
+            .iter()
+            .cycle()
+            .take(100)
+            .map(|c| format!("{c}\n"))
+            .collect();
+        let mut parsed = test_util::parse(&text);
+        assert!(
+            parsed.diagnostics().is_empty(),
+            "{:?}",
+            parsed.diagnostics()
+        );
+
+        // Moves every following command
+        parsed.edit(0..0, "say x\n");
+        assert_fresh_parse(&mut parsed);
+
+        // Changes a single command in the middle of the file
+        let score = parsed.source.text().find("obj 5").unwrap() + 4;
+        parsed.edit(score..score + 1, "-12");
+        assert_fresh_parse(&mut parsed);
+
+        // Removes a line, which shortens the file
+        let start = parsed.source.text().find("tp @s").unwrap();
+        parsed.edit(start..start + "tp @s ~ ~1 ~\n".len(), "");
+        assert_fresh_parse(&mut parsed);
+
+        // Adds a command to a nested block
+        let block = parsed.source.text().find("    scoreboard").unwrap();
+        parsed.edit(block..block, "    say nested\n");
+        assert_fresh_parse(&mut parsed);
+    }
+
+    #[test]
+    fn reparse_range_equals_full_parse() {
+        let text: String = COMMANDS
+            .iter()
+            .cycle()
+            .take(100)
+            .map(|c| format!("{c}\n"))
+            .collect();
+        let source = SourceFile::new(None, text.clone());
+        let mut ctx = ParseContext::new(&source, test_util::tree());
+        ctx.previous = Some(ctx.parse().unwrap());
+
+        // Parses the edited source from scratch, with the same interner
+        let full_parse = |ctx: &mut ParseContext<'_>| {
+            let source = SourceFile::new(None, ctx.source.text().to_owned());
+            let mut fresh = ParseContext::new(&source, test_util::tree());
+            fresh.interner = mem::take(&mut ctx.interner);
+            let result = fresh.parse();
+            ctx.interner = mem::take(&mut fresh.interner);
+            result
+        };
+
+        // Replaces the name of a command in the middle of the file
+        let middle = text.len() / 2;
+        let kill = middle + text[middle..].find("kill @e").unwrap();
+        let result = ctx.reparse_range(kill..kill + "kill".len(), "tp");
+        assert!(ctx.source.text()[kill..].starts_with("tp @e[type=!player"));
+        assert_eq!(source.text(), text);
+        assert_eq!(result, full_parse(&mut ctx));
+
+        // Moves a line out of the nested block before it
+        ctx.previous = Some(result.unwrap());
+        let nested = middle + ctx.source.text()[middle..].find("    scoreboard").unwrap();
+        let result = ctx.reparse_range(nested..nested + 4, "");
+        assert_eq!(result, full_parse(&mut ctx));
+
+        // Without a previous result, the whole source is parsed
+        let result = ctx.reparse_range(0..0, "say x\n");
+        assert_eq!(result, full_parse(&mut ctx));
+    }
+}
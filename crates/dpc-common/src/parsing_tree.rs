@@ -1,31 +1,92 @@
-use std::{cmp::Ordering, fmt, iter, ops::Range};
+use std::{fmt, iter, mem, ops::Range};
 
-use smallvec::SmallVec;
+use rustc_hash::FxHashMap;
+use smallvec::{SmallVec, smallvec};
 
 use super::{Node, NodeKind};
 use crate::{
+    intern::{Interner, StaticInterner, Symbol},
     parse::{
         ParseContext, Reader,
-        argument::ParseArgContext,
+        argument::{Argument as ArgumentType, ParseArgContext},
         cst::{Argument, ArgumentValue, Block, Command, Item},
         errors::{
-            IndentationError, IndentationErrorKind, InvalidLiteralError, ParseError,
-            TooManyArgumentsError,
+            ForbiddenCommandError, IncompleteCommandError, IndentationError, IndentationErrorKind,
+            InvalidLiteralError, MissingRunBodyError, ParseComplexityWarning, ParseError,
+            TooManyArgumentsError, TrailingArgumentCharsError,
         },
     },
     span::Span,
 };
 
+/// The similarity to a literal above which input that matches no child is reported as a
+/// misspelled literal instead of with the error of an argument child.
+const INVALID_LITERAL_SIMILARITY: f64 = 0.5;
+
+/// The similarity to a literal above which parsing continues as if the literal was matched, when
+/// recovering from errors.
+const RECOVERY_LITERAL_SIMILARITY: f64 = 0.8;
+
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub struct ParsingNode {
     pub(super) node: Node,
+    /// The name of a literal node, interned in [`ParsingTree::literals`], so that literals can be
+    /// matched by comparing symbols.
+    #[cfg_attr(feature = "cache", serde(skip))]
+    pub(super) literal: Option<Symbol>,
     pub(super) children: Range<usize>,
+    /// Whether the children are those of the node this node redirects to.
+    pub(super) redirect: bool,
 }
 
+/// The parameters of a command, as returned by [`ParsingTree::signature`].
+#[derive(Debug)]
+pub struct Signature<'t> {
+    pub parameters: Vec<Parameter<'t>>,
+    /// The index of the parameter of the node the signature was requested for.
+    pub active_parameter: usize,
+}
+
+#[derive(Debug)]
+pub struct Parameter<'t> {
+    pub node_id: usize,
+    pub name: &'t str,
+    /// The type of the parameter, or `None` for literals and blocks.
+    pub argument: Option<&'t ArgumentType>,
+}
+
+/// Something which may be typed at a position, as returned by [`ParsingTree::completions_at`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Completion {
+    pub node_id: usize,
+    /// The name of a literal, or the type of an argument as printed by its `Debug` impl, e.g.
+    /// `int_range`.
+    pub text: String,
+    pub kind: CompletionKind,
+    /// Whether a command may end after this node.
+    pub executable: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionKind {
+    Literal,
+    Argument,
+}
+
+/// The range of a command and its leading literals, as returned by
+/// [`ParsingTree::index_commands`].
+pub type IndexedCommand = (Range<usize>, Vec<String>);
+
 #[derive(Default)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub struct ParsingTree {
     pub(super) nodes: Vec<ParsingNode>,
     pub(super) num_roots: usize,
+    /// The distinct names of all literal nodes. Not cached, since they are interned again from the
+    /// nodes after reading the cache.
+    #[cfg_attr(feature = "cache", serde(skip))]
+    pub(super) literals: StaticInterner,
 }
 
 struct ParseResult {
@@ -38,8 +99,266 @@ impl ParsingTree {
         self.nodes.get(idx).map(|lin_node| &lin_node.node)
     }
 
+    /// Returns the interner holding the names of all literal nodes.
+    pub fn literals(&self) -> &StaticInterner {
+        &self.literals
+    }
+
+    /// Returns the interned name of the node, if it is a literal.
+    pub fn literal_symbol(&self, idx: usize) -> Option<Symbol> {
+        self.nodes.get(idx)?.literal
+    }
+
+    /// Interns the names of all literal nodes into [`Self::literals`].
+    pub(crate) fn intern_literals(&mut self) {
+        let mut literals = StaticInterner::new();
+        for node in &mut self.nodes {
+            node.literal = match &node.node.kind {
+                NodeKind::Literal(name) => Some(literals.intern(name)),
+                NodeKind::Argument { .. } | NodeKind::Block => None,
+            };
+        }
+        self.literals = literals;
+    }
+
     pub fn parse(&self, ctx: &mut ParseContext<'_>) -> Result<Block, ParseError> {
-        self.parse_commands(Reader::new(ctx.source.text()), 0, ctx)
+        let source = ctx.source.shared_text();
+        self.parse_commands(Reader::new(&source), 0, ctx)
+    }
+
+    /// Returns the index of the node at the end of the path of node names.
+    pub fn find_node<T: AsRef<str>>(&self, path: impl IntoIterator<Item = T>) -> Option<usize> {
+        let mut children = 0..self.num_roots;
+        let mut node_idx = None;
+        for path_element in path {
+            let idx = children
+                .clone()
+                .find(|&idx| self.nodes[idx].node.name() == path_element.as_ref())?;
+            node_idx = Some(idx);
+            children = self.nodes[idx].children.clone();
+        }
+        node_idx
+    }
+
+    /// Returns the parameters of the command containing the node, for signature help.
+    ///
+    /// The parameters start at the root of the command and include the node itself. They continue
+    /// past the node for as long as each node has exactly one possible next parameter.
+    pub fn signature(&self, node_id: usize) -> Option<Signature<'_>> {
+        let mut path = self.node_path(node_id)?;
+
+        let active_parameter = path.len() - 1;
+        loop {
+            let last = &self.nodes[path[path.len() - 1]];
+            // Stop at redirects, so that recursive commands like `execute` end
+            if last.children.len() != 1 || last.redirect {
+                break;
+            }
+            path.push(last.children.start);
+        }
+
+        Some(Signature {
+            parameters: path
+                .into_iter()
+                .map(|node_id| {
+                    let node = &self.nodes[node_id].node;
+                    Parameter {
+                        node_id,
+                        name: node.name(),
+                        argument: match &node.kind {
+                            NodeKind::Argument { arg, .. } => Some(arg),
+                            NodeKind::Literal(_) | NodeKind::Block => None,
+                        },
+                    }
+                })
+                .collect(),
+            active_parameter,
+        })
+    }
+
+    /// Returns the literals and arguments which may follow the text before `offset`, for
+    /// autocompletion. The word the offset is in is not taken into account, so the completions
+    /// still have to be filtered by what was typed of it.
+    ///
+    /// Returns nothing in comments and after text which does not match any command.
+    pub fn completions_at(&self, ctx: &mut ParseContext<'_>, offset: usize) -> Vec<Completion> {
+        let source = ctx.source.shared_text();
+        let source = &*source;
+        let offset = source.floor_char_boundary(offset.min(source.len()));
+        let line_start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line = &source[line_start..offset];
+        if line.trim_start().starts_with('#') {
+            return Vec::new();
+        }
+        let word_start = line
+            .rfind(char::is_whitespace)
+            .map_or(line_start, |i| line_start + i + 1);
+
+        let mut reader = Reader::with_range(source, line_start..word_start);
+        reader.skip_whitespace();
+        let children = if reader.has_more() {
+            // Errors at the end, like a missing command after `run`, are expected while typing
+            let was_partial = mem::replace(&mut ctx.options.partial, true);
+            let command = self.parse_command(reader, ctx);
+            ctx.options.partial = was_partial;
+            match command.and_then(|command| self.children_after(command)) {
+                Some(children) => children,
+                None => return Vec::new(),
+            }
+        } else {
+            0..self.num_roots
+        };
+
+        self.completions(children)
+    }
+
+    fn completions(&self, children: Range<usize>) -> Vec<Completion> {
+        let mut completions = Vec::new();
+        for node_id in children {
+            let node = &self.nodes[node_id].node;
+            let (text, kind) = match &node.kind {
+                NodeKind::Literal(literal) => (literal.to_string(), CompletionKind::Literal),
+                NodeKind::Argument { arg, .. } => (format!("{arg:?}"), CompletionKind::Argument),
+                // A command follows, which starts at the roots
+                NodeKind::Block => {
+                    completions.extend(self.completions(0..self.num_roots));
+                    continue;
+                }
+            };
+            completions.push(Completion {
+                node_id,
+                text,
+                kind,
+                executable: node.executable,
+            });
+        }
+        completions
+    }
+
+    /// Returns the children of the last node of the command, following it into the command of a
+    /// block on the same line. Returns `None` if the command did not parse up to its end.
+    fn children_after(&self, mut command: Command) -> Option<Range<usize>> {
+        loop {
+            if command.error.is_some() {
+                return None;
+            }
+            let last = command.args.pop()?;
+            match last.value {
+                ArgumentValue::Block(block) => match block.items.into_iter().next_back() {
+                    Some(Item::Command(inner)) => command = inner,
+                    _ => return Some(0..self.num_roots),
+                },
+                _ => return Some(self.nodes[last.lin_node_id].children.clone()),
+            }
+        }
+    }
+
+    /// For a node which redirects to another node, returns the names on the path to the target,
+    /// e.g. `["execute"]` for the `<targets>` node of `execute as <targets>`. Redirects to the root
+    /// result in an empty path.
+    ///
+    /// Returns `None` if the node does not redirect.
+    pub fn redirect_target_path(&self, node_id: usize) -> Option<Vec<&str>> {
+        let node = self.nodes.get(node_id)?;
+        if !node.redirect || node.children.is_empty() {
+            return None;
+        }
+        if node.children == (0..self.num_roots) {
+            return Some(Vec::new());
+        }
+
+        // Redirecting nodes share the children range of their target
+        let target = self
+            .nodes
+            .iter()
+            .position(|other| !other.redirect && other.children == node.children)?;
+        let path = self.node_path(target)?;
+        Some(
+            path.into_iter()
+                .map(|idx| self.nodes[idx].node.name())
+                .collect(),
+        )
+    }
+
+    /// Returns the nodes from a root down to the node, e.g. to name the command an argument
+    /// belongs to in a diagnostic. Redirects are not followed, so for `execute as @s run ...` the
+    /// path of `run` is `execute`, `run`.
+    ///
+    /// Returns `None` if there is no such node.
+    pub fn find_node_path(&self, node_id: usize) -> Option<Vec<&Node>> {
+        let path = self.node_path(node_id)?;
+        Some(path.into_iter().map(|idx| &self.nodes[idx].node).collect())
+    }
+
+    /// Returns the nodes from a root down to the node, ignoring redirects.
+    fn node_path(&self, node_id: usize) -> Option<Vec<usize>> {
+        if node_id >= self.nodes.len() {
+            return None;
+        }
+
+        let mut path = vec![node_id];
+        while let Some(parent) = self.parent(path[path.len() - 1]) {
+            path.push(parent);
+        }
+        if path[path.len() - 1] >= self.num_roots {
+            return None;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Returns the node which has the node as a child, ignoring redirects. Root nodes have no
+    /// parent.
+    fn parent(&self, node_id: usize) -> Option<usize> {
+        // Children are always stored after their parent
+        self.nodes[..node_id]
+            .iter()
+            .position(|node| !node.redirect && node.children.contains(&node_id))
+    }
+
+    /// Parses the whole source as a single command, without grouping it into lines.
+    pub fn parse_single_command(&self, ctx: &mut ParseContext<'_>) -> Option<Command> {
+        let source = ctx.source.shared_text();
+        self.parse_command(Reader::new(&source), ctx)
+    }
+
+    /// Parses the whole source as a single command, as if the node at the end of `root_path` was
+    /// the root, e.g. `["execute"]` parses just the arguments of an `execute` command.
+    ///
+    /// Returns `None` if the path does not exist or the source is empty.
+    pub fn parse_from(&self, ctx: &mut ParseContext<'_>, root_path: &[&str]) -> Option<Command> {
+        let children = match root_path {
+            [] => 0..self.num_roots,
+            _ => self.nodes[self.find_node(root_path)?].children.clone(),
+        };
+        let source = ctx.source.shared_text();
+        self.parse_command_from(Reader::new(&source), children, ctx)
+    }
+
+    /// Returns the range and the leading literal path of each top-level command, e.g.
+    /// `["scoreboard", "players", "set"]`, for building a search index. Unlike [`Self::parse`],
+    /// arguments are not parsed, the path simply ends at the first word that is not a literal.
+    pub fn index_commands(&self, source: &str) -> Result<Vec<IndexedCommand>, ParseError> {
+        let groups = group(source, 0, 0, None)?;
+
+        Ok(groups
+            .into_iter()
+            .filter(|(_, kind)| matches!(kind, GroupKind::Command))
+            .map(|(range, _)| {
+                let mut path = Vec::new();
+                let mut children = 0..self.num_roots;
+                for word in source[range.clone()].split_whitespace() {
+                    let Some(idx) = self.nodes[children.clone()].iter().position(|child| {
+                        matches!(&child.node.kind, NodeKind::Literal(name) if &**name == word)
+                    }) else {
+                        break;
+                    };
+                    path.push(word.to_owned());
+                    children = self.nodes[children.start + idx].children.clone();
+                }
+                (range, path)
+            })
+            .collect())
     }
 
     fn parse_commands(
@@ -48,9 +367,15 @@ impl ParsingTree {
         indent: usize,
         ctx: &mut ParseContext<'_>,
     ) -> Result<Block, ParseError> {
-        let groups = group(reader.get_src(), reader.get_pos(), indent)?;
+        let groups = group(
+            reader.get_src(),
+            reader.get_pos(),
+            indent,
+            ctx.options.expand_tabs,
+        )?;
 
         Ok(Block {
+            span: Span::new(reader.get_pos(), reader.get_src().len()),
             items: groups
                 .into_iter()
                 .filter_map(|(range, kind)| match kind {
@@ -63,8 +388,89 @@ impl ParsingTree {
         })
     }
 
+    /// Parses the source again after the bytes in `edit` were replaced by `new_len` bytes of new
+    /// text. Top-level commands which are not affected by the edit are taken from `previous`
+    /// instead of being parsed again.
+    ///
+    /// `previous` must be the result of parsing the source before the edit, with the same options
+    /// and interner. The result is the same as that of [`Self::parse`].
+    pub fn reparse(
+        &self,
+        ctx: &mut ParseContext<'_>,
+        previous: Block,
+        edit: Range<usize>,
+        new_len: usize,
+    ) -> Result<Block, ParseError> {
+        let source = ctx.source.shared_text();
+        let groups = group(&source, 0, 0, ctx.options.expand_tabs)?;
+        let edited = edit.start..edit.start + new_len;
+        let offset = new_len as isize - edit.len() as isize;
+
+        // Commands are keyed by where they start after the edit. Commands touching the edit are
+        // left out, they have to be parsed again anyway.
+        let mut reusable: FxHashMap<usize, Item> = FxHashMap::default();
+        for mut item in previous.items {
+            let span = item.span();
+            if matches!(item, Item::Comment(_)) || span.end >= edit.start && span.start <= edit.end
+            {
+                continue;
+            }
+            if span.start > edit.end {
+                item.shift(offset);
+            }
+            reusable.insert(item.span().start, item);
+        }
+
+        let mut items = Vec::with_capacity(groups.len());
+        for (range, kind) in groups {
+            if let GroupKind::Comment = kind {
+                items.push(Item::Comment(range.into()));
+                continue;
+            }
+
+            // Groups touching the edit are parsed again. The extent of any other group is the same
+            // as before, since it only changes if the old or the new group reaches into the edit.
+            let overlaps = range.start <= edited.end && range.end >= edited.start;
+            let reused = match overlaps {
+                true => None,
+                false => reusable.remove(&range.start),
+            };
+
+            match reused {
+                Some(item) => items.push(item),
+                None => items.extend(
+                    self.parse_command(Reader::with_range(&source, range), ctx)
+                        .map(Item::Command),
+                ),
+            }
+        }
+
+        Ok(Block {
+            span: Span::new(0, source.len()),
+            items,
+        })
+    }
+
     fn parse_command(&self, reader: Reader<'_>, ctx: &mut ParseContext<'_>) -> Option<Command> {
-        let result = self.parse_children(reader, 0..self.num_roots, ctx)?;
+        self.parse_command_from(reader, 0..self.num_roots, ctx)
+    }
+
+    fn parse_command_from(
+        &self,
+        reader: Reader<'_>,
+        children: Range<usize>,
+        ctx: &mut ParseContext<'_>,
+    ) -> Option<Command> {
+        // Commands in blocks get their own budget, the outer command continues with its own
+        // afterwards
+        let outer_remaining =
+            mem::replace(&mut ctx.remaining_candidates, ctx.options.max_candidates);
+        let outer_exceeded_at = ctx.exceeded_candidates_at.take();
+        let result = self.parse_children(reader.clone(), children, ctx);
+        ctx.remaining_candidates = outer_remaining;
+        let exceeded_candidates_at =
+            mem::replace(&mut ctx.exceeded_candidates_at, outer_exceeded_at);
+        let result = result?;
 
         let mut command = Command {
             args: Vec::new(),
@@ -86,18 +492,84 @@ impl ParsingTree {
             }
         }
 
+        self.check_denied_commands(&mut command, ctx);
+
+        if command.error.is_none()
+            && let Some(last_arg) = command.args.last()
+            && !last_arg.has_errors()
+            && !self.nodes[last_arg.lin_node_id].node.executable
+            && !matches!(last_arg.value, ArgumentValue::Block(_))
+        {
+            let span = Span::new(last_arg.span.end, last_arg.span.end);
+            command.error = Some(ParseError::IncompleteCommand(IncompleteCommandError {
+                span,
+            }));
+        }
+
+        if ctx.options.partial {
+            // The reader ends with the parsed range, e.g. the line being completed
+            let end_of_input = reader.get_src().trim_end().len();
+            command
+                .error
+                .take_if(|error| error.span().end >= end_of_input);
+            for arg in &mut command.args {
+                arg.errors.retain(|error| error.span().end < end_of_input);
+            }
+        }
+
+        if let Some(start) = exceeded_candidates_at
+            && let Some(first_arg) = command.args.first_mut()
+        {
+            let span = Span::new(start, reader.get_src().trim_end().len());
+            first_arg
+                .errors
+                .push(ParseError::ParseComplexity(ParseComplexityWarning { span }));
+        }
+
         Some(command)
     }
 
+    fn check_denied_commands(&self, command: &mut Command, ctx: &ParseContext<'_>) {
+        let literals: Vec<_> = command
+            .args
+            .iter()
+            .filter(|arg| matches!(arg.value, ArgumentValue::Literal))
+            .filter_map(|arg| Some((arg.span, self.get_node(arg.lin_node_id)?.name())))
+            .collect();
+        let Some(denied) = ctx.options.denied_commands.iter().find(|denied| {
+            !denied.is_empty()
+                && denied.len() <= literals.len()
+                && denied.iter().zip(&literals).all(|(a, (_, b))| a == b)
+        }) else {
+            return;
+        };
+
+        // The denied path matched, so the command has at least as many literals
+        let (last_literal_span, _) = literals[denied.len() - 1];
+        let span = Span::new(command.args[0].span.start, last_literal_span.end);
+        command.args[0]
+            .errors
+            .push(ParseError::ForbiddenCommand(ForbiddenCommandError { span }));
+    }
+
     fn parse_children(
         &self,
         mut reader: Reader<'_>,
         children: Range<usize>,
         ctx: &mut ParseContext<'_>,
     ) -> Option<Result<ParseResult, ParseError>> {
+        let end_of_previous = reader.get_pos();
         reader.skip_whitespace();
         if !reader.has_more() {
-            return None;
+            // A block has to follow `run`, even though it is allowed to be empty
+            let expects_block = self.nodes[children]
+                .iter()
+                .any(|child| matches!(child.node.kind, NodeKind::Block));
+            return expects_block.then(|| {
+                Err(ParseError::MissingRunBody(MissingRunBodyError {
+                    span: Span::new(end_of_previous, end_of_previous),
+                }))
+            });
         }
         // make reader immutable
         let reader = reader;
@@ -116,6 +588,10 @@ impl ParsingTree {
             NodeKind::Literal(_) => Some(reader.clone().parse_with_span(Reader::read_literal)),
             _ => None,
         };
+        // Literal children are matched by symbol, so the input is only hashed once
+        let current_symbol = current_literal
+            .as_ref()
+            .and_then(|(_, value)| self.literals.get(value));
 
         let mut candidates = Vec::new();
 
@@ -124,11 +600,11 @@ impl ParsingTree {
             let mut child_reader = reader.clone();
 
             match &child.node.kind {
-                NodeKind::Literal(literal) => {
-                    let (span, value) = current_literal
+                NodeKind::Literal(_) => {
+                    let (span, _) = current_literal
                         .clone()
                         .expect("parsing tree is not correctly sorted");
-                    if &**literal == value {
+                    if current_symbol.is_some() && child.literal == current_symbol {
                         child_reader.set_pos(span.end);
                         return Some(Ok(ParseResult {
                             value: Argument {
@@ -144,6 +620,15 @@ impl ParsingTree {
                     }
                 }
                 NodeKind::Argument { arg, .. } => {
+                    match &mut ctx.remaining_candidates {
+                        Some(0) if !candidates.is_empty() => {
+                            ctx.exceeded_candidates_at.get_or_insert(reader.get_pos());
+                            break;
+                        }
+                        Some(remaining) => *remaining = remaining.saturating_sub(1),
+                        None => (),
+                    }
+
                     let (span, (value, errors)) = child_reader.parse_with_span(|reader| {
                         let mut parse_arg_ctx = ParseArgContext {
                             reader,
@@ -155,7 +640,18 @@ impl ParsingTree {
                     });
                     let result = match value {
                         Ok(value) => {
-                            assert!(child_reader.peek().is_none_or(char::is_whitespace));
+                            // Parsers usually reject malformed tokens themselves. Should one stop
+                            // in the middle of a token anyway, the rest is skipped, so that the
+                            // following arguments are still parsed.
+                            let mut errors = errors;
+                            if child_reader.peek().is_some_and(|chr| !chr.is_whitespace()) {
+                                let (range, _) = child_reader.parse_with_span(|reader| {
+                                    reader.read_until(char::is_whitespace)
+                                });
+                                errors.push(ParseError::TrailingArgumentChars(
+                                    TrailingArgumentCharsError { span: range.into() },
+                                ));
+                            }
                             let next = self
                                 .parse_children(child_reader, child.children.clone(), ctx)
                                 .map(Box::new);
@@ -173,14 +669,16 @@ impl ParsingTree {
                         Err(err) => Err(err),
                     };
 
-                    // TODO: If the current child is a redirecting node, we should return with the
-                    // current parsed node
+                    // Redirecting nodes need no special handling here: their children are those
+                    // of the redirect target, so parsing simply continues there, e.g. after
+                    // `execute as @s` with the subcommands of `execute`.
 
                     candidates.push(result);
                 }
                 NodeKind::Block => {
-                    let block = match get_indent(&child_reader) {
+                    let block = match get_indent(&child_reader, ctx.options.expand_tabs) {
                         None => Ok(Block {
+                            span: Span::new(child_reader.get_pos(), child_reader.get_src().len()),
                             items: self
                                 .parse_command(child_reader.clone(), ctx)
                                 .map(|command| vec![Item::Command(command)])
@@ -210,28 +708,98 @@ impl ParsingTree {
             }
         }
 
-        if candidates.is_empty() {
-            if let Some((span, _)) = current_literal {
-                candidates.push(Err(ParseError::InvalidLiteral(InvalidLiteralError {
-                    span: span.into(),
-                    valid_literals: children.clone(),
-                })));
+        // Input which matches no literal is reported as such. If all argument children failed as
+        // well, the input is only reported as a literal if it resembles one, e.g. `time set nigth`,
+        // since the error of the argument is more helpful otherwise.
+        if let Some((range, input)) = current_literal
+            && (candidates.is_empty()
+                || !candidates.iter().any(|candidate| {
+                    candidate
+                        .as_ref()
+                        .is_ok_and(|result| !result.value.has_errors())
+                }) && self
+                    .likely_literal(children.clone(), input, INVALID_LITERAL_SIMILARITY)
+                    .is_some())
+        {
+            candidates.clear();
+            let span = Span::from(range);
+            let error = ParseError::InvalidLiteral(InvalidLiteralError {
+                span,
+                valid_literals: children.clone(),
+            });
+
+            // Continue as if the most likely literal was matched, so the rest of the command is
+            // still available to editor features
+            if ctx.options.recover
+                && let Some(child_idx) =
+                    self.likely_literal(children.clone(), input, RECOVERY_LITERAL_SIMILARITY)
+            {
+                let mut child_reader = reader.clone();
+                child_reader.set_pos(span.end);
+                return Some(Ok(ParseResult {
+                    value: Argument {
+                        span,
+                        lin_node_id: child_idx,
+                        value: ArgumentValue::Literal,
+                        errors: smallvec![error],
+                    },
+                    next: self
+                        .parse_children(child_reader, self.nodes[child_idx].children.clone(), ctx)
+                        .map(Box::new),
+                }));
             }
+
+            candidates.push(Err(error));
         }
 
-        candidates.sort_by(|a, b| match (a, b) {
-            (Ok(_), Err(_)) => Ordering::Less,
-            (Err(_), Ok(_)) => Ordering::Greater,
-            (Ok(a), Ok(b)) => match (a.value.has_errors(), b.value.has_errors()) {
-                (false, true) => Ordering::Less,
-                (true, false) => Ordering::Greater,
-                _ => Ordering::Equal,
-            },
-            _ => Ordering::Equal,
+        // Prefer candidates which lead to a complete command, e.g. `tp <destination>` over
+        // `tp <targets> <location>` for `tp @s`
+        candidates.sort_by_key(|candidate| match candidate {
+            Ok(result) => (false, !self.is_complete(result), result.value.has_errors()),
+            Err(_) => (true, true, true),
         });
 
         Some(candidates.swap_remove(0))
     }
+
+    /// Returns whether the arguments following from the result have no errors and end at an
+    /// executable node or a block.
+    fn is_complete(&self, mut result: &ParseResult) -> bool {
+        loop {
+            if result.value.has_errors() {
+                return false;
+            }
+            match result.next.as_deref() {
+                Some(Ok(next)) => result = next,
+                Some(Err(_)) => return false,
+                None => {
+                    return self.nodes[result.value.lin_node_id].node.executable
+                        || matches!(result.value.value, ArgumentValue::Block(_));
+                }
+            }
+        }
+    }
+
+    /// Returns the literal child which the input most likely was meant to be, if there is one
+    /// whose similarity exceeds `min_similarity`.
+    fn likely_literal(
+        &self,
+        children: Range<usize>,
+        input: &str,
+        min_similarity: f64,
+    ) -> Option<usize> {
+        children
+            .filter_map(|child_idx| match &self.nodes[child_idx].node.kind {
+                NodeKind::Literal(literal) => Some((
+                    child_idx,
+                    strsim::normalized_damerau_levenshtein(literal, input),
+                )),
+                _ => None,
+            })
+            .filter(|(_, similarity)| *similarity > min_similarity)
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(child_idx, _)| child_idx)
+    }
 }
 
 impl fmt::Debug for ParsingTree {
@@ -278,15 +846,16 @@ impl fmt::Debug for ParsingTree {
     }
 }
 
-fn get_indent(reader: &Reader) -> Option<(usize, usize)> {
+fn get_indent(reader: &Reader, tab_width: Option<usize>) -> Option<(usize, usize)> {
     let string = reader.get_src();
     let pos = reader.get_pos();
 
     let mut indent = 0;
     for (i, chr) in string[..pos].char_indices().rev() {
-        match chr {
-            ' ' => indent += 1,
-            '\n' => {
+        match (chr, tab_width) {
+            (' ', _) => indent += 1,
+            ('\t', Some(tab_width)) => indent += tab_width,
+            ('\n' | '\r', _) => {
                 let line_start = i + 1;
                 return Some((line_start, indent));
             }
@@ -302,38 +871,61 @@ enum GroupKind {
     Comment,
 }
 
+/// Returns the ranges of all lines, excluding their line breaks. Lines may be terminated by `\n`,
+/// `\r\n` or a lone `\r`, so that files written on any platform are split alike.
+fn line_ranges(string: &str) -> impl Iterator<Item = Range<usize>> + use<'_> {
+    let mut next_line_start = Some(0);
+    iter::from_fn(move || {
+        let start = next_line_start?;
+        let Some(len) = string[start..].find(['\n', '\r']) else {
+            next_line_start = None;
+            return Some(start..string.len());
+        };
+        let end = start + len;
+        next_line_start = Some(match string[end..].starts_with("\r\n") {
+            true => end + 2,
+            false => end + 1,
+        });
+        Some(start..end)
+    })
+}
+
+/// Returns the length in bytes and the width of the indentation of the line, or `None` if the line
+/// is blank. Tabs only count as indentation if a tab width is given.
+fn measure_indent(line: &str, tab_width: Option<usize>) -> Option<(usize, usize)> {
+    let mut width = 0;
+    for (i, chr) in line.char_indices() {
+        match (chr, tab_width) {
+            (' ', _) => width += 1,
+            ('\t', Some(tab_width)) => width += tab_width,
+            _ => return Some((i, width)),
+        }
+    }
+    None
+}
+
 fn group(
     string: &str,
     offset: usize,
     common_indent: usize,
+    tab_width: Option<usize>,
 ) -> Result<Vec<(Range<usize>, GroupKind)>, ParseError> {
     let mut current_group_range: Option<Range<usize>> = None;
-    let lines = string[offset..]
-        .char_indices()
-        // Find all line endings
-        .filter_map(|(i, chr)| (chr == '\n').then_some((offset + i, chr)))
-        // Add a line ending at the end of the string
-        .chain(iter::once((string.len(), '\n')))
-        // Work out line ranges
-        .map({
-            let mut next_line_start = offset;
-            move |(linebreak_idx, linebreak_chr)| {
-                let line_range = next_line_start..linebreak_idx;
-                next_line_start = linebreak_idx + linebreak_chr.len_utf8();
-                line_range
-            }
-        })
+    let lines = line_ranges(&string[offset..])
+        .map(|line_range| (offset + line_range.start)..(offset + line_range.end))
         // Work out line indentation and remove blank lines
         .filter_map(|line_range| {
-            string[line_range.clone()]
-                .find(|chr| chr != ' ')
-                .map(|indent| (line_range, indent))
+            measure_indent(&string[line_range.clone()], tab_width)
+                .map(|(indent_len, indent)| (line_range, indent_len, indent))
         });
 
     let mut groups = Vec::new();
 
-    for (line_range, indent) in lines {
-        let first_char = string[line_range.clone()][indent..].chars().next().unwrap();
+    for (line_range, indent_len, indent) in lines {
+        let first_char = string[line_range.clone()][indent_len..]
+            .chars()
+            .next()
+            .unwrap();
 
         if first_char == '#' && indent <= common_indent {
             if let Some(group_range) = current_group_range.take() {
@@ -344,9 +936,14 @@ fn group(
         }
 
         if first_char.is_whitespace() {
+            // Tabs only end up here if no tab width is configured
+            let kind = match first_char {
+                '\t' => IndentationErrorKind::TabIndentation,
+                _ => IndentationErrorKind::MixedWhitespace,
+            };
             return Err(ParseError::Indentation(IndentationError {
                 span: line_range.into(),
-                kind: IndentationErrorKind::MixedWhitespace,
+                kind,
             }));
         }
 
@@ -382,3 +979,630 @@ fn group(
 
     Ok(groups)
 }
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::{ArgumentType, CompletionKind};
+    use crate::{parse::ParseContext, source::SourceFile, test_util};
+
+    /// Returns the texts of the completions at the `|` in the text.
+    fn completions(text: &str) -> Vec<String> {
+        let offset = text.find('|').unwrap();
+        let source = SourceFile::new(None, text.replace('|', ""));
+        let tree = test_util::tree();
+        let mut ctx = ParseContext::new(&source, test_util::tree());
+        let completions = tree.completions_at(&mut ctx, offset);
+        completions
+            .into_iter()
+            .map(|completion| completion.text)
+            .collect()
+    }
+
+    fn parse_with_denied(text: &str, denied_commands: &[&[&str]]) -> crate::Parsed {
+        let source = SourceFile::new(None, text.to_owned());
+        let mut ctx = ParseContext::new(&source, test_util::tree());
+        ctx.options.denied_commands = denied_commands
+            .iter()
+            .map(|path| path.iter().map(|name| name.to_string()).collect())
+            .collect();
+        let result = ctx.parse();
+        let interner = std::mem::take(&mut ctx.interner);
+        crate::Parsed {
+            source,
+            tree: test_util::tree(),
+            result,
+            interner,
+        }
+    }
+
+    fn error_messages(text: &str, partial: bool) -> Vec<String> {
+        let source = SourceFile::new(None, text.to_owned());
+        let mut ctx = ParseContext::new(&source, test_util::tree());
+        ctx.options.partial = partial;
+        let result = ctx.parse();
+        let parsed = crate::Parsed {
+            source,
+            tree: test_util::tree(),
+            result,
+            interner: Default::default(),
+        };
+        parsed
+            .diagnostics()
+            .iter()
+            .map(|diagnostic| diagnostic.message().to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn partial_input_suppresses_end_of_input_errors() {
+        assert!(!error_messages("execute as @s ", false).is_empty());
+        assert!(error_messages("execute as @s ", true).is_empty());
+    }
+
+    #[test]
+    fn incomplete_command_is_reported() {
+        assert_eq!(
+            error_messages("execute as @s ", false),
+            ["Incomplete command"]
+        );
+        assert_eq!(
+            error_messages("scoreboard players", false),
+            ["Incomplete command"]
+        );
+        // `tp <destination>` is complete, unlike `tp <targets> <location>`
+        assert!(error_messages("tp @s", false).is_empty());
+    }
+
+    #[test]
+    fn partial_input_reports_other_errors() {
+        assert_eq!(
+            error_messages("time add -5 foo\nexecute as @s ", true),
+            ["Tick count must not be less than 0, found -5"]
+        );
+    }
+
+    #[test]
+    fn roots_are_completed_at_the_start_of_a_line() {
+        let roots = completions("|");
+        assert!(roots.contains(&"execute".to_owned()));
+        assert!(roots.contains(&"say".to_owned()));
+        assert_eq!(completions("say hi\n|\nsay x"), roots);
+        assert_eq!(completions("say hi\n    |"), roots);
+    }
+
+    #[test]
+    fn execute_subcommands_are_completed() {
+        let subcommands = completions("execute |");
+        for subcommand in ["as", "at", "if", "positioned", "run", "unless"] {
+            assert!(subcommands.contains(&subcommand.to_owned()), "{subcommand}");
+        }
+        assert!(!subcommands.contains(&"say".to_owned()));
+
+        // What was typed of the current word is ignored
+        assert_eq!(completions("execute a|"), subcommands);
+    }
+
+    #[test]
+    fn completions_before_the_last_line() {
+        let roots = completions("|");
+        assert_eq!(completions("execute run |\nsay hi\n"), roots);
+        assert_eq!(completions("execute |\nsay hi"), completions("execute |"));
+
+        let tree = test_util::tree();
+        let source = SourceFile::new(None, "scoreboard players set @s |\nsay hi".to_owned());
+        let mut ctx = ParseContext::new(&source, test_util::tree());
+        let completions = tree.completions_at(&mut ctx, 26);
+        assert_eq!(completions.len(), 1);
+        assert_eq!(completions[0].kind, CompletionKind::Argument);
+        assert_eq!(completions[0].text, "objective");
+    }
+
+    #[test]
+    fn nothing_is_completed_in_comments() {
+        assert!(completions("# execute |").is_empty());
+    }
+
+    #[test]
+    fn trailing_argument_chars_are_skipped() {
+        let parsed = test_util::parse("give @s diamond[]x 5");
+        let diagnostics = parsed.diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].message(),
+            "Unexpected characters after argument"
+        );
+        assert_eq!(diagnostics[0].span(), crate::span::Span::new(17, 18));
+        // The count after the skipped characters is still parsed
+        let command = parsed.result.as_ref().unwrap().commands().next().unwrap();
+        assert_eq!(command.args.len(), 4);
+        assert!(matches!(
+            command.args[3].value,
+            crate::parse::cst::ArgumentValue::Integer(crate::parse::argument::Integer {
+                value: Some(5)
+            })
+        ));
+
+        let parsed = test_util::parse("scoreboard players set @s obj 12abc");
+        assert_eq!(parsed.diagnostics().len(), 1);
+    }
+
+    #[test]
+    fn crlf_and_cr_line_endings() {
+        for text in [
+            "tp @s ~ ~1 ~\r\nsay b\r\n",
+            "tp @s ~ ~1 ~\rsay b",
+            "tp @s ~ ~1 ~\r\nsay b",
+        ] {
+            let parsed = test_util::parse(text);
+            assert!(parsed.diagnostics().is_empty(), "{text:?}");
+            let block = parsed.result.as_ref().unwrap();
+            let spans: Vec<_> = block.commands().map(|command| command.span()).collect();
+            let second_start = text.find("say").unwrap();
+            assert_eq!(
+                spans,
+                [
+                    crate::span::Span::new(0, 12),
+                    crate::span::Span::new(second_start, second_start + 5)
+                ],
+                "{text:?}"
+            );
+        }
+
+        let text = "execute as @a run\r\n    say a\r\n    tp @s ~ ~ ~\r\n";
+        let parsed = test_util::parse(text);
+        assert!(parsed.diagnostics().is_empty());
+        let block = parsed.result.as_ref().unwrap();
+        let nested: Vec<_> = block
+            .all_commands()
+            .skip(1)
+            .map(|command| command.span())
+            .collect();
+        assert_eq!(
+            nested,
+            [
+                crate::span::Span::new(23, 28),
+                crate::span::Span::new(34, 45)
+            ]
+        );
+    }
+
+    #[test]
+    fn tab_indentation_is_rejected() {
+        let parsed = test_util::parse("execute as @a run\n\tsay a\n");
+        let diagnostics = parsed.diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message(), "Indentation error");
+        assert_eq!(diagnostics[0].span(), crate::span::Span::new(18, 24));
+        let labels = diagnostics[0].labels();
+        assert_eq!(labels[0].message(), "Tabs are not allowed in indentation");
+        let help = diagnostics[0].sub();
+        assert_eq!(
+            help[0].message(),
+            "Indent with spaces instead, e.g. four spaces per tab"
+        );
+    }
+
+    /// Returns the path of the last argument of the first command, joined by dots.
+    fn dotted_path(text: &str) -> String {
+        let parsed = test_util::parse(text);
+        let command = parsed.result.as_ref().unwrap().commands().next().unwrap();
+        let lin_node_id = command.args.last().unwrap().lin_node_id;
+        let path = parsed.tree.find_node_path(lin_node_id).unwrap();
+        path.iter()
+            .map(|node| node.name())
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
+    #[test]
+    fn node_paths() {
+        assert_eq!(
+            dotted_path("scoreboard players operation @s a += @s b"),
+            "scoreboard.players.operation.targets.targetObjective.operation.source.sourceObjective"
+        );
+        assert_eq!(dotted_path("say hi"), "say.message");
+        assert_eq!(dotted_path("time set day"), "time.set.day");
+        // Nodes reached through a redirect have the path of the redirect target, here `execute`
+        assert_eq!(dotted_path("execute as @s at @s"), "execute.at.targets");
+
+        let tree = test_util::tree();
+        assert!(tree.find_node_path(usize::MAX).is_none());
+    }
+
+    #[test]
+    fn denied_commands_are_forbidden() {
+        // Game profiles are not parsed yet, so `op` is tested without its argument
+        let parsed = parse_with_denied("op\nstop\nsay hi\n", &[&["op"], &["stop"]]);
+        let diagnostics: Vec<_> = parsed
+            .diagnostics()
+            .into_iter()
+            .filter(|diagnostic| diagnostic.message() == "Forbidden command")
+            .collect();
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].span(), crate::span::Span::new(0, 2));
+        assert_eq!(diagnostics[1].span(), crate::span::Span::new(3, 7));
+        assert_eq!(
+            diagnostics[0].labels()[0].message(),
+            "This command is not allowed"
+        );
+    }
+
+    #[test]
+    fn denied_command_prefixes() {
+        let denied: &[&[&str]] = &[&["time", "set"]];
+        let parsed = parse_with_denied(
+            "time set day
+time query daytime
+",
+            denied,
+        );
+        let diagnostics = parsed.diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].span(), crate::span::Span::new(0, 8));
+
+        // Paths which only match partially or are shorter than the denied path are allowed
+        let parsed = parse_with_denied(
+            "time add 1
+time
+",
+            denied,
+        );
+        let messages: Vec<_> = parsed
+            .diagnostics()
+            .iter()
+            .map(|diagnostic| diagnostic.message().to_owned())
+            .collect();
+        assert_eq!(messages, ["Incomplete command"]);
+    }
+
+    /// Parses the text as if the node at `root_path` was the root.
+    fn parse_from(text: &str, root_path: &[&str]) -> Option<crate::parse::cst::Command> {
+        let source = SourceFile::new(None, text.to_owned());
+        let tree = test_util::tree();
+        let mut ctx = ParseContext::new(&source, test_util::tree());
+        tree.parse_from(&mut ctx, root_path)
+    }
+
+    #[test]
+    fn parse_from_execute_node() {
+        use crate::parse::cst::ArgumentValue;
+
+        let tree = test_util::tree();
+        let command = parse_from("if score @s o matches 1", &["execute"]).unwrap();
+        assert!(command.error.is_none(), "{:?}", command.error);
+        assert!(command.args.iter().all(|arg| !arg.has_errors()));
+        assert_eq!(command.literal_path(&tree), ["if", "score", "matches"]);
+        assert_eq!(command.span(), crate::span::Span::new(0, 23));
+        assert!(matches!(
+            command.args[2].value,
+            ArgumentValue::ScoreHolder(_)
+        ));
+        assert!(matches!(command.args[3].value, ArgumentValue::Objective(_)));
+        assert!(matches!(command.args[5].value, ArgumentValue::IntRange(_)));
+        let range = command.args.last().unwrap().span;
+        assert_eq!(range, crate::span::Span::new(22, 23));
+    }
+
+    #[test]
+    fn parse_from_nested_and_unknown_paths() {
+        let tree = test_util::tree();
+        let command = parse_from("@s o 5", &["scoreboard", "players", "set"]).unwrap();
+        assert!(command.error.is_none(), "{:?}", command.error);
+        assert_eq!(command.args.len(), 3);
+        assert!(command.literal_path(&tree).is_empty());
+
+        // The arguments of `execute` are not valid commands on their own
+        let command = parse_from("if score @s o matches 1", &[]).unwrap();
+        assert!(command.error.is_some());
+
+        assert!(parse_from("if score @s o matches 1", &["execute", "nope"]).is_none());
+    }
+
+    fn parse_with_recover(text: &str, recover: bool) -> crate::Parsed {
+        let source = SourceFile::new(None, text.to_owned());
+        let mut ctx = ParseContext::new(&source, test_util::tree());
+        ctx.options.recover = recover;
+        let result = ctx.parse();
+        let interner = std::mem::take(&mut ctx.interner);
+        crate::Parsed {
+            source,
+            tree: test_util::tree(),
+            result,
+            interner,
+        }
+    }
+
+    #[test]
+    fn misspelled_literals_are_reported_above_the_threshold() {
+        // `nigxx` has a similarity of 0.6 to `night`, so it is reported as a misspelled literal
+        assert_eq!(
+            error_messages("time set nigxx\n", false),
+            ["Invalid literal"]
+        );
+        // `nixxx` only has a similarity of 0.4, so the error of the `<time>` argument is reported
+        let messages = error_messages("time set nixxx\n", false);
+        assert_eq!(messages.len(), 1);
+        assert_ne!(messages[0], "Invalid literal");
+    }
+
+    #[test]
+    fn misspelled_literals_are_recovered_above_the_threshold() {
+        let literal_path = |text: &str| {
+            let parsed = parse_with_recover(text, true);
+            let command = parsed.result.as_ref().unwrap().commands().next().unwrap();
+            let path = command.literal_path(&parsed.tree);
+            path.iter().map(|name| name.to_string()).collect::<Vec<_>>()
+        };
+
+        // `midnigt` has a similarity of 0.875 to `midnight`, so parsing continues with it
+        assert_eq!(
+            literal_path("time set midnigt\n"),
+            ["time", "set", "midnight"]
+        );
+        // `nigth` only has a similarity of 0.8 to `night`, which is not enough to recover
+        assert_eq!(literal_path("time set nigth\n"), ["time", "set"]);
+
+        // Without recovery the literal is never assumed
+        let parsed = parse_with_recover("time set midnigt\n", false);
+        let command = parsed.result.as_ref().unwrap().commands().next().unwrap();
+        assert_eq!(command.literal_path(&parsed.tree), ["time", "set"]);
+        assert_eq!(
+            parsed
+                .diagnostics()
+                .iter()
+                .map(|diagnostic| diagnostic.message())
+                .collect::<Vec<_>>(),
+            ["Invalid literal"]
+        );
+    }
+
+    /// Returns the parameter names of the signature of the argument at the `|`, with the active
+    /// parameter in brackets.
+    fn signature_at(text: &str) -> String {
+        let offset = text.find('|').unwrap();
+        let parsed = test_util::parse(&text.replace('|', ""));
+        let command = parsed.result.as_ref().unwrap().commands().next().unwrap();
+        let arg = command
+            .args
+            .iter()
+            .find(|arg| arg.span.start <= offset && offset <= arg.span.end)
+            .unwrap();
+        let signature = parsed.tree.signature(arg.lin_node_id).unwrap();
+        let names = signature.parameters.iter().enumerate().map(|(idx, param)| {
+            if idx == signature.active_parameter {
+                format!("[{}]", param.name)
+            } else {
+                param.name.to_owned()
+            }
+        });
+        names.collect::<Vec<_>>().join(" ")
+    }
+
+    #[test]
+    fn signature_inside_tp() {
+        // `tp` redirects to `teleport`, which the signature is made of
+        assert_eq!(signature_at("tp @s ~|1 ~ ~"), "teleport targets [location]");
+        assert_eq!(signature_at("tp @s @|p"), "teleport targets [destination]");
+        assert_eq!(signature_at("tp @|s ~ ~ ~"), "teleport [targets]");
+
+        // Parameters which always follow are part of the signature as well
+        assert_eq!(
+            signature_at("scoreboard players set @|s o 1"),
+            "scoreboard players set [targets] objective score"
+        );
+    }
+
+    #[test]
+    fn signature_parameter_types() {
+        let tree = test_util::tree();
+        let location = tree.find_node(["teleport", "targets", "location"]).unwrap();
+        let signature = tree.signature(location).unwrap();
+        assert_eq!(signature.active_parameter, 2);
+        assert!(signature.parameters[0].argument.is_none());
+        assert!(matches!(
+            signature.parameters[2].argument,
+            Some(ArgumentType::Vec3 { .. })
+        ));
+
+        assert!(tree.signature(usize::MAX).is_none());
+    }
+
+    #[test]
+    fn literals_are_interned() {
+        use crate::intern::Interner;
+
+        let tree = test_util::tree();
+        let run = tree.find_node(["execute", "run"]).unwrap();
+        let symbol = tree.literal_symbol(run).unwrap();
+        assert_eq!(tree.literals().resolve(symbol), Some("run"));
+        assert_eq!(tree.literals().get("run"), Some(symbol));
+
+        // Equal literals share a symbol, arguments have none
+        let targets = tree.find_node(["execute", "as", "targets"]).unwrap();
+        assert_eq!(tree.literal_symbol(targets), None);
+        let objectives = tree
+            .find_node(["scoreboard", "objectives", "list"])
+            .unwrap();
+        let players = tree.find_node(["scoreboard", "players", "list"]).unwrap();
+        assert_ne!(objectives, players);
+        assert_eq!(
+            tree.literal_symbol(objectives),
+            tree.literal_symbol(players)
+        );
+    }
+
+    /// Returns the message and span of each diagnostic.
+    fn errors(text: &str) -> Vec<(String, crate::span::Span)> {
+        let parsed = test_util::parse(text);
+        parsed
+            .diagnostics()
+            .iter()
+            .map(|diagnostic| (diagnostic.message().to_owned(), diagnostic.span()))
+            .collect()
+    }
+
+    #[test]
+    fn run_without_body() {
+        use crate::span::Span;
+
+        let missing = |offset| vec![("Missing command".to_owned(), Span::new(offset, offset))];
+        assert_eq!(errors("execute run"), missing(11));
+        assert_eq!(errors("execute run  \nsay hi\n"), missing(11));
+        assert_eq!(errors("execute as @s run\n"), missing(17));
+        assert_eq!(errors("return run"), missing(10));
+        assert_eq!(errors("return run\nsay hi\n"), missing(10));
+
+        // A body on the same line or indented below is fine
+        assert!(errors("execute run say hi\nreturn run say hi\n").is_empty());
+        assert!(errors("execute run\n    say hi\nreturn run\n    say hi\n").is_empty());
+    }
+
+    /// Builds a tree with the command `amb`, followed by `depth` levels of three word arguments
+    /// each, which all accept the same input.
+    fn ambiguous_tree(depth: u32) -> std::sync::Arc<super::ParsingTree> {
+        use crate::{
+            BuildNodeId, BuildTree, Node,
+            parse::argument::{Argument, StringKind},
+        };
+
+        fn insert_level(tree: &mut BuildTree, parent: BuildNodeId, depth: u32) {
+            if depth == 0 {
+                return;
+            }
+            for i in 0..3 {
+                let node =
+                    Node::argument(format!("word{i}"), Argument::String(StringKind::SingleWord));
+                let child = tree.insert(parent, node.executable());
+                insert_level(tree, child, depth - 1);
+            }
+        }
+
+        let mut tree = BuildTree::default();
+        let amb = tree.insert(BuildNodeId::ROOT, "amb");
+        insert_level(&mut tree, amb, depth);
+        std::sync::Arc::new(tree.into_parsing_tree())
+    }
+
+    fn parse_with_budget(text: &str, max_candidates: Option<usize>) -> crate::Parsed {
+        let tree = ambiguous_tree(4);
+        let source = SourceFile::new(None, text.to_owned());
+        let mut ctx = ParseContext::new(&source, std::sync::Arc::clone(&tree));
+        ctx.options.max_candidates = max_candidates;
+        let result = ctx.parse();
+        let interner = std::mem::take(&mut ctx.interner);
+        crate::Parsed {
+            source,
+            tree,
+            result,
+            interner,
+        }
+    }
+
+    #[test]
+    fn candidate_budget_is_reported() {
+        use crate::{diagnostics::Level, span::Span};
+
+        let text = "amb a b c d\n";
+        let parsed = parse_with_budget(text, Some(10));
+        let diagnostics = parsed.diagnostics();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].level(), Level::Warn);
+        assert_eq!(diagnostics[0].message(), "Command is too ambiguous");
+        // The warning starts where the budget ran out and reaches to the end of the command
+        let span = diagnostics[0].span();
+        assert_eq!(span.end, 11);
+        assert!(span.start > 4 && span.start < 11, "{span:?}");
+        assert_eq!(
+            diagnostics[0].labels()[0].message(),
+            "Not all interpretations of these arguments were checked"
+        );
+
+        // The best candidate found so far is still used
+        let command = parsed.result.as_ref().unwrap().commands().next().unwrap();
+        assert_eq!(command.args.len(), 5);
+        assert_eq!(command.args[4].span, Span::new(10, 11));
+        assert!(command.error.is_none());
+    }
+
+    #[test]
+    fn candidate_budget_is_not_reported_when_sufficient() {
+        // 3 + 9 + 27 + 81 candidates are tried for the four arguments
+        assert!(
+            parse_with_budget("amb a b c d\n", Some(120))
+                .diagnostics()
+                .is_empty()
+        );
+        assert!(
+            parse_with_budget("amb a b c d\n", None)
+                .diagnostics()
+                .is_empty()
+        );
+        assert_eq!(
+            parse_with_budget("amb a b c d\n", Some(119))
+                .diagnostics()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn redirect_target_paths() {
+        let tree = test_util::tree();
+        let targets = tree.find_node(["execute", "as", "targets"]).unwrap();
+        assert_eq!(tree.redirect_target_path(targets), Some(vec!["execute"]));
+        let tp = tree.find_node(["tp"]).unwrap();
+        assert_eq!(tree.redirect_target_path(tp), Some(vec!["teleport"]));
+
+        // Nodes which do not redirect have no target
+        let execute = tree.find_node(["execute"]).unwrap();
+        assert_eq!(tree.redirect_target_path(execute), None);
+        assert_eq!(tree.redirect_target_path(usize::MAX), None);
+    }
+
+    #[test]
+    fn redirect_target_path_of_nested_and_root_targets() {
+        use crate::{BuildNodeId, BuildTree};
+
+        let mut tree = BuildTree::default();
+        let outer = tree.insert(BuildNodeId::ROOT, "outer");
+        let inner = tree.insert(outer, "inner");
+        tree.insert(inner, "leaf");
+        let to_inner = tree.insert(BuildNodeId::ROOT, "to_inner");
+        let to_root = tree.insert(outer, "to_root");
+        tree.redirect(to_inner, inner);
+        tree.redirect(to_root, BuildNodeId::ROOT);
+        let tree = tree.into_parsing_tree();
+
+        let to_inner = tree.find_node(["to_inner"]).unwrap();
+        assert_eq!(
+            tree.redirect_target_path(to_inner),
+            Some(vec!["outer", "inner"])
+        );
+        let to_root = tree.find_node(["outer", "to_root"]).unwrap();
+        assert_eq!(tree.redirect_target_path(to_root), Some(Vec::new()));
+    }
+
+    #[test]
+    fn index_multi_command_file() {
+        let text = "# setup\nscoreboard players set @s o 1\n\nexecute as @a run\n    say hi\nnope x\ntime set day\n";
+        let tree = test_util::tree();
+        let index: Vec<_> = tree
+            .index_commands(text)
+            .unwrap()
+            .into_iter()
+            .map(|(range, path)| (&text[range], path.join(" ")))
+            .collect();
+        assert_eq!(
+            index,
+            [
+                (
+                    "scoreboard players set @s o 1",
+                    "scoreboard players set".to_owned()
+                ),
+                ("execute as @a run\n    say hi", "execute as".to_owned()),
+                ("nope x", String::new()),
+                ("time set day", "time set day".to_owned()),
+            ]
+        );
+    }
+}
@@ -142,6 +142,20 @@ impl fmt::Debug for SmallString {
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for SmallString {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for SmallString {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self::from)
+    }
+}
+
 impl Drop for SmallString {
     fn drop(&mut self) {
         if !self.is_inline() {
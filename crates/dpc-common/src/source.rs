@@ -1,11 +1,34 @@
 use std::{
     ops::Range,
     path::{Path, PathBuf},
+    sync::Arc,
 };
 
+use rustc_hash::FxHashMap;
+
+use crate::span::FileSpan;
+
+/// Identifies a source file when diagnostics span multiple files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FileId(u32);
+
+impl FileId {
+    pub const fn new(index: u32) -> Self {
+        Self(index)
+    }
+
+    pub const fn index(self) -> u32 {
+        self.0
+    }
+}
+
+/// A source file and the offsets of its line breaks. The text is shared between clones, so
+/// cloning a file is cheap.
+#[derive(Clone)]
 pub struct SourceFile {
     path: Option<PathBuf>,
-    text: String,
+    text: Arc<str>,
     line_endings: Vec<usize>,
 }
 
@@ -14,7 +37,7 @@ impl SourceFile {
         let line_endings = find_line_endings(&text).collect();
         Self {
             path,
-            text,
+            text: text.into(),
             line_endings,
         }
     }
@@ -27,12 +50,25 @@ impl SourceFile {
         &self.text
     }
 
+    /// Returns the text, which stays valid while the file is edited. The parser uses this to read
+    /// the text while its context, which holds the file, is borrowed mutably.
+    pub(crate) fn shared_text(&self) -> Arc<str> {
+        Arc::clone(&self.text)
+    }
+
     pub fn replace_range(&mut self, range: Range<usize>, new_text: &str) {
-        let line = self.byte_to_line(range.start).unwrap();
-        self.text.replace_range(range.clone(), new_text);
-        self.line_endings.drain(line..);
+        // Scanning starts one byte early, since the edit may turn a `\r` before it into half of a
+        // `\r\n` or the other way around
+        let scan_start = self.text.floor_char_boundary(range.start.saturating_sub(1));
+        let first_changed = self.line_endings.partition_point(|&idx| idx < scan_start);
+        let mut text = String::with_capacity(self.text.len() - range.len() + new_text.len());
+        text.push_str(&self.text[..range.start]);
+        text.push_str(new_text);
+        text.push_str(&self.text[range.end..]);
+        self.text = text.into();
+        self.line_endings.drain(first_changed..);
         self.line_endings
-            .extend(find_line_endings(&self.text[range.start..]).map(|off| off + range.start));
+            .extend(find_line_endings(&self.text[scan_start..]).map(|off| off + scan_start));
     }
 
     pub fn byte_to_line(&self, idx: usize) -> Option<usize> {
@@ -42,6 +78,19 @@ impl SourceFile {
         })
     }
 
+    /// Returns the 1-based line and the 0-based column of the byte offset, or `None` if the offset
+    /// is past the end or inside a character. Columns are counted in characters, and a line ending
+    /// belongs to the line it ends.
+    pub fn line_col(&self, idx: usize) -> Option<(usize, usize)> {
+        let line = self.byte_to_line(idx)?;
+        let line_start = match line {
+            0 => 0,
+            _ => self.line_endings[line - 1] + 1,
+        };
+        let column = self.text.get(line_start..idx)?.chars().count();
+        Some((line + 1, column))
+    }
+
     pub fn line_to_byte(&self, line: usize) -> Option<usize> {
         (line == 0)
             .then_some(0)
@@ -49,9 +98,218 @@ impl SourceFile {
     }
 }
 
-fn find_line_endings(string: &str) -> impl Iterator<Item = usize> + use<'_> {
-    string
-        .char_indices()
-        .filter(|(_, chr)| *chr == '\n')
+/// Returns the line and column of the byte offset like [`SourceFile::line_col`], for text which is
+/// not held by a source file. The line breaks are searched for on every call, so prefer the method
+/// of [`SourceFile`] if the same text is queried repeatedly.
+pub fn line_col(text: &str, idx: usize) -> Option<(usize, usize)> {
+    text.get(..idx)?;
+    let (line, line_start) = find_line_endings(text)
+        .take_while(|&line_end| line_end < idx)
+        .fold((0, 0), |(line, _), line_end| (line + 1, line_end + 1));
+    let column = text.get(line_start..idx)?.chars().count();
+    Some((line + 1, column))
+}
+
+/// Returns the offsets of all line breaks, which are `\n`, `\r\n` or a lone `\r`. A `\r\n` is
+/// recorded at its `\n`, so the `\r` belongs to the line it ends.
+pub(crate) fn find_line_endings(string: &str) -> impl Iterator<Item = usize> + use<'_> {
+    let bytes = string.as_bytes();
+    bytes
+        .iter()
+        .enumerate()
+        .filter(move |&(idx, &byte)| {
+            byte == b'\n' || byte == b'\r' && bytes.get(idx + 1) != Some(&b'\n')
+        })
         .map(|(idx, _)| idx)
 }
+
+/// Owns the source files of a datapack and assigns a [`FileId`] to each of them.
+#[derive(Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+    ids_by_path: FxHashMap<PathBuf, FileId>,
+}
+
+impl SourceMap {
+    /// Adds the file to the map. If a file with the same path was added before, it is replaced and
+    /// keeps its id.
+    pub fn add_file(&mut self, file: SourceFile) -> FileId {
+        if let Some(id) = file.path().and_then(|path| self.file_id_of(path)) {
+            self.files[id.index() as usize] = file;
+            return id;
+        }
+
+        let id = FileId::new(self.files.len().try_into().expect("too many source files"));
+        if let Some(path) = file.path() {
+            self.ids_by_path.insert(path.to_owned(), id);
+        }
+        self.files.push(file);
+        id
+    }
+
+    pub fn file_id_of(&self, path: &Path) -> Option<FileId> {
+        self.ids_by_path.get(path).copied()
+    }
+
+    pub fn get(&self, id: FileId) -> Option<&SourceFile> {
+        self.files.get(id.index() as usize)
+    }
+
+    pub fn get_mut(&mut self, id: FileId) -> Option<&mut SourceFile> {
+        self.files.get_mut(id.index() as usize)
+    }
+
+    pub fn files(&self) -> impl Iterator<Item = (FileId, &SourceFile)> {
+        self.files
+            .iter()
+            .enumerate()
+            .map(|(idx, file)| (FileId::new(idx as u32), file))
+    }
+
+    /// Returns the text covered by the span, or `None` if the file does not exist or the span is
+    /// out of bounds.
+    pub fn resolve(&self, span: FileSpan) -> Option<&str> {
+        self.get(span.file)?.text().get(span.span.as_range())
+    }
+
+    /// Returns the 1-based line and the 0-based column of the start of the span, see
+    /// [`SourceFile::line_col`].
+    pub fn line_col(&self, span: FileSpan) -> Option<(usize, usize)> {
+        self.get(span.file)?.line_col(span.span.start)
+    }
+
+    /// Returns the path of the file as a string, suitable for diagnostics.
+    pub fn display_name(&self, id: FileId) -> Option<String> {
+        Some(match self.get(id)?.path() {
+            Some(path) => path.to_string_lossy().into_owned(),
+            None => format!("<file {}>", id.index()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{FileId, SourceFile, SourceMap, line_col};
+    use crate::span::{FileSpan, Span};
+
+    /// Checks that the method and the free function agree before returning the result.
+    fn position(text: &str, idx: usize) -> Option<(usize, usize)> {
+        let result = SourceFile::new(None, text.to_owned()).line_col(idx);
+        assert_eq!(line_col(text, idx), result);
+        result
+    }
+
+    #[test]
+    fn line_endings_belong_to_their_line() {
+        let text = "say a\nsay b\r\nsay c\rsay d";
+        assert_eq!(position(text, 0), Some((1, 0)));
+        assert_eq!(position(text, 5), Some((1, 5)));
+        assert_eq!(position(text, 6), Some((2, 0)));
+        assert_eq!(position(text, 11), Some((2, 5)));
+        assert_eq!(position(text, 12), Some((2, 6)));
+        assert_eq!(position(text, 13), Some((3, 0)));
+        assert_eq!(position(text, 18), Some((3, 5)));
+        assert_eq!(position(text, 19), Some((4, 0)));
+    }
+
+    #[test]
+    fn offset_past_the_end() {
+        assert_eq!(position("say a\n", 6), Some((2, 0)));
+        assert_eq!(position("say a\n", 7), None);
+        assert_eq!(position("", 0), Some((1, 0)));
+        assert_eq!(position("", 1), None);
+    }
+
+    #[test]
+    fn columns_are_counted_in_characters() {
+        let text = "say a\nsay äö x";
+        assert_eq!(position(text, 12), Some((2, 5)));
+        assert_eq!(position(text, 14), Some((2, 6)));
+        // Offsets inside a character have no column
+        assert_eq!(position(text, 11), None);
+    }
+
+    fn source_map() -> (SourceMap, FileId, FileId) {
+        let mut sources = SourceMap::default();
+        let a = sources.add_file(SourceFile::new(
+            Some("data/foo/function/a.mcfunction".into()),
+            "say a\nfunction foo:b\n".to_owned(),
+        ));
+        let b = sources.add_file(SourceFile::new(
+            Some("data/foo/function/b.mcfunction".into()),
+            "say bär\n".to_owned(),
+        ));
+        (sources, a, b)
+    }
+
+    #[test]
+    fn files_are_looked_up_by_path() {
+        let (sources, a, b) = source_map();
+        assert_ne!(a, b);
+        let path = Path::new("data/foo/function/b.mcfunction");
+        assert_eq!(sources.file_id_of(path), Some(b));
+        assert_eq!(sources.get(b).unwrap().text(), "say bär\n");
+        assert_eq!(sources.file_id_of(Path::new("b.mcfunction")), None);
+        assert!(sources.get(FileId::new(2)).is_none());
+
+        let ids: Vec<_> = sources.files().map(|(id, _)| id).collect();
+        assert_eq!(ids, [a, b]);
+    }
+
+    #[test]
+    fn file_spans_are_resolved_to_text() {
+        let (sources, a, b) = source_map();
+        assert_eq!(
+            sources.resolve(Span::new(6, 20).in_file(a)),
+            Some("function foo:b")
+        );
+        assert_eq!(
+            sources.resolve(FileSpan::new(b, Span::new(4, 8))),
+            Some("bär")
+        );
+
+        // Out of bounds, inside a character or in an unknown file
+        assert_eq!(sources.resolve(Span::new(4, 20).in_file(b)), None);
+        assert_eq!(sources.resolve(Span::new(4, 6).in_file(b)), None);
+        assert_eq!(
+            sources.resolve(Span::new(0, 1).in_file(FileId::new(5))),
+            None
+        );
+    }
+
+    #[test]
+    fn adding_a_file_again_replaces_it() {
+        let (mut sources, _, b) = source_map();
+        let path = "data/foo/function/b.mcfunction";
+        let id = sources.add_file(SourceFile::new(Some(path.into()), "say c\n".to_owned()));
+        assert_eq!(id, b);
+        assert_eq!(sources.files().count(), 2);
+        assert_eq!(sources.resolve(Span::new(4, 5).in_file(b)), Some("c"));
+
+        // Files without a path are never replaced
+        let first = sources.add_file(SourceFile::new(None, String::new()));
+        let second = sources.add_file(SourceFile::new(None, String::new()));
+        assert_ne!(first, second);
+        assert_eq!(sources.display_name(second).unwrap(), "<file 3>");
+        assert_eq!(sources.display_name(b).unwrap(), path);
+    }
+
+    #[test]
+    fn file_spans_are_resolved_to_positions() {
+        let (sources, a, b) = source_map();
+        assert_eq!(sources.line_col(Span::new(0, 3).in_file(a)), Some((1, 0)));
+        assert_eq!(sources.line_col(Span::new(15, 20).in_file(a)), Some((2, 9)));
+        assert_eq!(sources.line_col(Span::new(21, 21).in_file(a)), Some((3, 0)));
+        // Columns are counted in characters
+        assert_eq!(sources.line_col(Span::new(7, 8).in_file(b)), Some((1, 6)));
+
+        assert_eq!(sources.line_col(Span::new(22, 22).in_file(a)), None);
+        assert_eq!(sources.line_col(Span::new(6, 7).in_file(b)), None);
+        assert_eq!(
+            sources.line_col(Span::new(0, 0).in_file(FileId::new(5))),
+            None
+        );
+    }
+}
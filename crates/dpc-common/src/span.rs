@@ -1,6 +1,9 @@
 use std::{fmt, ops::Range};
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+use crate::source::FileId;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Span {
     pub start: usize,
     pub end: usize,
@@ -19,6 +22,40 @@ impl Span {
     pub fn as_range(self) -> Range<usize> {
         self.start..self.end
     }
+
+    /// Returns whether the two spans intersect. Spans that merely touch do not overlap, so an empty
+    /// span only overlaps spans which contain it strictly.
+    pub fn overlaps(self, other: Self) -> bool {
+        self.start < other.end && other.start < self.end
+    }
+
+    /// Returns whether the span is not inverted and lies within a source of `source_len` bytes.
+    pub fn validate(&self, source_len: usize) -> bool {
+        self.start <= self.end && self.end <= source_len
+    }
+
+    /// Returns a span which is guaranteed to pass [`Span::validate`]. Invalid spans are logged
+    /// and clamped to the source instead of causing panics further down the line.
+    pub fn clamp(self, source_len: usize) -> Self {
+        if self.validate(source_len) {
+            return self;
+        }
+        tracing::warn!(span = ?self, source_len, "clamping invalid span");
+        let start = self.start.min(source_len);
+        Self::new(start, self.end.clamp(start, source_len))
+    }
+
+    /// Moves the span by `offset` bytes, e.g. after text was inserted before it.
+    pub(crate) fn shift(self, offset: isize) -> Self {
+        Self::new(
+            self.start.wrapping_add_signed(offset),
+            self.end.wrapping_add_signed(offset),
+        )
+    }
+
+    pub fn in_file(self, file: FileId) -> FileSpan {
+        FileSpan { file, span: self }
+    }
 }
 
 impl From<Span> for Range<usize> {
@@ -38,3 +75,83 @@ impl fmt::Debug for Span {
         write!(f, "{}..{}", self.start, self.end)
     }
 }
+
+/// A span within a specific source file, for analyses spanning multiple files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FileSpan {
+    pub file: FileId,
+    pub span: Span,
+}
+
+impl FileSpan {
+    pub const fn new(file: FileId, span: Span) -> Self {
+        Self { file, span }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Span;
+
+    #[test]
+    fn inverted_span_is_invalid_and_clamped() {
+        let span = Span::new(5, 2);
+        assert!(!span.validate(10));
+        assert_eq!(span.clamp(10), Span::new(5, 5));
+    }
+
+    #[test]
+    fn out_of_bounds_span_is_invalid_and_clamped() {
+        assert!(Span::new(2, 10).validate(10));
+        assert!(!Span::new(2, 11).validate(10));
+        assert_eq!(Span::new(2, 11).clamp(10), Span::new(2, 10));
+        assert_eq!(Span::new(12, 15).clamp(10), Span::new(10, 10));
+    }
+
+    #[test]
+    fn spans_are_ordered_by_start_then_end() {
+        assert!(Span::new(1, 9) < Span::new(2, 3));
+        assert!(Span::new(2, 3) < Span::new(2, 4));
+        assert_eq!(
+            Span::new(2, 3).cmp(&Span::new(2, 3)),
+            std::cmp::Ordering::Equal
+        );
+
+        let mut spans = vec![
+            Span::new(4, 5),
+            Span::new(0, 8),
+            Span::new(4, 4),
+            Span::new(0, 2),
+        ];
+        spans.sort();
+        assert_eq!(
+            spans,
+            [
+                Span::new(0, 2),
+                Span::new(0, 8),
+                Span::new(4, 4),
+                Span::new(4, 5)
+            ]
+        );
+    }
+
+    #[test]
+    fn overlapping_spans() {
+        assert!(Span::new(0, 5).overlaps(Span::new(4, 8)));
+        assert!(Span::new(4, 8).overlaps(Span::new(0, 5)));
+        assert!(Span::new(0, 10).overlaps(Span::new(3, 4)));
+        assert!(Span::new(3, 4).overlaps(Span::new(3, 4)));
+    }
+
+    #[test]
+    fn touching_spans_do_not_overlap() {
+        assert!(!Span::new(0, 4).overlaps(Span::new(4, 8)));
+        assert!(!Span::new(4, 8).overlaps(Span::new(0, 4)));
+        assert!(!Span::new(0, 2).overlaps(Span::new(5, 8)));
+        assert!(!Span::new(4, 4).overlaps(Span::new(4, 8)));
+        assert!(!Span::new(4, 4).overlaps(Span::new(0, 4)));
+        assert!(!Span::new(4, 4).overlaps(Span::new(4, 4)));
+        assert!(Span::new(4, 4).overlaps(Span::new(0, 8)));
+    }
+}
@@ -0,0 +1,71 @@
+use rustc_hash::FxHashMap;
+
+use crate::{
+    ParsingTree,
+    parse::cst::{self, Block, Command},
+};
+
+/// Counts how often each command occurs in the given files, keyed by the literal path of the
+/// command. Commands in nested blocks are counted separately from the command containing them.
+pub fn command_histogram<'a>(
+    blocks: impl IntoIterator<Item = &'a Block>,
+    tree: &ParsingTree,
+) -> FxHashMap<Vec<String>, usize> {
+    struct HistogramVisitor<'a> {
+        tree: &'a ParsingTree,
+        counts: FxHashMap<Vec<String>, usize>,
+    }
+
+    impl cst::Visitor for HistogramVisitor<'_> {
+        fn visit_command(&mut self, command: &Command) {
+            let path = command
+                .literal_path(self.tree)
+                .into_iter()
+                .map(str::to_owned)
+                .collect();
+            *self.counts.entry(path).or_default() += 1;
+            cst::walk_command(self, command);
+        }
+    }
+
+    let mut visitor = HistogramVisitor {
+        tree,
+        counts: FxHashMap::default(),
+    };
+    for block in blocks {
+        cst::walk_block(&mut visitor, block);
+    }
+    visitor.counts
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::command_histogram;
+    use crate::test_util;
+
+    #[test]
+    fn counts_commands_across_files() {
+        let files = [
+            test_util::parse("say a\nsay b\n# comment\ntime set day\n"),
+            test_util::parse("execute as @a run\n    say c\n    time set night\nsay d\n"),
+            test_util::parse(""),
+        ];
+        let blocks = files.iter().map(|parsed| parsed.result.as_ref().unwrap());
+        let histogram = command_histogram(blocks, &test_util::tree());
+
+        let mut counts: Vec<_> = histogram
+            .iter()
+            .map(|(path, count)| (path.join(" "), *count))
+            .collect();
+        counts.sort();
+        assert_eq!(
+            counts,
+            [
+                ("execute as run".to_owned(), 1),
+                ("say".to_owned(), 4),
+                ("time set day".to_owned(), 1),
+                ("time set night".to_owned(), 1),
+            ]
+        );
+    }
+}
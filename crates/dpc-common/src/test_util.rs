@@ -0,0 +1,31 @@
+//! Helpers for tests which parse commands with the vanilla grammar.
+
+use std::sync::{Arc, OnceLock};
+
+use crate::{
+    Parsed, ParsingTree,
+    parse::cst::{Argument, ArgumentValue},
+};
+
+/// Returns the parsing tree built from the `commands.json` at the root of the repository. It is
+/// only built once, since that is slow in debug builds.
+pub(crate) fn tree() -> Arc<ParsingTree> {
+    static TREE: OnceLock<Arc<ParsingTree>> = OnceLock::new();
+    let tree = TREE.get_or_init(|| {
+        let commands_json = include_str!("../../../commands.json");
+        Arc::new(crate::load_tree(commands_json).unwrap())
+    });
+    Arc::clone(tree)
+}
+
+pub(crate) fn parse(text: &str) -> Parsed {
+    crate::parse_source(tree(), None, text.to_owned())
+}
+
+/// Returns the first argument, including those of nested commands, whose value matches.
+pub(crate) fn find_arg(parsed: &Parsed, predicate: impl Fn(&ArgumentValue) -> bool) -> &Argument {
+    let block = parsed.result.as_ref().unwrap();
+    let mut args = block.all_commands().flat_map(|command| &command.args);
+    args.find(|arg| predicate(&arg.value))
+        .expect("no argument matches")
+}
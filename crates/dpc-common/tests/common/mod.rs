@@ -0,0 +1,17 @@
+use std::sync::{Arc, OnceLock};
+
+use dpc_common::{Parsed, ParsingTree};
+
+/// Returns the parsing tree of the vanilla `commands.json`, which is only built once.
+pub fn tree() -> Arc<ParsingTree> {
+    static TREE: OnceLock<Arc<ParsingTree>> = OnceLock::new();
+    let tree = TREE.get_or_init(|| {
+        let commands_json = include_str!("../../../../commands.json");
+        Arc::new(dpc_common::load_tree(commands_json).unwrap())
+    });
+    Arc::clone(tree)
+}
+
+pub fn parse(text: &str) -> Parsed {
+    dpc_common::parse_source(tree(), None, text.to_owned())
+}
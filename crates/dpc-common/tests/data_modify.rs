@@ -0,0 +1,132 @@
+//! The forms of `data modify`, which combine most of the NBT and resource arguments.
+
+mod common;
+
+use dpc_common::parse::cst::ArgumentValueKind::{
+    self, Coordinates3, Entity, Integer, Literal, Nbt, NbtPath, ResourceLocation as Location,
+};
+
+/// Parses a single command, asserts that it has no errors and returns the kinds of its arguments.
+fn argument_kinds(text: &str) -> Vec<ArgumentValueKind> {
+    let parsed = common::parse(text);
+    assert!(
+        parsed.diagnostics().is_empty(),
+        "{text}: {:?}",
+        parsed.diagnostics()
+    );
+    let block = parsed.result.as_ref().unwrap();
+    let command = block.all_commands().last().unwrap();
+    command.args.iter().map(|arg| arg.value.kind()).collect()
+}
+
+#[test]
+fn set_from() {
+    assert_eq!(
+        argument_kinds("data modify storage foo:bar a.b set from storage foo:baz c"),
+        [
+            Literal, Literal, Literal, Location, NbtPath, Literal, Literal, Literal, Location,
+            NbtPath
+        ]
+    );
+    assert_eq!(
+        argument_kinds("data modify entity @s Items set from block ~ ~1 ~ Items[0]"),
+        [
+            Literal,
+            Literal,
+            Literal,
+            Entity,
+            NbtPath,
+            Literal,
+            Literal,
+            Literal,
+            Coordinates3,
+            NbtPath
+        ]
+    );
+}
+
+#[test]
+fn set_value() {
+    assert_eq!(
+        argument_kinds("data modify storage foo:bar a set value {x:1b, y:[I;1,2]}"),
+        [
+            Literal, Literal, Literal, Location, NbtPath, Literal, Literal, Nbt
+        ]
+    );
+}
+
+#[test]
+fn set_string() {
+    assert_eq!(
+        argument_kinds("data modify storage foo:bar a set string entity @s CustomName 1 -1"),
+        [
+            Literal, Literal, Literal, Location, NbtPath, Literal, Literal, Literal, Entity,
+            NbtPath, Integer, Integer
+        ]
+    );
+    assert_eq!(
+        argument_kinds("data modify storage foo:bar a set string storage foo:baz b"),
+        [
+            Literal, Literal, Literal, Location, NbtPath, Literal, Literal, Literal, Location,
+            NbtPath
+        ]
+    );
+}
+
+#[test]
+fn insert() {
+    assert_eq!(
+        argument_kinds("data modify storage foo:bar list insert -1 value 5"),
+        [
+            Literal, Literal, Literal, Location, NbtPath, Literal, Integer, Literal, Nbt
+        ]
+    );
+    assert_eq!(
+        argument_kinds("data modify storage foo:bar list insert 0 from storage foo:baz x"),
+        [
+            Literal, Literal, Literal, Location, NbtPath, Literal, Integer, Literal, Literal,
+            Location, NbtPath
+        ]
+    );
+}
+
+#[test]
+fn append_prepend_and_merge() {
+    assert_eq!(
+        argument_kinds("data modify storage foo:bar list append value \"x\""),
+        [
+            Literal, Literal, Literal, Location, NbtPath, Literal, Literal, Nbt
+        ]
+    );
+    assert_eq!(
+        argument_kinds("data modify storage foo:bar list prepend from entity @s Pos[0]"),
+        [
+            Literal, Literal, Literal, Location, NbtPath, Literal, Literal, Literal, Entity,
+            NbtPath
+        ]
+    );
+    assert_eq!(
+        argument_kinds("data modify block 0 64 0 Items merge value {a:1}"),
+        [
+            Literal,
+            Literal,
+            Literal,
+            Coordinates3,
+            NbtPath,
+            Literal,
+            Literal,
+            Nbt
+        ]
+    );
+}
+
+#[test]
+fn inside_execute_run() {
+    let text = "execute as @a run\n    data modify storage foo:bar a set value 1\n";
+    assert_eq!(
+        argument_kinds(text),
+        [
+            Literal, Literal, Literal, Location, NbtPath, Literal, Literal, Nbt
+        ]
+    );
+}
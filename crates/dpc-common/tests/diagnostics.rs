@@ -0,0 +1,44 @@
+//! Snapshot tests of the rendered diagnostics of broken commands. Run them with
+//! `UPDATE_SNAPSHOTS=1` to write the current output to the snapshot files instead of comparing.
+
+mod common;
+
+use std::{env, fs, path::Path};
+
+fn assert_snapshot(name: &str, source: &str) {
+    let parsed = common::parse(source);
+    let rendered: String = parsed
+        .diagnostics()
+        .iter()
+        .map(|diagnostic| diagnostic.render_plain(source, "test.mcfunction"))
+        .collect();
+
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{name}.txt"));
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::write(&path, &rendered).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path)
+        .unwrap_or_else(|err| panic!("failed to read {}: {err}", path.display()));
+    assert_eq!(
+        rendered, expected,
+        "snapshot `{name}` changed, run with UPDATE_SNAPSHOTS=1 to update it"
+    );
+}
+
+#[test]
+fn indentation() {
+    assert_snapshot("indentation", "execute run\n    help\n  help\n");
+}
+
+#[test]
+fn invalid_literal() {
+    assert_snapshot("invalid_literal", "help\nscoreboard player list\n");
+}
+
+#[test]
+fn unterminated_string() {
+    assert_snapshot("unterminated_string", "datapack enable \"file/pack\n");
+}
@@ -0,0 +1,70 @@
+//! The numeric types of the two-component positions of `spreadplayers`, `worldborder center` and
+//! `forceload`.
+
+mod common;
+
+use dpc_common::parse::{
+    argument::{Coordinates, WorldCoordinate},
+    cst::ArgumentValue,
+};
+
+/// Parses a single command, asserts that it has no errors and returns its first two-component
+/// position as `(value, relative)` pairs.
+fn position(text: &str) -> [(Option<f64>, bool); 2] {
+    let parsed = common::parse(text);
+    assert!(
+        parsed.diagnostics().is_empty(),
+        "{text}: {:?}",
+        parsed.diagnostics()
+    );
+    let command = parsed.result.as_ref().unwrap().commands().next().unwrap();
+    let coords = command.args.iter().find_map(|arg| match &arg.value {
+        ArgumentValue::Coordinates2(Coordinates::World(coords)) => Some(*coords),
+        _ => None,
+    });
+    coords
+        .expect("no world position")
+        .map(|WorldCoordinate { value, relative }| (value.value, relative))
+}
+
+fn messages(text: &str) -> Vec<String> {
+    let diagnostics = common::parse(text).diagnostics();
+    diagnostics.iter().map(|d| d.message().to_owned()).collect()
+}
+
+#[test]
+fn spreadplayers_center_is_doubles() {
+    assert_eq!(
+        position("spreadplayers 0 0 1 10 false @a"),
+        [(Some(0.0), false), (Some(0.0), false)]
+    );
+    assert_eq!(
+        position("spreadplayers 0.5 -.5 1 10 under 64 true @a"),
+        [(Some(0.5), false), (Some(-0.5), false)]
+    );
+}
+
+#[test]
+fn worldborder_center_is_doubles() {
+    assert_eq!(
+        position("worldborder center ~ ~"),
+        [(Some(0.0), true), (Some(0.0), true)]
+    );
+    assert_eq!(
+        position("worldborder center 1.5 ~-2.5"),
+        [(Some(1.5), false), (Some(-2.5), true)]
+    );
+}
+
+#[test]
+fn forceload_columns_are_integers() {
+    assert_eq!(
+        position("forceload add 1 -2"),
+        [(Some(1.0), false), (Some(-2.0), false)]
+    );
+    assert_eq!(
+        position("forceload add ~1.5 ~"),
+        [(Some(1.5), true), (Some(0.0), true)]
+    );
+    assert_eq!(messages("forceload add 1.5 2"), ["Invalid number"]);
+}
@@ -0,0 +1,129 @@
+//! The forms of `schedule`, which combine function references, times and trailing literals.
+
+mod common;
+
+use dpc_common::{
+    Compiler,
+    call_graph::{CallGraph, FunctionTags},
+    intern::Interner,
+    lint::lint_unknown_functions,
+    parse::cst::{ArgumentValue, Command},
+};
+
+/// Parses a single command, asserts that it has no errors and returns its literal path.
+fn literal_path(text: &str) -> Vec<String> {
+    let parsed = common::parse(text);
+    assert!(
+        parsed.diagnostics().is_empty(),
+        "{text}: {:?}",
+        parsed.diagnostics()
+    );
+    let command = parsed.result.as_ref().unwrap().commands().next().unwrap();
+    let path = command.literal_path(&parsed.tree);
+    path.into_iter().map(str::to_owned).collect()
+}
+
+/// Returns the function and the time in ticks of a `schedule function` command.
+fn function_and_ticks(command: &Command, interner: &impl Interner) -> (String, Option<i32>) {
+    let function = command.args.iter().find_map(|arg| match arg.value {
+        ArgumentValue::Function(function) => function.location?.resolve(interner),
+        _ => None,
+    });
+    let ticks = command.args.iter().find_map(|arg| match arg.value {
+        ArgumentValue::Time(time) => Some(time.ticks),
+        _ => None,
+    });
+    (function.unwrap(), ticks.unwrap())
+}
+
+#[test]
+fn schedule_function_in_ticks() {
+    assert_eq!(
+        literal_path("schedule function foo:bar 20t"),
+        ["schedule", "function"]
+    );
+
+    let parsed = common::parse("schedule function foo:bar 20t");
+    let command = parsed.result.as_ref().unwrap().commands().next().unwrap();
+    assert_eq!(
+        function_and_ticks(command, &parsed.interner),
+        ("foo:bar".to_owned(), Some(20))
+    );
+}
+
+#[test]
+fn schedule_function_append() {
+    assert_eq!(
+        literal_path("schedule function foo:bar 1d append"),
+        ["schedule", "function", "append"]
+    );
+    assert_eq!(
+        literal_path("schedule function foo:bar 2.5s replace"),
+        ["schedule", "function", "replace"]
+    );
+
+    let parsed = common::parse("schedule function foo:bar 1d append");
+    let command = parsed.result.as_ref().unwrap().commands().next().unwrap();
+    assert_eq!(
+        function_and_ticks(command, &parsed.interner),
+        ("foo:bar".to_owned(), Some(24000))
+    );
+}
+
+#[test]
+fn schedule_clear() {
+    assert_eq!(
+        literal_path("schedule clear foo:bar"),
+        ["schedule", "clear"]
+    );
+}
+
+#[test]
+fn invalid_schedule_forms() {
+    assert!(
+        !common::parse("schedule function foo:bar")
+            .diagnostics()
+            .is_empty()
+    );
+    assert!(
+        !common::parse("schedule function foo:bar -1")
+            .diagnostics()
+            .is_empty()
+    );
+    assert!(
+        !common::parse("schedule function foo:bar 1t later")
+            .diagnostics()
+            .is_empty()
+    );
+}
+
+#[test]
+fn scheduled_functions_are_validated_against_the_pack() {
+    let mut compiler = Compiler::new(common::tree());
+    let main = compiler.compile_file(
+        "foo:main".into(),
+        "schedule function foo:later 1s\nschedule function foo:missing 1s\n".to_owned(),
+    );
+    let later = compiler.compile_file("foo:later".into(), "say later\n".to_owned());
+    let functions = [("foo:main", &main), ("foo:later", &later)]
+        .map(|(id, file)| (id, file.result.as_ref().unwrap()));
+    let graph = CallGraph::new(
+        functions,
+        compiler.tree(),
+        compiler.interner(),
+        FunctionTags::default(),
+    );
+
+    let diagnostics = lint_unknown_functions(
+        main.result.as_ref().unwrap(),
+        compiler.tree(),
+        compiler.interner(),
+        &graph,
+    );
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].message(), "Unknown function");
+    assert_eq!(
+        diagnostics[0].labels()[0].message(),
+        "`foo:missing` is not part of the pack"
+    );
+}
@@ -0,0 +1,80 @@
+//! The commands which take a target followed by a text component, which must end exactly at the
+//! end of the command.
+
+mod common;
+
+use dpc_common::parse::cst::ArgumentValueKind::{self, Entity, Literal, Nbt};
+
+/// Parses a single command, asserts that it has no errors and returns the kinds and texts of its
+/// arguments.
+fn arguments(text: &str) -> Vec<(ArgumentValueKind, &str)> {
+    let parsed = common::parse(text);
+    assert!(
+        parsed.diagnostics().is_empty(),
+        "{text}: {:?}",
+        parsed.diagnostics()
+    );
+    let block = parsed.result.as_ref().unwrap();
+    let command = block.commands().next().unwrap();
+    command
+        .args
+        .iter()
+        .map(|arg| (arg.value.kind(), &text[arg.span.as_range()]))
+        .collect()
+}
+
+#[test]
+fn tellraw_compound() {
+    assert_eq!(
+        arguments(r#"tellraw @a {"text":"hi"}"#),
+        [
+            (Literal, "tellraw"),
+            (Entity, "@a"),
+            (Nbt, r#"{"text":"hi"}"#)
+        ]
+    );
+}
+
+#[test]
+fn tellraw_plain_string() {
+    assert_eq!(
+        arguments(r#"tellraw @a "plain""#),
+        [(Literal, "tellraw"), (Entity, "@a"), (Nbt, r#""plain""#)]
+    );
+}
+
+#[test]
+fn title_list() {
+    assert_eq!(
+        arguments(r#"title @a subtitle ["a","b"]"#),
+        [
+            (Literal, "title"),
+            (Entity, "@a"),
+            (Literal, "subtitle"),
+            (Nbt, r#"["a","b"]"#)
+        ]
+    );
+}
+
+#[test]
+fn component_with_spaces() {
+    assert_eq!(
+        arguments(r#"tellraw @a[tag=x] { "text" : "a b" , "bold" : true }"#),
+        [
+            (Literal, "tellraw"),
+            (Entity, "@a[tag=x]"),
+            (Nbt, r#"{ "text" : "a b" , "bold" : true }"#)
+        ]
+    );
+}
+
+#[test]
+fn trailing_text_after_the_component_is_reported() {
+    let parsed = common::parse(r#"tellraw @a {"text":"hi"} extra"#);
+    let messages: Vec<_> = parsed
+        .diagnostics()
+        .iter()
+        .map(|diagnostic| diagnostic.message().to_owned())
+        .collect();
+    assert_eq!(messages, ["Too many arguments"]);
+}
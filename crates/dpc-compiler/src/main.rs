@@ -1,61 +1,518 @@
-use std::{fs, path::PathBuf, sync::Arc};
+use std::{
+    fs,
+    io::{self, BufRead, Write},
+    path::{Path, PathBuf},
+    process,
+    sync::{Arc, mpsc},
+    time::{Duration, Instant},
+};
 
 use clap::Parser;
 use dpc_common::{
-    parse::{
-        ParseContext, cst,
-        errors::{EmitDiagnostic, ParseError},
-    },
+    CompiledFile, Compiler, ParsingTree,
+    call_graph::CallGraph,
+    dependencies::external_dependencies,
+    diagnostics::Diagnostic,
+    function_tag::{FunctionTag, collect_function_tags, parse_function_tag},
+    lint,
+    parse::{ParseContext, cst, errors::ParseErrorVisitor},
     source::SourceFile,
 };
+use notify::{EventKind, RecursiveMode, Watcher};
 
 /// Datapack Compiler
+///
+/// Checks the file given as the only argument, which is the same as `dpc check <FILE>`.
 #[derive(clap::Parser)]
+#[command(args_conflicts_with_subcommands = true, arg_required_else_help = true)]
 struct Options {
-    /// The file to compile
+    /// Print how long each phase took to stderr
+    #[arg(long, global = true)]
+    time: bool,
+    #[command(flatten)]
+    check: Option<CheckArgs>,
+    #[command(subcommand)]
+    action: Option<Action>,
+}
+
+#[derive(clap::Subcommand)]
+enum Action {
+    /// Parse a file and report all errors
+    Check(CheckArgs),
+    /// Read commands from stdin and print how they are parsed
+    Repl,
+    /// Print the resources a datapack references but does not define as JSON
+    Dependencies {
+        /// The directory of the datapack
+        path: PathBuf,
+    },
+}
+
+#[derive(clap::Args)]
+struct CheckArgs {
+    /// The file to compile, or a directory of `.mcfunction` files
     file: PathBuf,
+    /// How diagnostics are printed
+    #[arg(long, value_enum, default_value_t = Format::Human)]
+    format: Format,
+    /// Check again whenever a file changes
+    #[arg(long)]
+    watch: bool,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum Format {
+    /// Colored reports with source snippets
+    Human,
+    /// GitHub Actions workflow commands, which are shown as annotations
+    Github,
 }
 
 fn main() {
     let options = Options::parse();
+    let mut timings = Timings::default();
+
+    let tree = timings.measure("loading grammar", || {
+        let commands_json = fs::read_to_string("commands.json").unwrap();
+        dpc_common::load_tree(&commands_json).unwrap_or_else(|err| {
+            eprintln!("Failed to load commands.json: {err}");
+            process::exit(1);
+        })
+    });
+    let tree = Arc::new(tree);
+
+    match (options.action, options.check) {
+        (Some(Action::Check(args)), _) | (None, Some(args)) => {
+            let CheckArgs {
+                file,
+                format,
+                watch,
+            } = args;
+            if let Format::Human = format {
+                println!("{tree:?}");
+            }
+            check_all(&tree, &file, format, &mut timings);
+            if options.time {
+                timings.print();
+            }
+            if watch {
+                watch_and_check(&tree, &file, format, options.time);
+            }
+        }
+        (Some(Action::Repl), _) => repl(tree),
+        (Some(Action::Dependencies { path }), _) => {
+            dependencies(&tree, &path, &mut timings);
+            if options.time {
+                timings.print();
+            }
+        }
+        (None, None) => unreachable!("clap requires a file or a subcommand"),
+    }
+}
+
+/// The durations of the phases of a command, for the `--time` flag.
+#[derive(Default)]
+struct Timings {
+    phases: Vec<(&'static str, Duration)>,
+}
+
+impl Timings {
+    fn measure<T>(&mut self, phase: &'static str, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        self.phases.push((phase, start.elapsed()));
+        result
+    }
+
+    fn print(&self) {
+        let width = self.phases.iter().map(|(phase, _)| phase.len()).max();
+        for (phase, duration) in &self.phases {
+            eprintln!(
+                "{phase:width$}  {duration:>10.2?}",
+                width = width.unwrap_or(0)
+            );
+        }
+        let total: Duration = self.phases.iter().map(|(_, duration)| *duration).sum();
+        eprintln!(
+            "{:width$}  {total:>10.2?}",
+            "total",
+            width = width.unwrap_or(0)
+        );
+    }
+}
+
+/// How long to wait for further changes before checking again. Editors often save a file in
+/// several steps.
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Checks the path again whenever it changes, until the process is stopped. To try it, run
+/// `dpc-compiler check --watch <FILE>`, then save the file with and without an error, and remove
+/// and recreate it. Each save clears the terminal and prints the new diagnostics.
+fn watch_and_check(tree: &Arc<ParsingTree>, path: &Path, format: Format, time: bool) {
+    // Editors often save by replacing the file, which ends a watch on the file itself, so a
+    // single file is watched through its directory instead
+    let (watched, mode) = match path.is_dir() {
+        true => (path, RecursiveMode::Recursive),
+        false => (
+            path.parent()
+                .filter(|parent| !parent.as_os_str().is_empty())
+                .unwrap_or(Path::new(".")),
+            RecursiveMode::NonRecursive,
+        ),
+    };
+
+    let (sender, receiver) = mpsc::channel();
+    let watcher = notify::recommended_watcher(sender)
+        .and_then(|mut watcher| watcher.watch(watched, mode).map(|()| watcher));
+    let _watcher = match watcher {
+        Ok(watcher) => watcher,
+        Err(err) => {
+            eprintln!("Failed to watch {}: {err}", path.display());
+            process::exit(1);
+        }
+    };
+    eprintln!("Watching {} for changes", path.display());
+
+    while let Ok(event) = receiver.recv() {
+        let mut changed = is_change(&event, path);
+        while let Ok(event) = receiver.recv_timeout(WATCH_DEBOUNCE) {
+            changed |= is_change(&event, path);
+        }
+        if !changed {
+            continue;
+        }
+
+        // Clear the terminal, so only the diagnostics of the latest check are visible
+        print!("\x1B[2J\x1B[H");
+        let mut timings = Timings::default();
+        check_all(tree, path, format, &mut timings);
+        if time {
+            timings.print();
+        }
+    }
+}
+
+/// Returns whether the event changes the watched path. Events of other files next to a watched
+/// file are ignored.
+fn is_change(event: &notify::Result<notify::Event>, path: &Path) -> bool {
+    match event {
+        Ok(event) => {
+            !matches!(event.kind, EventKind::Access(_))
+                && (path.is_dir()
+                    || event
+                        .paths
+                        .iter()
+                        .any(|changed| changed.file_name() == path.file_name()))
+        }
+        Err(err) => {
+            eprintln!("Failed to watch for changes: {err}");
+            false
+        }
+    }
+}
+
+/// Checks the file, or all `.mcfunction` files if the path is a directory, and prints the
+/// diagnostics.
+fn check_all(tree: &Arc<ParsingTree>, path: &Path, format: Format, timings: &mut Timings) {
+    for file in &check(tree, path, timings) {
+        print_file(file, format, timings);
+    }
+}
+
+/// Parses and lints the file, or all `.mcfunction` files if the path is a directory.
+fn check(tree: &Arc<ParsingTree>, path: &Path, timings: &mut Timings) -> Vec<CompiledFile> {
+    let mut compiler = Compiler::new(Arc::clone(tree));
+    let mut compiled = compile(&mut compiler, path, timings);
+    timings.measure("linting", || lint_files(&compiler, path, &mut compiled));
+    compiled
+}
+
+/// Prints the resources the datapack references but does not define as JSON.
+fn dependencies(tree: &Arc<ParsingTree>, path: &Path, timings: &mut Timings) {
+    let mut compiler = Compiler::new(Arc::clone(tree));
+    let files = compile(&mut compiler, path, timings);
+    let manifest = timings.measure("collecting dependencies", || {
+        let ids = function_ids(&files);
+        let graph = call_graph(&compiler, path, &files, &ids);
+        let blocks = files.iter().filter_map(|file| file.result.as_ref().ok());
+        external_dependencies(blocks, compiler.tree(), compiler.interner(), &graph)
+    });
+    println!("{}", manifest.to_json());
+}
+
+/// Parses the file, or all `.mcfunction` files if the path is a directory. Files which cannot be
+/// read are reported and skipped.
+fn compile(compiler: &mut Compiler, path: &Path, timings: &mut Timings) -> Vec<CompiledFile> {
+    let mut files = Vec::new();
+    collect_files(path, "mcfunction", &mut files);
+    files.sort();
+
+    let mut compiled = Vec::new();
+    for file in files {
+        let source = match timings.measure("reading source", || fs::read_to_string(&file)) {
+            Ok(source) => source,
+            Err(err) => {
+                eprintln!("Failed to read {}: {err}", file.display());
+                continue;
+            }
+        };
+        let (source, result) = timings.measure("parsing", || compiler.parse_file(file, source));
+        compiled.push(timings.measure("collecting diagnostics", || {
+            compiler.finish_file(source, result)
+        }));
+    }
+    compiled
+}
+
+/// Adds the warnings of all lints to the diagnostics of the files. If the path is a directory, it
+/// is treated as (a part of) a datapack, so the functions can be checked against each other.
+fn lint_files(compiler: &Compiler, path: &Path, files: &mut [CompiledFile]) {
+    let tree = compiler.tree();
+    for file in files.iter_mut() {
+        if let Ok(block) = &file.result {
+            let text = file.source.text();
+            file.diagnostics.extend(lint::lint_file(block, tree, text));
+        }
+    }
+
+    if path.is_dir() {
+        let ids = function_ids(files);
+        let graph = call_graph(compiler, path, files, &ids);
+
+        for (file, id) in files.iter_mut().zip(&ids) {
+            if let (Some(id), Ok(block)) = (id, &file.result) {
+                let diagnostics = lint::lint_self_recursion(id, block, tree, compiler.interner());
+                file.diagnostics.extend(diagnostics);
+            }
+        }
+        for (function, diagnostic) in
+            lint::lint_unreachable_functions(&graph, CallGraph::ENTRY_POINTS)
+        {
+            if let Some(idx) = ids.iter().position(|id| id.as_deref() == Some(&*function)) {
+                files[idx].diagnostics.push(diagnostic);
+            }
+        }
+    }
+
+    for file in files {
+        file.diagnostics.sort_by_key(Diagnostic::span);
+    }
+}
 
-    let tree = Arc::new(dpc_common::load_tree());
-    println!("{tree:?}");
+/// Returns the ids of the files which are functions of a datapack, in the order of the files.
+fn function_ids(files: &[CompiledFile]) -> Vec<Option<String>> {
+    files
+        .iter()
+        .map(|file| {
+            file.source
+                .path()
+                .and_then(|path| resource_id(path, &["function"]))
+        })
+        .collect()
+}
 
-    let source = fs::read_to_string(&options.file).unwrap();
-    let file_name = options.file.to_string_lossy().into_owned();
-    let source_file = SourceFile::new(Some(options.file), source);
-    let mut ctx = ParseContext::new(&source_file, Arc::clone(&tree));
+/// Builds the call graph of the functions, given with their ids, and the function tags of the
+/// datapack.
+fn call_graph(
+    compiler: &Compiler,
+    path: &Path,
+    files: &[CompiledFile],
+    ids: &[Option<String>],
+) -> CallGraph {
+    let functions = files
+        .iter()
+        .zip(ids)
+        .filter_map(|(file, id)| Some((id.as_deref()?, file.result.as_ref().ok()?)));
+    CallGraph::new(
+        functions,
+        compiler.tree(),
+        compiler.interner(),
+        collect_function_tags(
+            read_function_tags(path)
+                .iter()
+                .map(|(id, tag)| (id.as_str(), tag)),
+        ),
+    )
+}
 
-    let block = ctx.parse();
-    println!("{block:#?}");
+/// Reads the function tags of the datapack, e.g. `data/minecraft/tags/function/load.json`. Tags
+/// which cannot be read are left out, since they are not checked themselves.
+fn read_function_tags(path: &Path) -> Vec<(String, FunctionTag)> {
+    let mut files = Vec::new();
+    collect_files(path, "json", &mut files);
+    files.sort();
+    files
+        .iter()
+        .filter_map(|file| {
+            let id = resource_id(file, &["tags", "function"])?;
+            let (tag, _) = parse_function_tag(&fs::read_to_string(file).ok()?);
+            Some((id, tag))
+        })
+        .collect()
+}
 
-    struct ParseErrorVisitor<'a> {
-        ctx: &'a ParseContext<'a>,
+/// Returns the id of a resource from its path within a datapack, e.g. `foo:bar/baz` for
+/// `data/foo/function/bar/baz.mcfunction` if the registry is `["function"]`. The last directory
+/// of the registry may also have the plural name used by older versions, e.g. `functions`.
+fn resource_id(path: &Path, registry: &[&str]) -> Option<String> {
+    let path = path.with_extension("");
+    let components: Vec<&str> = path
+        .iter()
+        .map(|component| component.to_str())
+        .collect::<Option<_>>()?;
+    let data = components
+        .iter()
+        .rposition(|&component| component == "data")?;
+    let (namespace, rest) = components[data + 1..].split_first()?;
+    if rest.len() <= registry.len() {
+        return None;
     }
 
-    impl cst::Visitor for ParseErrorVisitor<'_> {
-        fn visit_parse_error(&mut self, error: &ParseError) {
-            let file_name = self.ctx.source.path().unwrap().to_str().unwrap();
-            let diag = error.emit(self.ctx);
-            diag.to_ariadne_report(file_name)
-                .eprint((file_name, ariadne::Source::from(self.ctx.source.text())))
-                .unwrap()
+    let (dirs, name) = rest.split_at(registry.len());
+    let (last_dir, dirs) = dirs.split_last()?;
+    let (last_expected, expected) = registry.split_last()?;
+    let is_registry = dirs == expected
+        && (last_dir == last_expected || last_dir.strip_suffix('s') == Some(last_expected));
+    is_registry.then(|| format!("{namespace}:{}", name.join("/")))
+}
+
+fn collect_files(path: &Path, extension: &str, files: &mut Vec<PathBuf>) {
+    if !path.is_dir() {
+        files.push(path.to_owned());
+        return;
+    }
+    let entries = match fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(err) => {
+            eprintln!("Failed to read {}: {err}", path.display());
+            return;
+        }
+    };
+    for entry in entries {
+        let path = match entry {
+            Ok(entry) => entry.path(),
+            Err(err) => {
+                eprintln!("Failed to read an entry of {}: {err}", path.display());
+                continue;
+            }
+        };
+        if path.is_dir() {
+            collect_files(&path, extension, files);
+        } else if path.extension().is_some_and(|ext| ext == extension) {
+            files.push(path);
         }
     }
+}
+
+fn print_file(file: &CompiledFile, format: Format, timings: &mut Timings) {
+    if let Format::Human = format {
+        println!("{:#?}", file.result);
+    }
+
+    let file_name = file
+        .source
+        .path()
+        .map(|path| path.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let diagnostics = &file.diagnostics;
+    let text = file.source.text();
+    timings.measure("printing diagnostics", || match format {
+        Format::Human => print_diagnostics(diagnostics, &file_name, text),
+        Format::Github => {
+            for diagnostic in diagnostics {
+                println!("{}", diagnostic.render_github(text, &file_name));
+            }
+        }
+    });
+}
+
+fn repl(tree: Arc<ParsingTree>) {
+    const FILE_NAME: &str = "<stdin>";
+
+    let mut interner = Default::default();
+    let mut stdin = io::stdin().lock();
+    let mut line = String::new();
+
+    loop {
+        print!("> ");
+        io::stdout().flush().unwrap();
+
+        line.clear();
+        if stdin.read_line(&mut line).unwrap() == 0 {
+            break;
+        }
+
+        let source_file = SourceFile::new(None, line.trim_end().to_owned());
+        let mut ctx = ParseContext::new(&source_file, Arc::clone(&tree));
+        ctx.interner = std::mem::take(&mut interner);
 
-    match block {
-        Ok(block) => {
-            let mut visitor = ParseErrorVisitor { ctx: &ctx };
-            cst::walk_block(&mut visitor, &block);
+        if let Some(command) = ctx.parse_single_command() {
+            println!("{command:#?}");
+            let mut visitor = ParseErrorVisitor::new(&ctx);
+            cst::walk_command(&mut visitor, &command);
+            print_diagnostics(&visitor.diagnostics, FILE_NAME, source_file.text());
         }
-        Err(err) => err
-            .emit(&ctx)
-            .to_ariadne_report(&file_name)
-            .eprint((
-                file_name.as_str(),
-                ariadne::Source::from(source_file.text()),
-            ))
-            .unwrap(),
+
+        interner = ctx.interner;
+    }
+}
+
+fn print_diagnostics(diagnostics: &[Diagnostic], file_name: &str, text: &str) {
+    for diagnostic in diagnostics {
+        diagnostic
+            .to_ariadne_report(file_name, text)
+            .eprint((file_name, ariadne::Source::from(text)))
+            .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, sync::Arc};
+
+    use super::{Timings, check};
+
+    #[test]
+    fn check_parses_and_lints_a_pack() {
+        let commands_json = fs::read_to_string("../../commands.json").unwrap();
+        let tree = Arc::new(dpc_common::load_tree(&commands_json).unwrap());
+
+        let pack = std::env::temp_dir().join(format!("dpc-check-{}", std::process::id()));
+        let functions = pack.join("data/foo/function");
+        fs::create_dir_all(&functions).unwrap();
+        fs::write(functions.join("a.mcfunction"), "function foo:b\n").unwrap();
+        fs::write(functions.join("b.mcfunction"), "say hi \nkill @q\n").unwrap();
+
+        let files = check(&tree, &pack, &mut Timings::default());
+        let messages: Vec<Vec<String>> = files
+            .iter()
+            .map(|file| {
+                let diagnostics = file.diagnostics.iter();
+                diagnostics.map(|d| d.message().to_owned()).collect()
+            })
+            .collect();
+        fs::remove_dir_all(&pack).unwrap();
+
+        assert_eq!(
+            messages,
+            [
+                vec!["Function is never run"],
+                vec!["Trailing whitespace", "Invalid selector"],
+            ]
+        );
+
+        // Missing files are reported instead of ending the watch loop
+        let missing = pack.join("missing.mcfunction");
+        assert!(check(&tree, &missing, &mut Timings::default()).is_empty());
+    }
+
+    #[test]
+    fn timings_record_phases_in_order() {
+        let mut timings = Timings::default();
+        assert_eq!(timings.measure("first", || 1), 1);
+        timings.measure("second", || ());
+
+        let phases: Vec<_> = timings.phases.iter().map(|(phase, _)| *phase).collect();
+        assert_eq!(phases, ["first", "second"]);
     }
 }
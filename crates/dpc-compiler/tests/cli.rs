@@ -0,0 +1,114 @@
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Output, Stdio},
+};
+
+/// Runs the compiler in the repository root, where `commands.json` is located.
+fn dpc(args: &[&str], stdin: &str) -> Output {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../..");
+    let mut child = Command::new(env!("CARGO_BIN_EXE_dpc-compiler"))
+        .args(args)
+        .current_dir(root)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success(), "{output:?}");
+    output
+}
+
+fn temp_file(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("dpc-cli-{}-{name}", std::process::id()));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn repl_prints_parsed_arguments() {
+    let output = dpc(&["repl"], "help foo\ntime add 5d\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout.matches("> Command {").count(), 2);
+    assert!(stdout.contains("value: String("), "{stdout}");
+    assert!(stdout.contains("value: Time("), "{stdout}");
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn file_argument_is_the_same_as_check() {
+    let path = temp_file("check.mcfunction", "help foo  \n");
+    let path = path.to_str().unwrap();
+
+    let positional = dpc(&[path, "--format", "github"], "");
+    let check = dpc(&["check", path, "--format", "github"], "");
+    std::fs::remove_file(path).unwrap();
+
+    let stdout = String::from_utf8(positional.stdout).unwrap();
+    assert!(stdout.contains("::warning"), "{stdout}");
+    assert_eq!(stdout, String::from_utf8(check.stdout).unwrap());
+}
+
+#[test]
+fn time_prints_phase_timings() {
+    let path = temp_file("time.mcfunction", "say hi\n");
+    let path = path.to_str().unwrap();
+
+    let timed = dpc(&["check", path, "--format", "github", "--time"], "");
+    let untimed = dpc(&["check", path, "--format", "github"], "");
+    std::fs::remove_file(path).unwrap();
+
+    let stderr = String::from_utf8(timed.stderr).unwrap();
+    for phase in [
+        "loading grammar",
+        "reading source",
+        "parsing",
+        "collecting diagnostics",
+        "linting",
+        "printing diagnostics",
+        "total",
+    ] {
+        assert!(stderr.contains(phase), "{phase} is missing from {stderr}");
+    }
+    assert!(untimed.stderr.is_empty());
+    assert_eq!(timed.stdout, untimed.stdout);
+}
+
+#[test]
+fn dependencies_prints_undefined_resources() {
+    let pack = temp_file("pack", "");
+    std::fs::remove_file(&pack).unwrap();
+    let functions = pack.join("data/foo/function");
+    std::fs::create_dir_all(&functions).unwrap();
+    std::fs::write(
+        functions.join("a.mcfunction"),
+        "loot give @s loot foo:chests/missing\nfunction foo:b\nfunction foo:gone\n",
+    )
+    .unwrap();
+    std::fs::write(functions.join("b.mcfunction"), "say hi\n").unwrap();
+
+    let output = dpc(&["dependencies", pack.to_str().unwrap()], "");
+    std::fs::remove_dir_all(&pack).unwrap();
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(
+        stdout,
+        r#"{
+  "minecraft:function": [
+    "foo:gone"
+  ],
+  "minecraft:loot_table": [
+    "foo:chests/missing"
+  ]
+}
+"#
+    );
+}
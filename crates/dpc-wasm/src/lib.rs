@@ -0,0 +1,103 @@
+use std::sync::Arc;
+
+use dpc_common::{ParsingTree, diagnostics::Diagnostic, lint, source::SourceFile};
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+/// A parsing tree built from a `commands.json` file, which can be reused for many calls.
+#[wasm_bindgen]
+pub struct Grammar {
+    tree: Arc<ParsingTree>,
+}
+
+#[wasm_bindgen]
+impl Grammar {
+    #[wasm_bindgen(constructor)]
+    pub fn new(grammar_json: &str) -> Result<Self, JsValue> {
+        let tree = dpc_common::load_tree(grammar_json)
+            .map_err(|err| JsValue::from_str(&err.to_string()))?;
+        Ok(Self {
+            tree: Arc::new(tree),
+        })
+    }
+
+    pub fn diagnose(&self, source: &str) -> Result<JsValue, JsValue> {
+        // Flattened structs are serialized as maps, which have to become plain objects for
+        // JavaScript
+        let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+        Ok(diagnose(Arc::clone(&self.tree), source).serialize(&serializer)?)
+    }
+}
+
+/// Parses the source using the grammar and returns all diagnostics. The grammar is built anew
+/// for every call, use [`Grammar`] to avoid that.
+#[wasm_bindgen]
+pub fn parse_and_diagnose(grammar_json: &str, source: &str) -> Result<JsValue, JsValue> {
+    Grammar::new(grammar_json)?.diagnose(source)
+}
+
+#[derive(Debug, Serialize)]
+pub struct Position {
+    /// The 1-based line number.
+    pub line: usize,
+    /// The 0-based column, counted in characters.
+    pub column: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LocatedDiagnostic {
+    #[serde(flatten)]
+    pub diagnostic: Diagnostic,
+    pub start: Position,
+    pub end: Position,
+}
+
+/// Parses the source and returns the diagnostics of all parse errors and lints, sorted by their
+/// position.
+pub fn diagnose(tree: Arc<ParsingTree>, source: &str) -> Vec<LocatedDiagnostic> {
+    let parsed = dpc_common::parse_source(tree, None, source.to_owned());
+    let mut diagnostics = parsed.diagnostics();
+    if let Ok(block) = &parsed.result {
+        diagnostics.extend(lint::lint_file(block, &parsed.tree, source));
+        diagnostics.sort_by_key(Diagnostic::span);
+    }
+
+    diagnostics
+        .into_iter()
+        .map(|diagnostic| {
+            let span = diagnostic.span().clamp(source.len());
+            LocatedDiagnostic {
+                start: position(&parsed.source, span.start),
+                end: position(&parsed.source, span.end),
+                diagnostic,
+            }
+        })
+        .collect()
+}
+
+fn position(source: &SourceFile, idx: usize) -> Position {
+    let (line, column) = source
+        .line_col(source.text().floor_char_boundary(idx))
+        .expect("offset is clamped to the source");
+    Position { line, column }
+}
+
+#[cfg(test)]
+mod tests {
+    use dpc_common::diagnostics::Level;
+
+    use super::*;
+
+    #[test]
+    fn lints_are_reported_as_warnings() {
+        let grammar = Grammar::new(include_str!("../../../commands.json")).unwrap();
+        let diagnostics = diagnose(Arc::clone(&grammar.tree), "help foo  \nhelp\n");
+
+        assert_eq!(diagnostics.len(), 1);
+        let located = &diagnostics[0];
+        assert_eq!(located.diagnostic.level(), Level::Warn);
+        assert_eq!(located.diagnostic.message(), "Trailing whitespace");
+        assert_eq!((located.start.line, located.start.column), (1, 8));
+        assert_eq!((located.end.line, located.end.column), (1, 10));
+    }
+}
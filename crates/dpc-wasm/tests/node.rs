@@ -0,0 +1,29 @@
+//! Smoke test of the JavaScript API. It runs in Node.js with `wasm-pack test --node`.
+#![cfg(target_arch = "wasm32")]
+
+use js_sys::{Array, Reflect};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+fn get(object: &JsValue, key: &str) -> JsValue {
+    Reflect::get(object, &JsValue::from_str(key)).unwrap()
+}
+
+#[wasm_bindgen_test]
+fn parse_and_diagnose_returns_located_diagnostics() {
+    let grammar_json = include_str!("../../../commands.json");
+    let diagnostics = dpc_wasm::parse_and_diagnose(grammar_json, "help\nfoo").unwrap();
+    let diagnostics = Array::from(&diagnostics);
+
+    assert_eq!(diagnostics.length(), 1);
+    let diagnostic = diagnostics.get(0);
+    assert_eq!(
+        get(&diagnostic, "level").as_string().as_deref(),
+        Some("error")
+    );
+    assert!(get(&diagnostic, "message").as_string().is_some());
+
+    let start = get(&diagnostic, "start");
+    assert_eq!(get(&start, "line").as_f64(), Some(2.0));
+    assert_eq!(get(&start, "column").as_f64(), Some(0.0));
+}